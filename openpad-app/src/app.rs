@@ -1,7 +1,13 @@
 use crate::async_runtime;
 use crate::components::editor_panel::{EditorPanelAction, EditorPanelWidgetRefExt};
+use crate::components::files_panel::FilesPanelAction;
+use crate::components::model_picker::{ModelPickerAction, ModelPickerWidgetRefExt};
+use crate::components::quick_open::QuickOpenAction;
+use crate::components::search_panel::{SearchPanelAction, SearchPanelWidgetRefExt};
 use crate::components::session_options_popup::SessionOptionsPopupWidgetRefExt;
+use crate::components::symbol_picker::SymbolPickerAction;
 use crate::constants::OPENCODE_SERVER_URL;
+use crate::file_access;
 use crate::state::{
     self, AppAction, AppState, CenterTabKind, OpenFileState, PendingCenterIntent,
     ProjectsPanelAction, SidebarMode,
@@ -27,15 +33,20 @@ const RIGHT_SIDEBAR_DEFAULT_WIDTH: f32 = 260.0;
 const RIGHT_SIDEBAR_MIN_WIDTH: f32 = 200.0;
 const RIGHT_SIDEBAR_MAX_WIDTH: f32 = 420.0;
 
-fn is_probably_binary(bytes: &[u8]) -> bool {
-    bytes.iter().take(8192).any(|b| *b == 0)
-}
-
 app_main!(App);
 mod action_dispatch;
+mod attention;
 pub(crate) mod composer;
+mod change_gutter;
+mod diff_tabs;
 mod dock_controller;
+mod file_sync;
 mod lifecycle;
+mod permission_policy;
+mod permission_tray;
+mod quick_open;
+mod revert_banner;
+mod rules_editor;
 mod sidebar;
 mod ui_sync;
 
@@ -60,12 +71,47 @@ script_mod! {
                 flow: Right
                 summary_title := Label { text: "Session Summary" }
                 View { width: Fill }
+                review_changes_button := Button { width: Fit, height: 20, text: "Review changes" }
             }
 
             summary_stats_label := Label { width: Fill, height: Fit, text: "" }
             summary_diff := Markdown { width: Fill, height: Fit }
         }
 
+        revert_banner := View {
+            visible: false
+            width: Fill, height: Fit
+            flow: Down, spacing: 4
+            padding: Inset{left: 10 right: 10 top: 6 bottom: 6}
+            show_bg: true
+            draw_bg +: { color: #2b2415 }
+
+            View {
+                width: Fill, height: Fit
+                flow: Right, spacing: 8
+                align: Align{ y: 0.5 }
+                revert_banner_label := Label {
+                    width: Fill, height: Fit
+                    text: ""
+                    draw_text +: { color: #f5c97a, text_style: theme.font_regular { font_size: 10 } }
+                }
+                revert_diff_button := Button { width: Fit, height: 20, text: "Show diff" }
+                commit_revert_button := Button { width: Fit, height: 20, text: "Commit to revert" }
+            }
+
+            revert_files_label := Label {
+                width: Fill, height: Fit
+                text: ""
+                draw_text +: { color: #c9b78f, text_style: theme.font_regular { font_size: 9 } }
+            }
+
+            revert_diff_scroll := ScrollYView {
+                visible: false
+                width: Fill, height: 280
+                revert_diff_view := DiffView { width: Fill, height: Fit }
+            }
+        }
+
         View {
             width: Fill, height: Fill
             message_list := MessageList { width: Fill, height: Fill }
@@ -76,12 +122,116 @@ script_mod! {
         width: Fill, height: Fill
         flow: Down
 
+        external_change_bar := View {
+            visible: false
+            width: Fill, height: Fit
+            flow: Right, spacing: 8
+            align: Align{ y: 0.5 }
+            padding: Inset{left: 10 right: 10 top: 6 bottom: 6}
+            show_bg: true
+            draw_bg +: { color: #33270f }
+            external_change_label := Label {
+                width: Fill, height: Fit
+                text: "This file changed on disk while you had unsaved edits."
+                draw_text +: { color: #f5c97a, text_style: theme.font_regular { font_size: 10 } }
+            }
+            external_choice_buttons := View {
+                width: Fit, height: Fit
+                flow: Right, spacing: 8
+                reload_external_button := Button { width: Fit, height: 20, text: "Reload" }
+                keep_mine_button := Button { width: Fit, height: 20, text: "Keep mine" }
+                merge_external_button := Button { width: Fit, height: 20, text: "Merge" }
+            }
+            merge_conflict_buttons := View {
+                visible: false
+                width: Fit, height: Fit
+                flow: Right, spacing: 8
+                use_mine_button := Button { width: Fit, height: 20, text: "Use mine" }
+                use_disk_button := Button { width: Fit, height: 20, text: "Use disk" }
+                cancel_merge_button := Button { width: Fit, height: 20, text: "Cancel merge" }
+            }
+        }
+
+        change_bar := View {
+            visible: false
+            width: Fill, height: Fit
+            flow: Down
+            show_bg: true
+            draw_bg +: { color: #151b23 }
+
+            View {
+                width: Fill, height: Fit
+                flow: Right, spacing: 8
+                align: Align{ y: 0.5 }
+                padding: Inset{left: 10 right: 10 top: 4 bottom: 4}
+                change_summary_label := Label {
+                    width: Fit, height: Fit
+                    text: ""
+                    draw_text +: { color: #8fb8e8, text_style: theme.font_regular { font_size: 10 } }
+                }
+                change_hunk_label := Label {
+                    width: Fill, height: Fit
+                    text: ""
+                    draw_text +: { color: #aab3bd, text_style: theme.font_regular { font_size: 10 } }
+                }
+                prev_change_button := Button { width: Fit, height: 20, text: "‹" }
+                next_change_button := Button { width: Fit, height: 20, text: "›" }
+                show_original_button := Button { width: Fit, height: 20, text: "Show original" }
+                revert_hunk_button := Button { width: Fit, height: 20, text: "Revert hunk" }
+                view_diff_button := Button { width: Fit, height: 20, text: "View diff" }
+            }
+
+            original_lines_wrap := View {
+                visible: false
+                width: Fill, height: Fit
+                padding: Inset{left: 10 right: 10 top: 2 bottom: 6}
+                original_lines_label := Label {
+                    width: Fill, height: Fit
+                    text: ""
+                    draw_text +: { color: #e08a8a, text_style: theme.font_code { font_size: 9 } }
+                }
+            }
+        }
+
         editor_panel := EditorPanel {
             width: Fill
             height: Fill
         }
     }
 
+    let DiffTabView = View {
+        width: Fill, height: Fill
+        flow: Down
+
+        View {
+            width: Fill, height: Fit
+            flow: Right, spacing: 8
+            align: Align{ y: 0.5 }
+            padding: Inset{left: 10 right: 10 top: 6 bottom: 6}
+            show_bg: true
+            draw_bg +: { color: #151b23 }
+            diff_file_label := Label {
+                width: Fill, height: Fit
+                text: ""
+                draw_text +: { color: #e6e9ee, text_style: theme.font_regular { font_size: 10 } }
+            }
+            diff_position_label := Label {
+                width: Fit, height: Fit
+                text: ""
+                draw_text +: { color: #aab3bd, text_style: theme.font_regular { font_size: 10 } }
+            }
+            prev_diff_file_button := Button { width: Fit, height: 20, text: "‹ Previous file" }
+            next_diff_file_button := Button { width: Fit, height: 20, text: "Next file ›" }
+            open_diff_file_button := Button { width: Fit, height: 20, text: "Open file" }
+        }
+
+        ScrollYView {
+            width: Fill, height: Fill
+            padding: Inset{left: 8 right: 8 top: 8 bottom: 8}
+            diff_view := DiffView { width: Fill, height: Fit }
+        }
+    }
+
     let CenterHomeView = View {
         width: Fill, height: Fill
         align: Align{ x: 0.5, y: 0.5 }
@@ -114,6 +264,7 @@ script_mod! {
         CenterHomeTab := CenterHomeView {}
         CenterChatTab := ChatTabView {}
         CenterCodeTab := CodeTabView {}
+        CenterDiffTab := DiffTabView {}
     }
 
     let ChatComposer = View {
@@ -144,14 +295,33 @@ script_mod! {
             skill_desc_label := Label { text: "" }
         }
 
+        symbol_picker := SymbolPicker {}
+
         InputBar {
             width: Fill
             input_box := InputField {}
             input_bar_toolbar := InputBarToolbar {
                 agent_dropdown := InputBarDropDown { labels: ["Agent"] }
                 skill_dropdown := InputBarDropDown { width: 120 labels: ["Skill"] }
-                provider_dropdown := InputBarDropDown { width: 120 labels: ["Provider"] }
-                model_dropdown := InputBarDropDown { width: 150 labels: ["Model"] }
+                model_picker_button := Button {
+                    width: Fit, height: 28
+                    padding: Inset{ left: 12, right: 12 }
+                    text: "Model ▾"
+                    draw_bg +: {
+                        color: #222
+                        color_hover: #2a2a2a
+                        color_active: #2a2a2a
+                        border_color: #333
+                        border_radius: 8.0
+                        border_size: 1.0
+                    }
+                    draw_text +: { color: #99a1b2, text_style: theme.font_regular { font_size: 9 } }
+                }
+                variant_selector := View {
+                    visible: false
+                    width: Fit, height: Fit
+                    variant_dropdown := InputBarDropDown { width: 100 labels: ["Default"] }
+                }
                 View { width: Fill }
                 send_button := SendButton { margin: Inset{ left: 0 } }
             }
@@ -160,6 +330,99 @@ script_mod! {
 
     let TerminalPanelWrap = TerminalPanel {}
 
+    let SessionFilterChip = Button {
+        width: Fit, height: 22
+        padding: Inset{ left: 6, right: 6 }
+        draw_bg +: {
+            color: theme.THEME_COLOR_TRANSPARENT
+            color_hover: theme.THEME_COLOR_HOVER_SUBTLE
+            color_active: theme.THEME_COLOR_HOVER_SUBTLE
+            border_radius: 4.0
+            border_size: 0.0
+        }
+        draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 9 } }
+    }
+
+    let AttentionRow = Button {
+        width: Fill, height: 24
+        padding: Inset{ left: 8, right: 8 }
+        align: Align{ x: 0.0, y: 0.5 }
+        visible: false
+        draw_bg +: {
+            color: theme.THEME_COLOR_TRANSPARENT
+            color_hover: theme.THEME_COLOR_HOVER_SUBTLE
+            color_active: theme.THEME_COLOR_HOVER_SUBTLE
+            border_radius: 4.0
+            border_size: 0.0
+        }
+        draw_text +: { color: theme.THEME_COLOR_TEXT_PRIMARY, text_style: theme.font_regular { font_size: 9 } }
+    }
+
+    let PermissionTrayRow = RoundedView {
+        width: Fill, height: Fit
+        flow: Down, spacing: 4
+        padding: 8
+        visible: false
+        show_bg: true
+        draw_bg +: {
+            color: #171a20
+            border_radius: 4.0
+        }
+
+        permission_session_label := Label {
+            width: Fill
+            text: ""
+            draw_text +: { color: theme.THEME_COLOR_TEXT_PRIMARY, text_style: theme.font_bold { font_size: 9 } }
+        }
+        permission_type_label := Label {
+            width: Fill
+            text: ""
+            draw_text +: { color: #f59e0b, text_style: theme.font_code { font_size: 9 } }
+        }
+        permission_meta_label := Label {
+            width: Fill
+            text: ""
+            draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_code { font_size: 8 } }
+        }
+        View {
+            width: Fill, height: Fit
+            flow: Right, spacing: 6
+            align: Align{ x: 1.0 }
+            permission_reject_button := SessionFilterChip { text: "Reject" }
+            permission_always_button := SessionFilterChip { text: "Always" }
+            permission_once_button := SessionFilterChip { text: "Once" }
+        }
+    }
+
+    let PermissionRuleRow = View {
+        width: Fill, height: 24
+        flow: Right, spacing: 4
+        align: Align{ y: 0.5 }
+        visible: false
+
+        rule_label := Label {
+            width: Fill
+            text: ""
+            draw_text +: { color: theme.THEME_COLOR_TEXT_PRIMARY, text_style: theme.font_code { font_size: 9 } }
+        }
+        rule_action_button := SessionFilterChip { text: "" }
+        rule_up_button := SessionFilterChip { text: "↑" }
+        rule_down_button := SessionFilterChip { text: "↓" }
+        rule_delete_button := SessionFilterChip { text: "✕" }
+    }
+
+    let RulesEditorInput = TextInput {
+        height: 26
+        draw_bg +: {
+            color: #15181d
+            color_focus: #15181d
+            border_radius: 4.0
+            border_size: 1.0
+            border_color: #333
+        }
+        draw_text +: { color: #e6e9ee, text_style: theme.font_code { font_size: 9 } }
+    }
+
     startup() do #(App::script_component(vm)){
         ui: Root{
             main_window := Window{
@@ -181,7 +444,27 @@ script_mod! {
 
                             sidebar_header := SidebarHeader {}
 
+                            files_toolbar := View {
+                                width: Fill, height: Fit
+                                flow: Right, align: Align{ x: 1.0, y: 0.5 }
+                                padding: Inset{ left: 10, right: 8, top: 4 }
+
+                                changed_only_toggle := Button {
+                                    width: Fit, height: 22
+                                    padding: Inset{ left: 8, right: 8 }
+                                    text: "All files"
+                                    draw_bg +: {
+                                        color: theme.THEME_COLOR_TRANSPARENT
+                                        color_hover: theme.THEME_COLOR_HOVER_SUBTLE
+                                        color_active: theme.THEME_COLOR_HOVER_SUBTLE
+                                        border_radius: 4.0
+                                        border_size: 0.0
+                                    }
+                                    draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 9 } }
+                                }
+                            }
                             files_panel := FilesPanel { visible: true }
+                            search_panel := SearchPanel { visible: false }
                             settings_panel := SettingsDialog { visible: false width: Fill height: Fill }
                         }
 
@@ -212,6 +495,11 @@ script_mod! {
                                             visible: false
                                             Label { text: "Working..." }
                                         }
+                                        rules_button := SessionFilterChip { text: "Rules" }
+                                        policy_button := SessionFilterChip { text: "Policy" }
+                                        permissions_button := SessionFilterChip { visible: false, text: "" }
+                                        attention_button := SessionFilterChip { visible: false, text: "" }
+                                        attention_next_button := SessionFilterChip { visible: false, text: "Next ›" }
                                         status_dot := StatusDot {}
                                         status_label := Label { text: "Connected" }
                                     }
@@ -232,6 +520,21 @@ script_mod! {
                                         project_badge := View { project_badge_label := Label { text: "No project" } }
                                     }
                                     Label { text: "/" }
+                                    session_breadcrumb := View {
+                                        visible: false
+                                        width: Fit, height: Fit
+                                        flow: Right, spacing: 4
+                                        align: Align{ y: 0.5 }
+                                        breadcrumb_root_wrap := View {
+                                            width: Fit, height: Fit
+                                            flow: Right, spacing: 4
+                                            align: Align{ y: 0.5 }
+                                            breadcrumb_root_button := Button { width: Fit, height: 20, text: "" }
+                                            Label { text: "›" }
+                                        }
+                                        breadcrumb_parent_button := Button { width: Fit, height: 20, text: "" }
+                                        Label { text: "›" }
+                                    }
                                     session_row := View { session_title := Label { text: "New Session" } }
                                     session_options_btn := Button {
                                         width: 28, height: 24
@@ -312,6 +615,200 @@ script_mod! {
                                 View { width: Fill, height: Fill }
                                 terminal_panel_wrap := TerminalPanelWrap {}
                             }
+
+                            attention_inbox := View {
+                                width: Fill, height: Fill
+                                visible: false
+                                flow: Down
+                                align: Align{ x: 1.0 }
+                                padding: Inset{ top: 38, right: 8 }
+
+                                RoundedView {
+                                    width: 320, height: Fit
+                                    flow: Down, spacing: 2
+                                    padding: 8
+                                    show_bg: true
+                                    draw_bg +: {
+                                        color: #1f232b
+                                        border_radius: 6.0
+                                        border_size: 1.0
+                                        border_color: #2f3642
+                                    }
+
+                                    attention_inbox_title := Label {
+                                        text: "Needs attention"
+                                        draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_bold { font_size: 9 } }
+                                    }
+                                    attention_row_0 := AttentionRow {}
+                                    attention_row_1 := AttentionRow {}
+                                    attention_row_2 := AttentionRow {}
+                                    attention_row_3 := AttentionRow {}
+                                    attention_row_4 := AttentionRow {}
+                                    attention_row_5 := AttentionRow {}
+                                    attention_row_6 := AttentionRow {}
+                                    attention_row_7 := AttentionRow {}
+                                    attention_more_label := Label {
+                                        visible: false
+                                        text: ""
+                                        draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 9 } }
+                                    }
+                                }
+                            }
+
+                            permissions_tray := View {
+                                width: Fill, height: Fill
+                                visible: false
+                                flow: Down
+                                align: Align{ x: 1.0 }
+                                padding: Inset{ top: 38, right: 8 }
+
+                                RoundedView {
+                                    width: 380, height: Fit
+                                    flow: Down, spacing: 6
+                                    padding: 8
+                                    show_bg: true
+                                    draw_bg +: {
+                                        color: #1f232b
+                                        border_radius: 6.0
+                                        border_size: 1.0
+                                        border_color: #2f3642
+                                    }
+
+                                    Label {
+                                        text: "Pending permissions  ·  ⌘⇧Y once, ⌘⇧A always, ⌘⇧N reject"
+                                        draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_bold { font_size: 9 } }
+                                    }
+                                    permission_row_0 := PermissionTrayRow {}
+                                    permission_row_1 := PermissionTrayRow {}
+                                    permission_row_2 := PermissionTrayRow {}
+                                    permission_row_3 := PermissionTrayRow {}
+                                    permission_row_4 := PermissionTrayRow {}
+                                    permissions_more_label := Label {
+                                        visible: false
+                                        text: ""
+                                        draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 9 } }
+                                    }
+                                }
+                            }
+
+                            policy_panel := View {
+                                width: Fill, height: Fill
+                                visible: false
+                                flow: Down
+                                align: Align{ x: 1.0 }
+                                padding: Inset{ top: 38, right: 8 }
+
+                                RoundedView {
+                                    width: 440, height: Fit
+                                    flow: Down, spacing: 6
+                                    padding: 10
+                                    show_bg: true
+                                    draw_bg +: {
+                                        color: #1f232b
+                                        border_radius: 6.0
+                                        border_size: 1.0
+                                        border_color: #2f3642
+                                    }
+
+                                    View {
+                                        width: Fill, height: Fit
+                                        flow: Right, spacing: 6
+                                        align: Align{ y: 0.5 }
+                                        Label {
+                                            text: "Auto-approval policy"
+                                            draw_text +: { color: theme.THEME_COLOR_TEXT_PRIMARY, text_style: theme.font_bold { font_size: 10 } }
+                                        }
+                                        View { width: Fill }
+                                        policy_dry_run_toggle := SessionFilterChip { text: "Dry run" }
+                                        policy_reload_button := SessionFilterChip { text: "Reload" }
+                                    }
+                                    policy_status_label := Label {
+                                        width: Fill
+                                        text: ""
+                                        draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 9 } }
+                                    }
+                                    policy_audit_label := Label {
+                                        width: Fill
+                                        text: ""
+                                        draw_text +: { color: theme.THEME_COLOR_TEXT_PRIMARY, text_style: theme.font_code { font_size: 9 } }
+                                    }
+                                }
+                            }
+
+                            rules_editor := View {
+                                width: Fill, height: Fill
+                                visible: false
+                                flow: Down
+                                align: Align{ x: 1.0 }
+                                padding: Inset{ top: 38, right: 8 }
+
+                                RoundedView {
+                                    width: 460, height: Fit
+                                    flow: Down, spacing: 4
+                                    padding: 10
+                                    show_bg: true
+                                    draw_bg +: {
+                                        color: #1f232b
+                                        border_radius: 6.0
+                                        border_size: 1.0
+                                        border_color: #2f3642
+                                    }
+
+                                    View {
+                                        width: Fill, height: Fit
+                                        flow: Right, spacing: 6
+                                        align: Align{ y: 0.5 }
+                                        Label {
+                                            text: "Permission rules"
+                                            draw_text +: { color: theme.THEME_COLOR_TEXT_PRIMARY, text_style: theme.font_bold { font_size: 10 } }
+                                        }
+                                        View { width: Fill }
+                                        rules_target_button := SessionFilterChip { text: "New sessions" }
+                                        rules_reset_button := SessionFilterChip { text: "Reset" }
+                                        rules_apply_button := SessionFilterChip { text: "Apply" }
+                                    }
+                                    Label {
+                                        width: Fill
+                                        text: "Evaluated top to bottom; the last matching rule wins."
+                                        draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 9 } }
+                                    }
+                                    rule_row_0 := PermissionRuleRow {}
+                                    rule_row_1 := PermissionRuleRow {}
+                                    rule_row_2 := PermissionRuleRow {}
+                                    rule_row_3 := PermissionRuleRow {}
+                                    rule_row_4 := PermissionRuleRow {}
+                                    rule_row_5 := PermissionRuleRow {}
+                                    rule_row_6 := PermissionRuleRow {}
+                                    rule_row_7 := PermissionRuleRow {}
+                                    rule_row_8 := PermissionRuleRow {}
+                                    rule_row_9 := PermissionRuleRow {}
+                                    View {
+                                        width: Fill, height: Fit
+                                        flow: Right, spacing: 6
+                                        align: Align{ y: 0.5 }
+                                        rules_page_label := Label {
+                                            width: Fill
+                                            text: ""
+                                            draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 9 } }
+                                        }
+                                        rules_prev_button := SessionFilterChip { text: "‹ Prev" }
+                                        rules_next_button := SessionFilterChip { text: "Next ›" }
+                                    }
+                                    View {
+                                        width: Fill, height: Fit
+                                        flow: Right, spacing: 6
+                                        align: Align{ y: 0.5 }
+                                        rules_permission_input := RulesEditorInput { width: 110, empty_text: "permission" }
+                                        rules_pattern_input := RulesEditorInput { width: Fill, empty_text: "pattern (default *)" }
+                                        rules_add_button := SessionFilterChip { text: "Add" }
+                                    }
+                                    rules_status_label := Label {
+                                        width: Fill
+                                        text: ""
+                                        draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 9 } }
+                                    }
+                                }
+                            }
                         }
 
                         right_sidebar_resize_handle := View { width: 6, height: Fill }
@@ -320,6 +817,51 @@ script_mod! {
                             width: 260.0, height: Fill
                             open_size: 260.0
                             flow: Down
+
+                            sessions_toolbar := View {
+                                width: Fill, height: Fit
+                                flow: Down
+                                padding: Inset{ left: 10, right: 8, top: 8 }
+                                spacing: 4
+
+                                session_filter_input := TextInput {
+                                    width: Fill, height: 28
+                                    empty_text: "Filter sessions"
+                                    draw_bg +: {
+                                        color: #15181d
+                                        color_focus: #15181d
+                                        border_radius: 6.0
+                                        border_size: 1.0
+                                        border_color: #333
+                                    }
+                                    draw_text +: { color: #e6e9ee, text_style: theme.font_regular { font_size: 10 } }
+                                }
+
+                                View {
+                                    width: Fill, height: Fit
+                                    flow: Right
+                                    spacing: 2
+                                    filter_working_toggle := SessionFilterChip { text: "Working" }
+                                    filter_changes_toggle := SessionFilterChip { text: "Changes" }
+                                    filter_shared_toggle := SessionFilterChip { text: "Shared" }
+                                    filter_permission_toggle := SessionFilterChip { text: "Permission" }
+                                }
+
+                                View {
+                                    width: Fill, height: Fit
+                                    flow: Right, align: Align{ y: 0.5 }
+                                    spacing: 2
+
+                                    session_filter_summary := Label {
+                                        width: Fill, height: Fit
+                                        text: ""
+                                        draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 9 } }
+                                    }
+                                    session_sort_button := SessionFilterChip { text: "Last updated" }
+                                    session_group_button := SessionFilterChip { text: "By project" }
+                                }
+                            }
+
                             sessions_panel := SessionsPanel { width: Fill, height: Fill }
                         }
                     }
@@ -327,6 +869,10 @@ script_mod! {
                     simple_dialog := SimpleDialog {}
 
                     session_options_popup := SessionOptionsPopup { visible: false }
+
+                    quick_open := QuickOpen {}
+
+                    model_picker := ModelPicker {}
                 }
             }
         }
@@ -354,6 +900,8 @@ pub struct App {
     #[rust]
     sidebar_mode: SidebarMode,
     #[rust]
+    files_changed_only: bool,
+    #[rust]
     terminal_open: bool,
     #[rust]
     client: Option<Arc<OpenCodeClient>>,
@@ -363,6 +911,8 @@ pub struct App {
     connected_once: bool,
     #[rust]
     providers_loaded_once: bool,
+    #[rust]
+    permission_tray_selected: usize,
 }
 
 impl App {
@@ -375,6 +925,10 @@ impl App {
         crate::components::sessions_panel::script_mod(vm);
         crate::components::sidebar_header::script_mod(vm);
         crate::components::session_options_popup::script_mod(vm);
+        crate::components::symbol_picker::script_mod(vm);
+        crate::components::search_panel::script_mod(vm);
+        crate::components::quick_open::script_mod(vm);
+        crate::components::model_picker::script_mod(vm);
         App::from_script_mod(vm, self::script_mod)
     }
 
@@ -405,7 +959,7 @@ impl App {
                 })
         });
 
-        let permission = self.state.selected_agent_permission();
+        let permission = self.state.new_session_permission();
         async_runtime::spawn_session_creator(runtime, client, project_directory, permission);
     }
 
@@ -542,16 +1096,55 @@ impl App {
         async_runtime::spawn_session_brancher(runtime, client, parent_session_id, directory);
     }
 
-    fn revert_to_message(&mut self, cx: &mut Cx, session_id: String, message_id: String) {
-        // Show confirmation dialog
-        self.ui
-            .simple_dialog(cx, &[id!(simple_dialog)])
-            .show_confirm(
-                cx,
-                "Revert Session",
+    fn set_session_archived(&mut self, _cx: &mut Cx, session_id: String, archived: bool) {
+        let Some(client) = self.client_or_error() else {
+            return;
+        };
+        let Some(runtime) = self._runtime.as_ref() else {
+            return;
+        };
+
+        let directory = self.get_session_directory(&session_id);
+        async_runtime::spawn_session_archiver(runtime, client, session_id, archived, directory);
+    }
+
+    /// The session a message belongs to, preferring the current one.
+    fn session_for_message(&self, message_id: &str) -> Option<String> {
+        self.state.current_session_id.clone().or_else(|| {
+            self.state
+                .messages_by_session
+                .iter()
+                .find_map(|(sid, messages)| {
+                    messages
+                        .iter()
+                        .any(|m| m.info.id() == message_id)
+                        .then(|| sid.clone())
+                })
+        })
+    }
+
+    /// Asks for confirmation before reverting. With a `part_id` only that
+    /// step of the message and what follows it are undone.
+    fn revert_to_message(
+        &mut self,
+        cx: &mut Cx,
+        session_id: String,
+        message_id: String,
+        part_id: Option<String>,
+    ) {
+        let (message, dialog_type) = match part_id {
+            Some(part_id) => (
+                "Revert to this step? The files it changed and every later step and message will be undone.",
+                format!("revert_session:{}:{}:{}", session_id, message_id, part_id),
+            ),
+            None => (
                 "Are you sure you want to revert to this message? All subsequent messages and actions will be lost.",
                 format!("revert_session:{}:{}", session_id, message_id),
-            );
+            ),
+        };
+        self.ui
+            .simple_dialog(cx, &[id!(simple_dialog)])
+            .show_confirm(cx, "Revert Session", message, dialog_type);
     }
 
     fn unrevert_session(&mut self, _cx: &mut Cx, session_id: String) {
@@ -580,6 +1173,7 @@ impl AppMain for App {
 
         match event {
             Event::Startup => {
+                self.reload_permission_policy();
                 self.connect_to_opencode(cx);
                 if !cx.in_makepad_studio() {
                     #[cfg(not(target_os = "macos"))]
@@ -624,6 +1218,11 @@ impl AppMain for App {
             }
             Event::KeyDown(ke) => {
                 if (ke.modifiers.logo || ke.modifiers.control)
+                    && ke.modifiers.shift
+                    && !ke.modifiers.alt
+                {
+                    self.handle_permission_tray_key(cx, ke);
+                } else if (ke.modifiers.logo || ke.modifiers.control)
                     && !ke.modifiers.shift
                     && !ke.modifiers.alt
                 {
@@ -637,6 +1236,12 @@ impl AppMain for App {
                         KeyCode::KeyI => {
                             self.toggle_right_sidebar(cx);
                         }
+                        KeyCode::KeyP => {
+                            self.open_quick_open(cx);
+                        }
+                        KeyCode::KeyJ => {
+                            self.jump_to_next_attention(cx);
+                        }
                         KeyCode::KeyS => {
                             if let Some(tab_id) = self.current_active_file_tab_id() {
                                 self.save_file_tab(cx, tab_id);
@@ -666,6 +1271,19 @@ impl AppMain for App {
                 .ui
                 .button(cx, &[id!(session_options_btn)])
                 .clicked(&actions);
+        if self
+            .ui
+            .button(cx, &[id!(side_panel), id!(changed_only_toggle)])
+            .clicked(&actions)
+        {
+            self.toggle_changed_files_only(cx);
+        }
+        self.handle_session_filter_bar(cx, &actions);
+        self.handle_attention_buttons(cx, &actions);
+        self.handle_permission_tray_buttons(cx, &actions);
+        self.handle_policy_buttons(cx, &actions);
+        self.handle_rules_editor_buttons(cx, &actions);
+
         if opts_clicked {
             log!("Session options button clicked");
             if let Some(session_id) = &self.state.current_session_id {
@@ -675,12 +1293,16 @@ impl AppMain for App {
                     .get(session_id)
                     .copied()
                     .unwrap_or(false);
+                let archived = self
+                    .state
+                    .find_session(session_id)
+                    .is_some_and(|session| session.time.is_archived());
                 self.ui
                     .widget(cx, &[id!(session_options_popup)])
                     .set_visible(cx, true);
                 self.ui
                     .session_options_popup(cx, &[id!(session_options_popup)])
-                    .show(cx, session_id.clone(), working);
+                    .show(cx, session_id.clone(), working, archived);
             }
         }
 
@@ -717,11 +1339,18 @@ impl AppMain for App {
                     ProjectsPanelAction::BranchSession(session_id) => {
                         self.branch_session(cx, session_id.clone());
                     }
+                    ProjectsPanelAction::ArchiveSession(session_id) => {
+                        self.set_session_archived(cx, session_id.clone(), true);
+                    }
+                    ProjectsPanelAction::UnarchiveSession(session_id) => {
+                        self.set_session_archived(cx, session_id.clone(), false);
+                    }
                     ProjectsPanelAction::OpenSessionContextMenu {
                         session_id,
                         x: _,
                         y: _,
                         working,
+                        archived,
                     } => {
                         log!(
                             "ProjectsPanelAction::OpenSessionContextMenu session_id={} working={}",
@@ -733,7 +1362,7 @@ impl AppMain for App {
                         popup_ref.redraw(cx);
                         self.ui
                             .session_options_popup(cx, &[id!(session_options_popup)])
-                            .show(cx, session_id.clone(), *working);
+                            .show(cx, session_id.clone(), *working, *archived);
                         popup_ref.redraw(cx);
                     }
                     ProjectsPanelAction::OpenProjectContextMenu { project_id } => {
@@ -808,6 +1437,11 @@ impl AppMain for App {
                             self.update_editor_header_ui_for_tab(cx, tab_id);
                         }
                     }
+                    EditorPanelAction::ChangeMarkerClicked(index) => {
+                        if let Some(tab_id) = self.current_active_file_tab_id() {
+                            self.jump_to_change_hunk(cx, tab_id, *index);
+                        }
+                    }
                     EditorPanelAction::None => {}
                 }
             }
@@ -816,19 +1450,21 @@ impl AppMain for App {
             if let Some(msg_action) = action.downcast_ref::<WidgetMessageListAction>() {
                 match msg_action {
                     WidgetMessageListAction::RevertToMessage(message_id) => {
-                        let mut target_session = self.state.current_session_id.clone();
-                        if target_session.is_none() {
-                            target_session = self.state.messages_by_session.iter().find_map(
-                                |(sid, messages)| {
-                                    messages
-                                        .iter()
-                                        .any(|m| m.info.id() == *message_id)
-                                        .then(|| sid.clone())
-                                },
-                            );
+                        if let Some(session_id) = self.session_for_message(message_id) {
+                            self.revert_to_message(cx, session_id, message_id.clone(), None);
                         }
-                        if let Some(session_id) = target_session {
-                            self.revert_to_message(cx, session_id, message_id.clone());
+                    }
+                    WidgetMessageListAction::RevertToStep {
+                        message_id,
+                        part_id,
+                    } => {
+                        if let Some(session_id) = self.session_for_message(message_id) {
+                            self.revert_to_message(
+                                cx,
+                                session_id,
+                                message_id.clone(),
+                                Some(part_id.clone()),
+                            );
                         }
                     }
                     _ => {}
@@ -841,6 +1477,11 @@ impl AppMain for App {
                     AppAction::SetSidebarMode(mode) => {
                         self.sidebar_mode = *mode;
                         self.update_sidebar_panel_visibility(cx);
+                        if *mode == SidebarMode::Search {
+                            self.ui
+                                .search_panel(cx, &[id!(side_panel), id!(search_panel)])
+                                .focus_query(cx);
+                        }
                     }
                     AppAction::DialogConfirmed { dialog_type, value } => {
                         self.handle_dialog_confirmed(cx, dialog_type.clone(), value.clone());
@@ -987,6 +1628,71 @@ impl AppMain for App {
                 }
             }
 
+            if let Some(quick_open_action) = action.downcast_ref::<QuickOpenAction>() {
+                match quick_open_action {
+                    QuickOpenAction::QueryChanged(query) => {
+                        self.update_quick_open_query(cx, query.clone());
+                    }
+                    QuickOpenAction::Open(relative_path) => {
+                        self.open_quick_open_result(cx, relative_path);
+                    }
+                    QuickOpenAction::Closed => {
+                        self.state.quick_open = Default::default();
+                    }
+                    QuickOpenAction::PreviewRequested { directory, file } => {
+                        self.load_quick_open_preview(directory.clone(), file.clone());
+                    }
+                    QuickOpenAction::None => {}
+                }
+            }
+
+            if let Some(ModelPickerAction::Selected {
+                provider_id,
+                model_id,
+            }) = action.downcast_ref::<ModelPickerAction>()
+            {
+                if state::reducer::select_model(&mut self.state, provider_id, model_id) {
+                    state::reducer::remember_composer_selection(&mut self.state);
+                    self.state.update_model_ui(&self.ui, cx);
+                    self.ui.redraw(cx);
+                }
+            }
+
+            if let Some(search_action) = action.downcast_ref::<SearchPanelAction>() {
+                match search_action {
+                    SearchPanelAction::Search(query) => {
+                        self.start_text_search(cx, query.clone());
+                    }
+                    SearchPanelAction::OpenMatch {
+                        absolute_path,
+                        line,
+                    } => {
+                        let project_id = self.state.text_search.project_id.clone();
+                        self.queue_or_open_file_at_line(
+                            cx,
+                            project_id,
+                            absolute_path.clone(),
+                            Some(*line),
+                        );
+                    }
+                    SearchPanelAction::None => {}
+                }
+            }
+
+            if let Some(FilesPanelAction::ListDirectory {
+                project_id,
+                directory,
+            }) = action.downcast_ref::<FilesPanelAction>()
+            {
+                self.list_remote_directory(project_id.clone(), directory.clone());
+            }
+
+            if let Some(SymbolPickerAction::Selected(symbol)) =
+                action.downcast_ref::<SymbolPickerAction>()
+            {
+                self.apply_symbol_selection(cx, symbol.clone());
+            }
+
             // Handle SettingsDialogAction
             if let Some(action) = action.downcast_ref::<SettingsDialogAction>() {
                 match action {
@@ -1042,6 +1748,8 @@ impl AppMain for App {
                     mime_type: "text/plain".to_string(),
                     data_url: String::new(),
                     raw_text: Some(remaining.clone()),
+                    symbol: None,
+                    symbol_picked_at: 0,
                 });
 
                 self.ui.text_input(cx, &[id!(input_box)]).set_text(cx, "");
@@ -1052,6 +1760,8 @@ impl AppMain for App {
                     .text_input(cx, &[id!(input_box)])
                     .set_text(cx, &remaining);
             }
+            let current = self.ui.text_input(cx, &[id!(input_box)]).text();
+            self.update_symbol_completion(cx, &current);
         }
 
         // Check for text input return
@@ -1065,6 +1775,32 @@ impl AppMain for App {
             }
         }
 
+        self.handle_external_change_buttons(cx, &actions);
+        self.handle_change_bar_buttons(cx, &actions);
+        self.handle_diff_tab_buttons(cx, &actions);
+        self.handle_revert_banner_buttons(cx, &actions);
+
+        // Breadcrumb back to the parents of a child session
+        for (button, root) in [
+            (id!(breadcrumb_root_button), true),
+            (id!(breadcrumb_parent_button), false),
+        ] {
+            if self.ui.button(cx, &[button]).clicked(&actions) {
+                let target = self.state.current_session_id.as_deref().and_then(|sid| {
+                    let ancestors = state::reducer::session_ancestors(&self.state.sessions, sid);
+                    let target = if root {
+                        ancestors.first()
+                    } else {
+                        ancestors.last()
+                    };
+                    target.map(|session| session.id.clone())
+                });
+                if let Some(session_id) = target {
+                    self.queue_or_select_session(cx, session_id);
+                }
+            }
+        }
+
         // Handle unrevert button
         if self
             .ui
@@ -1158,39 +1894,39 @@ impl AppMain for App {
                 .up_drop_down(cx, &[id!(input_bar_toolbar), id!(skill_dropdown)])
                 .set_selected_item(cx, 0);
             self.update_skill_ui(cx);
+            state::reducer::remember_composer_selection(&mut self.state);
         }
 
-        // Handle dropdown selections (main input bar only)
-        // Provider selection changed - update model list
-        if let Some(idx) = self
+        if self
             .ui
-            .up_drop_down(cx, &[id!(input_bar_toolbar), id!(provider_dropdown)])
-            .changed(&actions)
+            .button(cx, &[id!(input_bar_toolbar), id!(model_picker_button)])
+            .clicked(&actions)
         {
-            self.state.selected_provider_idx = idx;
-            self.state.update_model_list_for_provider();
-            self.ui
-                .up_drop_down(cx, &[id!(input_bar_toolbar), id!(model_dropdown)])
-                .set_labels(cx, self.state.model_labels.clone());
+            let current = self.state.composer_selection().model;
             self.ui
-                .up_drop_down(cx, &[id!(input_bar_toolbar), id!(model_dropdown)])
-                .set_selected_item(cx, 0);
+                .model_picker(cx, &[id!(model_picker)])
+                .show(cx, current);
         }
 
-        // Model selection changed
         if let Some(idx) = self
             .ui
-            .up_drop_down(cx, &[id!(input_bar_toolbar), id!(model_dropdown)])
+            .up_drop_down(cx, &[id!(input_bar_toolbar), id!(variant_dropdown)])
             .changed(&actions)
         {
-            self.state.selected_model_idx = idx;
+            let variant = idx
+                .checked_sub(1)
+                .and_then(|idx| self.state.selected_model_variants().get(idx).cloned());
+            state::reducer::select_variant(&mut self.state, variant);
+            state::reducer::remember_composer_selection(&mut self.state);
         }
+
         if let Some(idx) = self
             .ui
             .up_drop_down(cx, &[id!(input_bar_toolbar), id!(agent_dropdown)])
             .changed(&actions)
         {
             self.state.selected_agent_idx = if idx > 0 { Some(idx - 1) } else { None };
+            state::reducer::remember_composer_selection(&mut self.state);
         }
         if let Some(idx) = self
             .ui
@@ -1199,6 +1935,7 @@ impl AppMain for App {
         {
             self.state.selected_skill_idx = if idx > 0 { Some(idx - 1) } else { None };
             self.update_skill_ui(cx);
+            state::reducer::remember_composer_selection(&mut self.state);
         }
 
         if needs_center_refresh {
@@ -1210,7 +1947,7 @@ impl AppMain for App {
 
 #[cfg(test)]
 mod tests {
-    use super::composer::get_image_data_url_regex;
+    use super::composer::{get_image_data_url_regex, symbol_source_text, trailing_symbol_query};
 
     #[test]
     fn test_data_url_detection() {
@@ -1248,4 +1985,37 @@ mod tests {
         let result = data_url_pattern.replace_all(text, "");
         assert_eq!(result, "Start  Middle  End");
     }
+
+    #[test]
+    fn test_trailing_symbol_query() {
+        assert_eq!(trailing_symbol_query("look at #parse_ev"), Some("parse_ev"));
+        assert_eq!(trailing_symbol_query("#"), Some(""));
+        assert_eq!(trailing_symbol_query("#Client::new"), Some("Client::new"));
+        assert_eq!(trailing_symbol_query("#done "), None);
+        assert_eq!(trailing_symbol_query("issue #12-a"), None);
+        assert_eq!(trailing_symbol_query("no trigger"), None);
+    }
+
+    #[test]
+    fn test_symbol_source_text() {
+        let source = symbol_source_text("explain #parse_sse_event please", "parse_sse_event", 8);
+        assert_eq!(source.value, "#parse_sse_event");
+        assert_eq!(source.start, 8);
+        assert_eq!(source.end, 24);
+
+        // Offsets count characters, not bytes.
+        let source = symbol_source_text("übersetze #parse", "parse", 10);
+        assert_eq!((source.start, source.end), (10, 16));
+
+        // A repeated mention resolves to the token the picker inserted, and a
+        // longer name sharing the prefix is not a match.
+        let text = "#parse_sse_event calls #parse, unlike #parse";
+        let source = symbol_source_text(text, "parse", 38);
+        assert_eq!((source.start, source.end), (38, 44));
+        let source = symbol_source_text(text, "parse", 20);
+        assert_eq!((source.start, source.end), (23, 29));
+
+        let missing = symbol_source_text("edited away", "parse_sse_event", 0);
+        assert_eq!((missing.start, missing.end), (0, 0));
+    }
 }
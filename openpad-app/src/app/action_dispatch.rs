@@ -22,6 +22,11 @@ impl App {
                         }
                     }
                     AppAction::OpenCodeEvent(oc_event) => {
+                        if let openpad_protocol::Event::PermissionAsked(request) = oc_event {
+                            if self.auto_reply_permission(cx, request) {
+                                continue;
+                            }
+                        }
                         let deleted_tab_id = match oc_event {
                             openpad_protocol::Event::SessionDeleted(session) => {
                                 self.state.tab_by_session.get(&session.id).copied()
@@ -29,10 +34,57 @@ impl App {
                             _ => None,
                         };
                         state::handle_opencode_event(&mut self.state, &self.ui, cx, oc_event);
+                        match oc_event {
+                            openpad_protocol::Event::FileEdited { file }
+                            | openpad_protocol::Event::FileWatcherUpdated { file, .. } => {
+                                self.refresh_file_status_for_path(file);
+                                self.reload_open_file_tabs(cx, file);
+                            }
+                            _ => {}
+                        }
                         if let Some(tab_id) = deleted_tab_id {
                             self.close_tab_now(cx, tab_id);
                         }
                     }
+                    AppAction::PendingPermissionsLoaded(permissions) => {
+                        // Requests already listed were run through the policy when
+                        // they first arrived.
+                        let known: Vec<String> = self
+                            .state
+                            .pending_permissions
+                            .iter()
+                            .map(|request| request.id.clone())
+                            .collect();
+                        let unanswered = permissions
+                            .iter()
+                            .filter(|request| {
+                                known.contains(&request.id)
+                                    || !self.auto_reply_permission(cx, request)
+                            })
+                            .cloned()
+                            .collect();
+                        state::handle_app_action(
+                            &mut self.state,
+                            &self.ui,
+                            cx,
+                            &AppAction::PendingPermissionsLoaded(unanswered),
+                        );
+                    }
+                    AppAction::PermissionSessionResolved {
+                        session_id,
+                        session,
+                    } => {
+                        if let Some(session) = session {
+                            // Upsert only; `SessionLoaded` could also select it.
+                            state::handle_app_action(
+                                &mut self.state,
+                                &self.ui,
+                                cx,
+                                &AppAction::SessionChildrenLoaded(vec![session.clone()]),
+                            );
+                        }
+                        self.release_held_permissions(cx, session_id);
+                    }
                     AppAction::PermissionResponded {
                         session_id,
                         request_id,
@@ -50,7 +102,7 @@ impl App {
                         session_id,
                         message_id,
                     } => {
-                        self.revert_to_message(cx, session_id.clone(), message_id.clone());
+                        self.revert_to_message(cx, session_id.clone(), message_id.clone(), None);
                     }
                     AppAction::UnrevertSession(session_id) => {
                         self.unrevert_session(cx, session_id.clone());
@@ -61,6 +113,11 @@ impl App {
                     } => {
                         self.load_session_diff(cx, session_id.clone(), message_id.clone());
                     }
+                    AppAction::SessionDiffLoaded { .. } => {
+                        state::handle_app_action(&mut self.state, &self.ui, cx, app_action);
+                        self.refresh_all_change_gutters(cx);
+                        self.refresh_diff_tabs(cx);
+                    }
                     AppAction::DialogConfirmed { dialog_type, value } => {
                         self.handle_dialog_confirmed(cx, dialog_type.clone(), value.clone());
                     }
@@ -71,6 +128,30 @@ impl App {
                     AppAction::ProjectsLoaded(projects) => {
                         state::handle_app_action(&mut self.state, &self.ui, cx, app_action);
                         self.load_all_sessions(projects.clone());
+                        for project in projects {
+                            self.load_file_status(&project.id);
+                        }
+                    }
+                    AppAction::FileStatusLoaded { project_id, .. } => {
+                        state::handle_app_action(&mut self.state, &self.ui, cx, app_action);
+                        self.state.file_status_in_flight.remove(project_id);
+                        if self.state.file_status_stale.remove(project_id) {
+                            self.load_file_status(project_id);
+                        }
+                    }
+                    AppAction::RemoteFileLoaded {
+                        project_id,
+                        absolute_path,
+                        line,
+                        result,
+                    } => {
+                        self.finish_remote_file_open(
+                            cx,
+                            project_id.clone(),
+                            absolute_path.clone(),
+                            *line,
+                            result.clone(),
+                        );
                     }
                     _ => {
                         state::handle_app_action(&mut self.state, &self.ui, cx, app_action);
@@ -133,14 +214,23 @@ impl App {
                     );
                 }
             }
+            "commit_revert" => {
+                let directory = self.get_session_directory(data);
+                async_runtime::spawn_revert_committer(runtime, client, data.to_string(), directory);
+            }
             "revert_session" => {
-                if let Some((session_id, message_id)) = data.split_once(':') {
+                if let Some((session_id, target)) = data.split_once(':') {
+                    let (message_id, part_id) = match target.split_once(':') {
+                        Some((message_id, part_id)) => (message_id, Some(part_id.to_string())),
+                        None => (target, None),
+                    };
                     let directory = self.get_session_directory(session_id);
                     async_runtime::spawn_message_reverter(
                         runtime,
                         client,
                         session_id.to_string(),
                         message_id.to_string(),
+                        part_id,
                         directory,
                     );
                 }
@@ -0,0 +1,125 @@
+use super::*;
+use crate::state::{reducer, AttentionKind};
+use std::collections::HashMap;
+
+const ATTENTION_ROWS: [LiveId; 8] = [
+    live_id!(attention_row_0),
+    live_id!(attention_row_1),
+    live_id!(attention_row_2),
+    live_id!(attention_row_3),
+    live_id!(attention_row_4),
+    live_id!(attention_row_5),
+    live_id!(attention_row_6),
+    live_id!(attention_row_7),
+];
+
+impl App {
+    fn session_label(&self, session_id: &str) -> String {
+        self.state
+            .find_session(session_id)
+            .map(async_runtime::get_session_title)
+            .unwrap_or_else(|| "Session".to_string())
+    }
+
+    /// Refresh the header badge, the inbox rows and the chat tab titles.
+    pub(super) fn refresh_attention_ui(&self, cx: &mut Cx) {
+        let inbox = reducer::attention_inbox(&self.state);
+
+        let header = self.ui.view(cx, &[id!(main_header), id!(status_row)]);
+        header
+            .button(cx, &[id!(attention_button)])
+            .set_visible(cx, !inbox.is_empty());
+        header
+            .button(cx, &[id!(attention_button)])
+            .set_text(cx, &format!("● {} need attention", inbox.len()));
+        header
+            .button(cx, &[id!(attention_next_button)])
+            .set_visible(cx, !inbox.is_empty());
+
+        let panel = self.ui.view(cx, &[id!(attention_inbox)]);
+        if inbox.is_empty() {
+            panel.set_visible(cx, false);
+        }
+        for (i, row_id) in ATTENTION_ROWS.iter().enumerate() {
+            let row = panel.button(cx, &[*row_id]);
+            match inbox.get(i) {
+                Some((session_id, kind)) => {
+                    let text = format!(
+                        "{}  {} · {}",
+                        kind.badge(),
+                        self.session_label(session_id),
+                        kind.label()
+                    );
+                    row.set_text(cx, &text);
+                    row.set_visible(cx, true);
+                }
+                None => row.set_visible(cx, false),
+            }
+        }
+        let hidden = inbox.len().saturating_sub(ATTENTION_ROWS.len());
+        panel
+            .label(cx, &[id!(attention_more_label)])
+            .set_visible(cx, hidden > 0);
+        panel
+            .label(cx, &[id!(attention_more_label)])
+            .set_text(cx, &format!("+{} more", hidden));
+
+        let badges: HashMap<&str, AttentionKind> = inbox
+            .iter()
+            .map(|(session_id, kind)| (session_id.as_str(), *kind))
+            .collect();
+        let dock = self.center_dock(cx);
+        for (tab_id, kind) in &self.state.center_tabs_by_id {
+            let CenterTabKind::Chat { session_id } = kind else {
+                continue;
+            };
+            let title = self.session_label(session_id);
+            let title = match badges.get(session_id.as_str()) {
+                Some(kind) => format!("{} {}", kind.badge(), title),
+                None => title,
+            };
+            dock.set_tab_title(cx, *tab_id, title);
+        }
+        self.ui.redraw(cx);
+    }
+
+    /// Open the next session that needs attention, cycling past the current one.
+    pub(super) fn jump_to_next_attention(&mut self, cx: &mut Cx) {
+        let inbox = reducer::attention_inbox(&self.state);
+        let next =
+            reducer::next_attention_session(&inbox, self.state.current_session_id.as_deref())
+                .map(str::to_string);
+        if let Some(session_id) = next {
+            self.ui
+                .view(cx, &[id!(attention_inbox)])
+                .set_visible(cx, false);
+            self.queue_or_select_session(cx, session_id);
+        }
+    }
+
+    pub(super) fn handle_attention_buttons(&mut self, cx: &mut Cx, actions: &Actions) {
+        let header = self.ui.view(cx, &[id!(main_header), id!(status_row)]);
+        let panel = self.ui.view(cx, &[id!(attention_inbox)]);
+        if header.button(cx, &[id!(attention_button)]).clicked(actions) {
+            panel.set_visible(cx, !panel.visible());
+            self.ui.redraw(cx);
+        }
+        if header
+            .button(cx, &[id!(attention_next_button)])
+            .clicked(actions)
+        {
+            self.jump_to_next_attention(cx);
+        }
+
+        let clicked = ATTENTION_ROWS
+            .iter()
+            .position(|row_id| panel.button(cx, &[*row_id]).clicked(actions));
+        if let Some(index) = clicked {
+            let inbox = reducer::attention_inbox(&self.state);
+            if let Some((session_id, _)) = inbox.into_iter().nth(index) {
+                panel.set_visible(cx, false);
+                self.queue_or_select_session(cx, session_id);
+            }
+        }
+    }
+}
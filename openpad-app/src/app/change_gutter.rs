@@ -0,0 +1,241 @@
+use super::*;
+use crate::utils::line_diff::{change_hunks, revert_hunk, LineChangeKind};
+use openpad_protocol::FileDiff;
+
+impl App {
+    /// The selected session's diff for `absolute_path`, if the agent touched it.
+    /// `current_session_id` is cleared while a file tab is active, so this goes
+    /// by the session last shown in a chat tab. Diff paths may be absolute or
+    /// relative to the project worktree.
+    pub(super) fn session_diff_for_file(&self, absolute_path: &str) -> Option<&FileDiff> {
+        let session_id = self
+            .state
+            .selected_session_id
+            .as_deref()
+            .or(self.state.current_session_id.as_deref())?;
+        let summary = self.state.find_session(session_id)?.summary.as_ref()?;
+        let open_path = Path::new(absolute_path);
+        summary.diffs.iter().find(|diff| {
+            let diff_path = Path::new(&diff.file);
+            if diff_path.is_absolute() {
+                diff_path == open_path
+            } else {
+                open_path.ends_with(diff_path)
+            }
+        })
+    }
+
+    /// Recompute the agent change markers of one file tab against its session diff.
+    pub(super) fn refresh_change_gutter(&mut self, cx: &mut Cx, tab_id: LiveId) {
+        let Some(CenterTabKind::File { open_file }) = self.state.center_tabs_by_id.get(&tab_id)
+        else {
+            return;
+        };
+        let editor = self
+            .center_dock(cx)
+            .item(tab_id)
+            .editor_panel(cx, &[id!(editor_panel)]);
+        let hunks = self
+            .session_diff_for_file(&open_file.absolute_path)
+            .map(|diff| change_hunks(&diff.before, &editor.get_text()))
+            .unwrap_or_default();
+        editor.set_change_hunks(cx, &hunks);
+
+        if let Some(CenterTabKind::File { open_file }) =
+            self.state.center_tabs_by_id.get_mut(&tab_id)
+        {
+            open_file.selected_hunk = open_file.selected_hunk.min(hunks.len().saturating_sub(1));
+            if hunks.is_empty() {
+                open_file.show_original = false;
+            }
+            open_file.change_hunks = hunks;
+        }
+        self.update_change_bar_ui(cx, tab_id);
+    }
+
+    pub(super) fn refresh_all_change_gutters(&mut self, cx: &mut Cx) {
+        let tab_ids: Vec<LiveId> = self
+            .state
+            .center_tabs_by_id
+            .iter()
+            .filter(|(_, kind)| matches!(kind, CenterTabKind::File { .. }))
+            .map(|(tab_id, _)| *tab_id)
+            .collect();
+        for tab_id in tab_ids {
+            self.refresh_change_gutter(cx, tab_id);
+        }
+    }
+
+    fn update_change_bar_ui(&self, cx: &mut Cx, tab_id: LiveId) {
+        let Some(CenterTabKind::File { open_file }) = self.state.center_tabs_by_id.get(&tab_id)
+        else {
+            return;
+        };
+        let item = self.center_dock(cx).item(tab_id);
+        let hunks = &open_file.change_hunks;
+        item.view(cx, &[id!(change_bar)])
+            .set_visible(cx, !hunks.is_empty());
+        let Some(hunk) = hunks.get(open_file.selected_hunk) else {
+            return;
+        };
+
+        let count = |kind: LineChangeKind| hunks.iter().filter(|h| h.kind == kind).count();
+        item.label(cx, &[id!(change_summary_label)]).set_text(
+            cx,
+            &format!(
+                "Agent changes  +{} ~{} −{}",
+                count(LineChangeKind::Added),
+                count(LineChangeKind::Modified),
+                count(LineChangeKind::Deleted)
+            ),
+        );
+        let lines = match hunk.len {
+            0 => format!("removed above line {}", hunk.start + 1),
+            1 => format!("line {}", hunk.start + 1),
+            len => format!("lines {}–{}", hunk.start + 1, hunk.start + len),
+        };
+        item.label(cx, &[id!(change_hunk_label)]).set_text(
+            cx,
+            &format!(
+                "{} {} ({}/{})",
+                hunk.kind.marker(),
+                lines,
+                open_file.selected_hunk + 1,
+                hunks.len()
+            ),
+        );
+
+        let original = if hunk.original.is_empty() {
+            "(no original lines, this hunk was added)".to_string()
+        } else {
+            hunk.original
+                .iter()
+                .map(|line| format!("- {}", line))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        item.label(cx, &[id!(original_lines_label)])
+            .set_text(cx, &original);
+        item.view(cx, &[id!(original_lines_wrap)])
+            .set_visible(cx, open_file.show_original);
+        item.button(cx, &[id!(show_original_button)]).set_text(
+            cx,
+            if open_file.show_original {
+                "Hide original"
+            } else {
+                "Show original"
+            },
+        );
+        item.button(cx, &[id!(revert_hunk_button)])
+            .set_visible(cx, !open_file.read_only);
+    }
+
+    fn select_change_hunk(&mut self, cx: &mut Cx, tab_id: LiveId, forward: bool) {
+        let Some(CenterTabKind::File { open_file }) = self.state.center_tabs_by_id.get_mut(&tab_id)
+        else {
+            return;
+        };
+        let len = open_file.change_hunks.len();
+        if len == 0 {
+            return;
+        }
+        open_file.selected_hunk = if forward {
+            (open_file.selected_hunk + 1) % len
+        } else {
+            (open_file.selected_hunk + len - 1) % len
+        };
+        let line = open_file.change_hunks[open_file.selected_hunk].start;
+        self.center_dock(cx)
+            .item(tab_id)
+            .editor_panel(cx, &[id!(editor_panel)])
+            .reveal_line(cx, line);
+        self.update_change_bar_ui(cx, tab_id);
+    }
+
+    /// Select the hunk whose gutter marker was clicked and show its original
+    /// lines, so it can be reverted from the change bar.
+    pub(super) fn jump_to_change_hunk(&mut self, cx: &mut Cx, tab_id: LiveId, index: usize) {
+        let Some(CenterTabKind::File { open_file }) = self.state.center_tabs_by_id.get_mut(&tab_id)
+        else {
+            return;
+        };
+        let Some(hunk) = open_file.change_hunks.get(index) else {
+            return;
+        };
+        let line = hunk.start;
+        open_file.selected_hunk = index;
+        open_file.show_original = true;
+        self.center_dock(cx)
+            .item(tab_id)
+            .editor_panel(cx, &[id!(editor_panel)])
+            .reveal_line(cx, line);
+        self.update_change_bar_ui(cx, tab_id);
+    }
+
+    /// Put the selected hunk's original lines back and save the file.
+    fn revert_selected_hunk(&mut self, cx: &mut Cx, tab_id: LiveId) {
+        if self.has_unsaved_file_tab_changes(cx, tab_id) {
+            crate::ui::state_updates::set_status_error(
+                &self.ui,
+                cx,
+                "revert failed: save or discard your edits first",
+            );
+            return;
+        }
+        let Some(CenterTabKind::File { open_file }) = self.state.center_tabs_by_id.get(&tab_id)
+        else {
+            return;
+        };
+        if open_file.read_only {
+            return;
+        }
+        let Some(hunk) = open_file.change_hunks.get(open_file.selected_hunk) else {
+            return;
+        };
+        let editor = self
+            .center_dock(cx)
+            .item(tab_id)
+            .editor_panel(cx, &[id!(editor_panel)]);
+        let reverted = revert_hunk(&editor.get_text(), hunk);
+        editor.set_text(cx, &reverted);
+        // Saving refreshes the markers.
+        self.save_file_tab(cx, tab_id);
+    }
+
+    pub(super) fn handle_change_bar_buttons(&mut self, cx: &mut Cx, actions: &Actions) {
+        let tab_ids: Vec<LiveId> = self
+            .state
+            .center_tabs_by_id
+            .iter()
+            .filter_map(|(tab_id, kind)| match kind {
+                CenterTabKind::File { open_file } if !open_file.change_hunks.is_empty() => {
+                    Some(*tab_id)
+                }
+                _ => None,
+            })
+            .collect();
+        for tab_id in tab_ids {
+            let item = self.center_dock(cx).item(tab_id);
+            if item.button(cx, &[id!(prev_change_button)]).clicked(actions) {
+                self.select_change_hunk(cx, tab_id, false);
+            }
+            if item.button(cx, &[id!(next_change_button)]).clicked(actions) {
+                self.select_change_hunk(cx, tab_id, true);
+            }
+            if item
+                .button(cx, &[id!(show_original_button)])
+                .clicked(actions)
+            {
+                if let Some(CenterTabKind::File { open_file }) =
+                    self.state.center_tabs_by_id.get_mut(&tab_id)
+                {
+                    open_file.show_original = !open_file.show_original;
+                }
+                self.update_change_bar_ui(cx, tab_id);
+            }
+            if item.button(cx, &[id!(revert_hunk_button)]).clicked(actions) {
+                self.revert_selected_hunk(cx, tab_id);
+            }
+        }
+    }
+}
@@ -1,4 +1,7 @@
 use super::*;
+use crate::components::symbol_picker::SymbolPickerWidgetRefExt;
+use crate::state::reducer;
+use openpad_protocol::{FilePartSourceText, Symbol};
 use regex::Regex;
 use std::sync::OnceLock;
 
@@ -24,7 +27,107 @@ fn image_extension_for_mime(mime_type: &str) -> &'static str {
     }
 }
 
+fn is_symbol_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | ':')
+}
+
+/// Returns the query of a `#symbol` token being typed at the end of the input, if any.
+pub(crate) fn trailing_symbol_query(text: &str) -> Option<&str> {
+    let token = text.rsplit(char::is_whitespace).next()?;
+    let query = token.strip_prefix('#')?;
+    query.chars().all(is_symbol_char).then_some(query)
+}
+
+/// Locates the `#name` reference for a symbol in the prompt text, in character
+/// offsets. `picked_at` is where the picker inserted the token; of the `#name`
+/// tokens left in the text, the one closest to it wins, so earlier mentions of
+/// the same name and edits before the token don't misplace the reference.
+pub(crate) fn symbol_source_text(text: &str, name: &str, picked_at: usize) -> FilePartSourceText {
+    let value = format!("#{}", name);
+    let len = value.chars().count();
+    let start = text
+        .match_indices(&value)
+        .filter(|(at, _)| {
+            !text[at + value.len()..]
+                .chars()
+                .next()
+                .is_some_and(is_symbol_char)
+        })
+        .map(|(at, _)| text[..at].chars().count())
+        .min_by_key(|start| start.abs_diff(picked_at));
+    let (start, end) = start.map(|start| (start, start + len)).unwrap_or((0, 0));
+    FilePartSourceText {
+        value,
+        start: start as i64,
+        end: end as i64,
+    }
+}
+
 impl App {
+    /// Show, refresh or hide the symbol picker for the `#query` token at the end of the input.
+    pub(super) fn update_symbol_completion(&mut self, cx: &mut Cx, text: &str) {
+        let query = trailing_symbol_query(text)
+            .filter(|query| !query.is_empty())
+            .map(str::to_string);
+        if query == self.state.symbol_query {
+            return;
+        }
+        self.state.symbol_query = query.clone();
+
+        let Some(query) = query else {
+            self.ui.symbol_picker(cx, &[id!(symbol_picker)]).hide(cx);
+            return;
+        };
+        let Some(client) = self.client.clone() else {
+            return;
+        };
+        let Some(runtime) = self._runtime.as_ref() else {
+            return;
+        };
+        let directory = self
+            .state
+            .current_session_id
+            .as_ref()
+            .and_then(|session_id| self.get_session_directory(session_id));
+
+        self.ui
+            .symbol_picker(cx, &[id!(symbol_picker)])
+            .show_searching(cx, &query);
+        async_runtime::spawn_symbol_searcher(runtime, client, query, directory);
+    }
+
+    /// Replace the `#query` token with the picked symbol's name and attach it to the prompt.
+    pub(super) fn apply_symbol_selection(&mut self, cx: &mut Cx, symbol: Symbol) {
+        let input = self.ui.text_input(cx, &[id!(input_box)]);
+        let text = input.text();
+        let prefix_len = trailing_symbol_query(&text)
+            .map(|query| text.len() - query.len() - 1)
+            .unwrap_or(text.len());
+        let new_text = format!("{}#{} ", &text[..prefix_len], symbol.name);
+        input.set_text(cx, &new_text);
+        let picked_at = text[..prefix_len].chars().count();
+
+        let already_attached = self.state.attached_files.iter().any(|file| {
+            file.symbol
+                .as_ref()
+                .is_some_and(|s| s.name == symbol.name && s.location.uri == symbol.location.uri)
+        });
+        if !already_attached {
+            self.state.attached_files.push(crate::state::AttachedFile {
+                filename: format!("#{}", symbol.name),
+                mime_type: "text/plain".to_string(),
+                data_url: String::new(),
+                raw_text: None,
+                symbol: Some(symbol),
+                symbol_picked_at: picked_at,
+            });
+        }
+
+        self.state.symbol_query = None;
+        self.ui.symbol_picker(cx, &[id!(symbol_picker)]).hide(cx);
+        self.update_attachments_ui(cx);
+    }
+
     /// Extract data URLs from text and add them as attachments.
     /// Returns the text with data URLs removed.
     pub(super) fn process_pasted_content(&mut self, cx: &mut Cx, text: &str) -> String {
@@ -61,6 +164,8 @@ impl App {
                 mime_type: mime_type.to_string(),
                 data_url: full_match.to_string(),
                 raw_text: None,
+                symbol: None,
+                symbol_picked_at: 0,
             });
 
             log!("Detected pasted image: {} ({})", mime_type, filename);
@@ -75,6 +180,29 @@ impl App {
         remaining_text
     }
 
+    /// Shows the model, agent and skill of `session_id` in the composer when
+    /// it comes into view. Sessions with nothing to go on yet (new, or with
+    /// messages still loading) keep the current picks.
+    pub(super) fn sync_composer_selection(&mut self, cx: &mut Cx, session_id: &str) {
+        if self.state.composer_selection_session.as_deref() == Some(session_id) {
+            return;
+        }
+        let Some(selection) = reducer::session_composer_selection(&self.state, session_id) else {
+            return;
+        };
+        reducer::apply_composer_selection(&mut self.state, &selection);
+        self.state.composer_selection_session = Some(session_id.to_string());
+
+        self.state.update_model_ui(&self.ui, cx);
+        self.ui
+            .up_drop_down(cx, &[id!(input_bar_toolbar), id!(agent_dropdown)])
+            .set_selected_item(cx, self.state.selected_agent_idx.map_or(0, |idx| idx + 1));
+        self.ui
+            .up_drop_down(cx, &[id!(input_bar_toolbar), id!(skill_dropdown)])
+            .set_selected_item(cx, self.state.selected_skill_idx.map_or(0, |idx| idx + 1));
+        self.update_skill_ui(cx);
+    }
+
     pub(super) fn send_message(&mut self, cx: &mut Cx, text: String) {
         let Some(client) = self.client_or_error() else {
             return;
@@ -101,9 +229,11 @@ impl App {
                 session.directory.clone()
             });
         let model_spec = self.state.selected_model_spec();
+        let variant = self.state.selected_variant.clone();
         let agent = self.state.selected_agent_name();
-        let permission = self.state.selected_agent_permission();
+        let permission = self.state.new_session_permission();
         let system = self.state.selected_skill_prompt();
+        reducer::remember_composer_selection(&mut self.state);
 
         self.state.is_working = true;
         crate::ui::state_updates::update_work_indicator(&self.ui, cx, true);
@@ -114,7 +244,12 @@ impl App {
             .attached_files
             .iter()
             .map(|file| {
-                if let Some(raw_text) = &file.raw_text {
+                if let Some(symbol) = &file.symbol {
+                    openpad_protocol::PartInput::symbol(
+                        symbol,
+                        symbol_source_text(&text, &symbol.name, file.symbol_picked_at),
+                    )
+                } else if let Some(raw_text) = &file.raw_text {
                     // Text attachments are sent as text parts
                     openpad_protocol::PartInput::text(raw_text)
                 } else {
@@ -133,6 +268,7 @@ impl App {
             Some(session_id),
             text,
             model_spec,
+            variant,
             agent,
             system,
             directory,
@@ -142,6 +278,8 @@ impl App {
 
         // Clear attached files after sending
         self.state.attached_files.clear();
+        self.state.symbol_query = None;
+        self.ui.symbol_picker(cx, &[id!(symbol_picker)]).hide(cx);
         self.update_attachments_ui(cx);
     }
 }
@@ -0,0 +1,271 @@
+use super::*;
+use crate::state::reducer::adjacent_diff_file;
+use openpad_protocol::FileDiff;
+use openpad_widgets::diff_model::FOLD_CONTEXT;
+use openpad_widgets::diff_view::{DiffViewApi, DiffViewWidgetExt};
+use openpad_widgets::message_logic::MessageProcessor;
+
+fn diff_tab_title(file: &str) -> String {
+    let display_name = Path::new(file)
+        .file_name()
+        .and_then(|v| v.to_str())
+        .unwrap_or(file);
+    format!("Δ {}", display_name)
+}
+
+impl App {
+    fn session_diffs(&self, session_id: &str) -> &[FileDiff] {
+        self.state
+            .find_session(session_id)
+            .and_then(|session| session.summary.as_ref())
+            .map(|summary| summary.diffs.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub(super) fn queue_or_open_diff(&mut self, cx: &mut Cx, session_id: String, file: String) {
+        if let Some(active_file_tab_id) = self.current_active_file_tab_id() {
+            if self.has_unsaved_file_tab_changes(cx, active_file_tab_id) {
+                self.state.pending_center_intent =
+                    Some(PendingCenterIntent::OpenDiff { session_id, file });
+                self.show_unsaved_editor_dialog(cx);
+                return;
+            }
+        }
+        self.open_diff_now(cx, session_id, file);
+    }
+
+    pub(super) fn open_diff_now(&mut self, cx: &mut Cx, session_id: String, file: String) {
+        let key = (session_id.clone(), file.clone());
+        if let Some(tab_id) = self.state.tab_by_diff.get(&key).copied() {
+            self.activate_center_tab(cx, tab_id);
+            self.render_diff_tab(cx, tab_id);
+            return;
+        }
+
+        let dock = self.center_dock(cx);
+        let (tab_bar, pos) = dock
+            .find_tab_bar_of_tab(live_id!(center_home_tab))
+            .unwrap_or((live_id!(root), 0));
+        let tab_id = dock.unique_id(LiveId::from_str(&format!("diff:{}:{}", session_id, file)).0);
+        let _ = dock.create_and_select_tab(
+            cx,
+            tab_bar,
+            tab_id,
+            live_id!(CenterDiffTab),
+            diff_tab_title(&file),
+            live_id!(CloseableTab),
+            Some(pos),
+        );
+
+        self.state
+            .center_tabs_by_id
+            .insert(tab_id, CenterTabKind::Diff { session_id, file });
+        self.state.tab_by_diff.insert(key, tab_id);
+        self.activate_center_tab(cx, tab_id);
+    }
+
+    /// Fill a diff tab from its session's current diff. Diff tabs are only
+    /// re-rendered when shown or when the session diff reloads, since building
+    /// the full-file diff text is too costly for every streamed event.
+    pub(super) fn render_diff_tab(&self, cx: &mut Cx, tab_id: LiveId) {
+        let Some(CenterTabKind::Diff { session_id, file }) =
+            self.state.center_tabs_by_id.get(&tab_id).cloned()
+        else {
+            return;
+        };
+        let item = self.center_dock(cx).item(tab_id);
+        let diffs = self.session_diffs(&session_id);
+        let position = diffs.iter().position(|diff| diff.file == file);
+
+        item.label(cx, &[id!(diff_file_label)]).set_text(cx, &file);
+        item.label(cx, &[id!(diff_position_label)]).set_text(
+            cx,
+            &match position {
+                Some(at) => format!("File {} of {}", at + 1, diffs.len()),
+                None => "No longer in the session diff".to_string(),
+            },
+        );
+        let can_navigate = diffs.len() > 1 || (position.is_none() && !diffs.is_empty());
+        item.button(cx, &[id!(prev_diff_file_button)])
+            .set_visible(cx, can_navigate);
+        item.button(cx, &[id!(next_diff_file_button)])
+            .set_visible(cx, can_navigate);
+
+        let diff_view = item.diff_view(cx, &[id!(diff_view)]);
+        match position.map(|at| &diffs[at]) {
+            Some(diff) => {
+                let mut text = format!(
+                    "... {} (+{} -{})\n",
+                    diff.file, diff.additions, diff.deletions
+                );
+                // The viewer expands the lines between hunks from `diff.before`.
+                text.push_str(&MessageProcessor::compute_unified_diff(
+                    &diff.before,
+                    &diff.after,
+                    FOLD_CONTEXT,
+                ));
+                let status = diff.status.as_deref().unwrap_or("modified");
+                diff_view.set_diff_text(
+                    cx,
+                    status,
+                    &format!("+{}", diff.additions),
+                    &format!("-{}", diff.deletions),
+                    &text,
+                );
+                diff_view.set_sources(cx, &[diff.before.as_str()]);
+                diff_view.set_expanded(cx, true);
+            }
+            None => diff_view.clear_diffs(cx),
+        }
+    }
+
+    pub(super) fn refresh_diff_tabs(&self, cx: &mut Cx) {
+        let tab_ids: Vec<LiveId> = self
+            .state
+            .center_tabs_by_id
+            .iter()
+            .filter(|(_, kind)| matches!(kind, CenterTabKind::Diff { .. }))
+            .map(|(tab_id, _)| *tab_id)
+            .collect();
+        for tab_id in tab_ids {
+            self.render_diff_tab(cx, tab_id);
+        }
+    }
+
+    fn step_diff_file(&mut self, cx: &mut Cx, tab_id: LiveId, forward: bool) {
+        let Some(CenterTabKind::Diff { session_id, file }) =
+            self.state.center_tabs_by_id.get(&tab_id).cloned()
+        else {
+            return;
+        };
+        let Some(next) = adjacent_diff_file(self.session_diffs(&session_id), &file, forward) else {
+            return;
+        };
+        let next = next.to_string();
+        // Another tab already shows that file: switch to it rather than
+        // showing the same diff twice.
+        if self
+            .state
+            .tab_by_diff
+            .contains_key(&(session_id.clone(), next.clone()))
+        {
+            self.open_diff_now(cx, session_id, next);
+            return;
+        }
+
+        self.state.tab_by_diff.remove(&(session_id.clone(), file));
+        self.state
+            .tab_by_diff
+            .insert((session_id.clone(), next.clone()), tab_id);
+        self.center_dock(cx)
+            .set_tab_title(cx, tab_id, diff_tab_title(&next));
+        self.state.center_tabs_by_id.insert(
+            tab_id,
+            CenterTabKind::Diff {
+                session_id,
+                file: next,
+            },
+        );
+        self.render_diff_tab(cx, tab_id);
+    }
+
+    /// Open the changed file itself, resolving relative diff paths against
+    /// the session directory.
+    fn open_diff_source_file(&mut self, cx: &mut Cx, tab_id: LiveId) {
+        let Some(CenterTabKind::Diff { session_id, file }) =
+            self.state.center_tabs_by_id.get(&tab_id).cloned()
+        else {
+            return;
+        };
+        let Some(session) = self.state.find_session(&session_id) else {
+            return;
+        };
+        let absolute_path = if Path::new(&file).is_absolute() {
+            file
+        } else {
+            Path::new(&session.directory)
+                .join(&file)
+                .to_string_lossy()
+                .to_string()
+        };
+        let project_id = session.project_id.clone();
+        self.queue_or_open_file(cx, project_id, absolute_path);
+    }
+
+    pub(super) fn handle_diff_tab_buttons(&mut self, cx: &mut Cx, actions: &Actions) {
+        let diff_tab_ids: Vec<LiveId> = self
+            .state
+            .center_tabs_by_id
+            .iter()
+            .filter(|(_, kind)| matches!(kind, CenterTabKind::Diff { .. }))
+            .map(|(tab_id, _)| *tab_id)
+            .collect();
+        for tab_id in diff_tab_ids {
+            let item = self.center_dock(cx).item(tab_id);
+            if item
+                .button(cx, &[id!(prev_diff_file_button)])
+                .clicked(actions)
+            {
+                self.step_diff_file(cx, tab_id, false);
+            }
+            if item
+                .button(cx, &[id!(next_diff_file_button)])
+                .clicked(actions)
+            {
+                self.step_diff_file(cx, tab_id, true);
+            }
+            if item
+                .button(cx, &[id!(open_diff_file_button)])
+                .clicked(actions)
+            {
+                self.open_diff_source_file(cx, tab_id);
+            }
+        }
+
+        let chat_tabs: Vec<(LiveId, String)> = self
+            .state
+            .center_tabs_by_id
+            .iter()
+            .filter_map(|(tab_id, kind)| match kind {
+                CenterTabKind::Chat { session_id } => Some((*tab_id, session_id.clone())),
+                _ => None,
+            })
+            .collect();
+        for (tab_id, session_id) in chat_tabs {
+            let item = self.center_dock(cx).item(tab_id);
+            if item
+                .button(cx, &[id!(review_changes_button)])
+                .clicked(actions)
+            {
+                if let Some(first) = self.session_diffs(&session_id).first() {
+                    let file = first.file.clone();
+                    self.queue_or_open_diff(cx, session_id, file);
+                }
+            }
+        }
+
+        let file_tabs: Vec<(LiveId, String)> = self
+            .state
+            .center_tabs_by_id
+            .iter()
+            .filter_map(|(tab_id, kind)| match kind {
+                CenterTabKind::File { open_file } if !open_file.change_hunks.is_empty() => {
+                    Some((*tab_id, open_file.absolute_path.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        for (tab_id, absolute_path) in file_tabs {
+            let item = self.center_dock(cx).item(tab_id);
+            if item.button(cx, &[id!(view_diff_button)]).clicked(actions) {
+                let target = self.state.selected_session_id.clone().zip(
+                    self.session_diff_for_file(&absolute_path)
+                        .map(|diff| diff.file.clone()),
+                );
+                if let Some((session_id, file)) = target {
+                    self.open_diff_now(cx, session_id, file);
+                }
+            }
+        }
+    }
+}
@@ -10,6 +10,9 @@ impl App {
         else {
             return false;
         };
+        if open_file.read_only {
+            return false;
+        }
         let item = self.center_dock(cx).item(tab_id);
         let current_text = item.editor_panel(cx, &[id!(editor_panel)]).get_text();
         current_text != open_file.text_cache
@@ -21,12 +24,39 @@ impl App {
         else {
             return false;
         };
+        if open_file.read_only {
+            // Saving through the server is not supported: its file API
+            // (`/file`, `/file/content`) has no write endpoint.
+            crate::ui::state_updates::set_status_error(
+                &self.ui,
+                cx,
+                "not saved: files opened from the server are read-only (it has no file write API)",
+            );
+            return false;
+        }
         let item = self.center_dock(cx).item(tab_id);
         let text = item.editor_panel(cx, &[id!(editor_panel)]).get_text();
         if text == open_file.text_cache {
             return true;
         }
-        if let Err(err) = std::fs::write(&open_file.absolute_path, text.as_bytes()) {
+        // Pick up changes the file watcher missed before overwriting them.
+        if let Ok(disk_text) = file_access::read_local_text(Path::new(&open_file.absolute_path)) {
+            if disk_text != open_file.text_cache {
+                self.apply_external_text(cx, tab_id, disk_text);
+            }
+        }
+        if let Some(CenterTabKind::File { open_file }) = self.state.center_tabs_by_id.get(&tab_id) {
+            if open_file.external_text.is_some() {
+                crate::ui::state_updates::set_status_error(
+                    &self.ui,
+                    cx,
+                    "save blocked: file changed on disk, choose reload, keep mine or merge",
+                );
+                return false;
+            }
+        }
+        if let Err(err) = file_access::write_local_text(Path::new(&open_file.absolute_path), &text)
+        {
             self.state.error_message = Some(format!(
                 "Failed to save {}: {}",
                 open_file.absolute_path, err
@@ -45,6 +75,7 @@ impl App {
             open_file.last_saved_revision = open_file.last_saved_revision.saturating_add(1);
         }
         self.update_editor_header_ui_for_tab(cx, tab_id);
+        self.refresh_change_gutter(cx, tab_id);
         true
     }
 
@@ -54,6 +85,15 @@ impl App {
         else {
             return;
         };
+        if open_file.external_text.is_some() {
+            // Discarding means taking the newer disk version, not the stale cache.
+            self.resolve_external_change(
+                cx,
+                tab_id,
+                super::file_sync::ExternalChangeChoice::Reload,
+            );
+            return;
+        }
         let item = self.center_dock(cx).item(tab_id);
         item.editor_panel(cx, &[id!(editor_panel)])
             .set_text(cx, &open_file.text_cache);
@@ -79,18 +119,130 @@ impl App {
         cx: &mut Cx,
         project_id: String,
         absolute_path: String,
+    ) {
+        self.queue_or_open_file_at_line(cx, project_id, absolute_path, None);
+    }
+
+    /// Like `queue_or_open_file`, but scrolls the editor to the zero-based `line` once open.
+    pub(super) fn queue_or_open_file_at_line(
+        &mut self,
+        cx: &mut Cx,
+        project_id: String,
+        absolute_path: String,
+        line: Option<usize>,
     ) {
         if let Some(tab_id) = self.current_active_file_tab_id() {
             if self.has_unsaved_file_tab_changes(cx, tab_id) {
                 self.state.pending_center_intent = Some(PendingCenterIntent::OpenFile {
                     project_id,
                     absolute_path,
+                    line,
                 });
                 self.show_unsaved_editor_dialog(cx);
                 return;
             }
         }
-        self.open_file_now(cx, project_id, absolute_path);
+        self.open_file_at_line_now(cx, project_id, absolute_path, line);
+    }
+
+    pub(super) fn open_file_at_line_now(
+        &mut self,
+        cx: &mut Cx,
+        project_id: String,
+        absolute_path: String,
+        line: Option<usize>,
+    ) {
+        if let Some(existing_tab_id) = self.state.tab_by_file.get(&absolute_path).copied() {
+            self.activate_center_tab(cx, existing_tab_id);
+            self.reveal_line_in_tab(cx, existing_tab_id, line);
+            return;
+        }
+
+        if self.state.file_access.is_server() {
+            self.request_remote_file(project_id, absolute_path, line);
+            return;
+        }
+
+        let content = match file_access::read_local_text(Path::new(&absolute_path)) {
+            Ok(content) => content,
+            Err(err) => {
+                log!("Not opening {}: {}", absolute_path, err);
+                return;
+            }
+        };
+        let tab_id = self.open_file_tab(cx, project_id, absolute_path, content, false);
+        self.reveal_line_in_tab(cx, tab_id, line);
+    }
+
+    fn reveal_line_in_tab(&mut self, cx: &mut Cx, tab_id: LiveId, line: Option<usize>) {
+        if let Some(line) = line {
+            self.center_dock(cx)
+                .item(tab_id)
+                .editor_panel(cx, &[id!(editor_panel)])
+                .reveal_line(cx, line);
+        }
+    }
+
+    pub(super) fn request_remote_file(
+        &mut self,
+        project_id: String,
+        absolute_path: String,
+        line: Option<usize>,
+    ) {
+        let Some(client) = self.client_or_error() else {
+            return;
+        };
+        let Some(runtime) = self._runtime.as_ref() else {
+            return;
+        };
+        let Some(root) = self
+            .state
+            .projects
+            .iter()
+            .find(|p| p.id == project_id)
+            .map(|p| p.worktree.clone())
+        else {
+            return;
+        };
+        async_runtime::spawn_remote_file_reader(
+            runtime,
+            client,
+            project_id,
+            root,
+            absolute_path,
+            line,
+        );
+    }
+
+    /// Open a tab for a file fetched from the server. Server files are read-only
+    /// because the file API has no write endpoint.
+    pub(super) fn finish_remote_file_open(
+        &mut self,
+        cx: &mut Cx,
+        project_id: String,
+        absolute_path: String,
+        line: Option<usize>,
+        result: Result<String, file_access::ReadTextError>,
+    ) {
+        let content = match result {
+            Ok(content) => content,
+            Err(err) => {
+                crate::ui::state_updates::set_status_error(
+                    &self.ui,
+                    cx,
+                    &format!("open failed: {}", err),
+                );
+                return;
+            }
+        };
+        match self.state.tab_by_file.get(&absolute_path).copied() {
+            // Already open: this is a refresh after a file change event.
+            Some(tab_id) => self.apply_external_text(cx, tab_id, content),
+            None => {
+                let tab_id = self.open_file_tab(cx, project_id, absolute_path, content, true);
+                self.reveal_line_in_tab(cx, tab_id, line);
+            }
+        }
     }
 
     pub(super) fn queue_or_select_session(&mut self, cx: &mut Cx, session_id: String) {
@@ -147,6 +299,9 @@ impl App {
                 CenterTabKind::File { open_file } => {
                     self.state.tab_by_file.remove(&open_file.absolute_path);
                 }
+                CenterTabKind::Diff { session_id, file } => {
+                    self.state.tab_by_diff.remove(&(session_id, file));
+                }
                 CenterTabKind::Home => {}
             }
         }
@@ -180,34 +335,25 @@ impl App {
             match kind {
                 CenterTabKind::Chat { session_id } => self.render_chat_tab(cx, tab_id, &session_id),
                 CenterTabKind::File { .. } => self.update_editor_header_ui_for_tab(cx, tab_id),
+                CenterTabKind::Diff { .. } => self.render_diff_tab(cx, tab_id),
                 CenterTabKind::Home => {}
             }
         }
     }
 
     pub(super) fn open_file_now(&mut self, cx: &mut Cx, project_id: String, absolute_path: String) {
-        if let Some(existing_tab_id) = self.state.tab_by_file.get(&absolute_path).copied() {
-            self.activate_center_tab(cx, existing_tab_id);
-            return;
-        }
-
-        let path = Path::new(&absolute_path);
-        if !path.is_file() {
-            return;
-        }
-
-        let Ok(bytes) = std::fs::read(path) else {
-            return;
-        };
-        if is_probably_binary(&bytes) {
-            return;
-        }
-
-        let Ok(content) = String::from_utf8(bytes) else {
-            return;
-        };
+        self.open_file_at_line_now(cx, project_id, absolute_path, None);
+    }
 
-        let display_name = std::path::Path::new(&absolute_path)
+    fn open_file_tab(
+        &mut self,
+        cx: &mut Cx,
+        project_id: String,
+        absolute_path: String,
+        content: String,
+        read_only: bool,
+    ) -> LiveId {
+        let display_name = Path::new(&absolute_path)
             .file_name()
             .and_then(|v| v.to_str())
             .unwrap_or(&absolute_path)
@@ -218,6 +364,12 @@ impl App {
             display_name: display_name.clone(),
             text_cache: content.clone(),
             last_saved_revision: 0,
+            read_only,
+            external_text: None,
+            pending_merge: None,
+            change_hunks: Vec::new(),
+            selected_hunk: 0,
+            show_original: false,
         };
 
         let dock = self.center_dock(cx);
@@ -245,12 +397,14 @@ impl App {
 
         let item = dock.item(tab_id);
         item.editor_panel(cx, &[id!(editor_panel)])
-            .set_read_only(cx, false);
+            .set_read_only(cx, read_only);
         item.editor_panel(cx, &[id!(editor_panel)])
             .set_text(cx, &content);
         item.editor_panel(cx, &[id!(editor_panel)]).focus_editor(cx);
         self.update_editor_header_ui_for_tab(cx, tab_id);
+        self.refresh_change_gutter(cx, tab_id);
         self.activate_center_tab(cx, tab_id);
+        tab_id
     }
 
     pub(super) fn select_session_now(&mut self, cx: &mut Cx, session_id: String) {
@@ -298,6 +452,7 @@ impl App {
         if !self.state.messages_by_session.contains_key(&session_id) {
             self.load_messages(session_id.clone());
         }
+        self.load_session_children(session_id);
         self.load_pending_permissions();
     }
 
@@ -310,10 +465,14 @@ impl App {
             PendingCenterIntent::OpenFile {
                 project_id,
                 absolute_path,
-            } => self.open_file_now(cx, project_id, absolute_path),
+                line,
+            } => self.open_file_at_line_now(cx, project_id, absolute_path, line),
             PendingCenterIntent::OpenSession { session_id } => {
                 self.select_session_now(cx, session_id)
             }
+            PendingCenterIntent::OpenDiff { session_id, file } => {
+                self.open_diff_now(cx, session_id, file)
+            }
             PendingCenterIntent::SwitchTab { tab_id } => self.activate_center_tab(cx, tab_id),
             PendingCenterIntent::CloseTab { tab_id } => self.close_tab_now(cx, tab_id),
         }
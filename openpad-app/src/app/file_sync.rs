@@ -0,0 +1,227 @@
+use super::*;
+use crate::utils::merge::{three_way_merge, ConflictSide, PendingMerge};
+
+/// How to settle a file that changed on disk under unsaved edits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum ExternalChangeChoice {
+    /// Drop the edits and load the disk version
+    Reload,
+    /// Keep the edits; the next save overwrites the disk version
+    KeepMine,
+    /// Replace the buffer with a diff3 merge of both versions, after the user
+    /// picks a side for each conflict
+    Merge,
+}
+
+impl App {
+    /// Re-read any open tab for `path` after a file watcher / agent edit event.
+    /// `path` may be absolute or relative to a project worktree.
+    pub(super) fn reload_open_file_tabs(&mut self, cx: &mut Cx, path: &str) {
+        let changed = Path::new(path);
+        let tabs: Vec<(LiveId, OpenFileState)> = self
+            .state
+            .center_tabs_by_id
+            .iter()
+            .filter_map(|(tab_id, kind)| match kind {
+                CenterTabKind::File { open_file } => Some((*tab_id, open_file.clone())),
+                _ => None,
+            })
+            .filter(|(_, open_file)| {
+                let open_path = Path::new(&open_file.absolute_path);
+                if changed.is_absolute() {
+                    open_path == changed
+                } else {
+                    open_path.ends_with(changed)
+                }
+            })
+            .collect();
+
+        for (tab_id, open_file) in tabs {
+            if open_file.read_only {
+                // Server-backed tabs come back through `finish_remote_file_open`.
+                self.request_remote_file(open_file.project_id, open_file.absolute_path, None);
+                continue;
+            }
+            match file_access::read_local_text(Path::new(&open_file.absolute_path)) {
+                Ok(disk_text) => self.apply_external_text(cx, tab_id, disk_text),
+                Err(err) => log!("Not reloading {}: {}", open_file.absolute_path, err),
+            }
+        }
+    }
+
+    /// Clean buffers follow the disk; dirty buffers keep their edits and show
+    /// the external change bar until the user picks a resolution.
+    pub(super) fn apply_external_text(&mut self, cx: &mut Cx, tab_id: LiveId, disk_text: String) {
+        let editor = self
+            .center_dock(cx)
+            .item(tab_id)
+            .editor_panel(cx, &[id!(editor_panel)]);
+        let current_text = editor.get_text();
+        let Some(CenterTabKind::File { open_file }) = self.state.center_tabs_by_id.get_mut(&tab_id)
+        else {
+            return;
+        };
+
+        if open_file.external_text.as_deref() != Some(disk_text.as_str()) {
+            // A newer disk version invalidates a merge in progress.
+            open_file.pending_merge = None;
+        }
+        if disk_text == open_file.text_cache {
+            // Our own save, or the file went back to what we loaded.
+            open_file.external_text = None;
+        } else if current_text == open_file.text_cache || current_text == disk_text {
+            if current_text != disk_text {
+                editor.set_text(cx, &disk_text);
+            }
+            open_file.text_cache = disk_text;
+            open_file.external_text = None;
+        } else {
+            open_file.external_text = Some(disk_text);
+        }
+        self.update_editor_header_ui_for_tab(cx, tab_id);
+        self.refresh_change_gutter(cx, tab_id);
+    }
+
+    pub(super) fn resolve_external_change(
+        &mut self,
+        cx: &mut Cx,
+        tab_id: LiveId,
+        choice: ExternalChangeChoice,
+    ) {
+        let editor = self
+            .center_dock(cx)
+            .item(tab_id)
+            .editor_panel(cx, &[id!(editor_panel)]);
+        let mine = editor.get_text();
+        let Some(CenterTabKind::File { open_file }) = self.state.center_tabs_by_id.get_mut(&tab_id)
+        else {
+            return;
+        };
+        open_file.pending_merge = None;
+        let Some(disk_text) = open_file.external_text.take() else {
+            return;
+        };
+
+        match choice {
+            ExternalChangeChoice::Reload => {
+                editor.set_text(cx, &disk_text);
+            }
+            ExternalChangeChoice::KeepMine => {}
+            ExternalChangeChoice::Merge => {
+                let merged = three_way_merge(&open_file.text_cache, &mine, &disk_text);
+                if let Some(text) = merged.resolve(&[]) {
+                    editor.set_text(cx, &text);
+                } else {
+                    // Conflicts are settled one by one from the change bar; the
+                    // buffer and baseline stay untouched until then.
+                    open_file.external_text = Some(disk_text);
+                    open_file.pending_merge = Some(PendingMerge::new(mine, merged));
+                    self.update_editor_header_ui_for_tab(cx, tab_id);
+                    return;
+                }
+            }
+        }
+        // The disk version is now the baseline, so saving writes over it knowingly.
+        open_file.text_cache = disk_text;
+        self.update_editor_header_ui_for_tab(cx, tab_id);
+        self.refresh_change_gutter(cx, tab_id);
+    }
+
+    /// Take `side` for the current conflict of the tab's pending merge, and
+    /// write the merged text into the buffer once the last one is settled.
+    pub(super) fn settle_merge_conflict(
+        &mut self,
+        cx: &mut Cx,
+        tab_id: LiveId,
+        side: ConflictSide,
+    ) {
+        let editor = self
+            .center_dock(cx)
+            .item(tab_id)
+            .editor_panel(cx, &[id!(editor_panel)]);
+        let current_text = editor.get_text();
+        let Some(CenterTabKind::File { open_file }) = self.state.center_tabs_by_id.get_mut(&tab_id)
+        else {
+            return;
+        };
+        let Some(pending) = open_file.pending_merge.as_mut() else {
+            return;
+        };
+        if pending.mine != current_text {
+            open_file.pending_merge = None;
+            crate::ui::state_updates::set_status_error(
+                &self.ui,
+                cx,
+                "merge cancelled: the file was edited while merging, merge again",
+            );
+            self.update_editor_header_ui_for_tab(cx, tab_id);
+            return;
+        }
+        if let Some(text) = pending.choose(side) {
+            open_file.pending_merge = None;
+            if let Some(disk_text) = open_file.external_text.take() {
+                open_file.text_cache = disk_text;
+            }
+            editor.set_text(cx, &text);
+            self.refresh_change_gutter(cx, tab_id);
+        }
+        self.update_editor_header_ui_for_tab(cx, tab_id);
+    }
+
+    pub(super) fn cancel_merge(&mut self, cx: &mut Cx, tab_id: LiveId) {
+        if let Some(CenterTabKind::File { open_file }) =
+            self.state.center_tabs_by_id.get_mut(&tab_id)
+        {
+            open_file.pending_merge = None;
+        }
+        self.update_editor_header_ui_for_tab(cx, tab_id);
+    }
+
+    pub(super) fn handle_external_change_buttons(&mut self, cx: &mut Cx, actions: &Actions) {
+        let tab_ids: Vec<LiveId> = self
+            .state
+            .center_tabs_by_id
+            .iter()
+            .filter_map(|(tab_id, kind)| match kind {
+                CenterTabKind::File { open_file } if open_file.external_text.is_some() => {
+                    Some(*tab_id)
+                }
+                _ => None,
+            })
+            .collect();
+        for tab_id in tab_ids {
+            let item = self.center_dock(cx).item(tab_id);
+            if item.button(cx, &[id!(use_mine_button)]).clicked(actions) {
+                self.settle_merge_conflict(cx, tab_id, ConflictSide::Mine);
+                continue;
+            }
+            if item.button(cx, &[id!(use_disk_button)]).clicked(actions) {
+                self.settle_merge_conflict(cx, tab_id, ConflictSide::Disk);
+                continue;
+            }
+            if item
+                .button(cx, &[id!(cancel_merge_button)])
+                .clicked(actions)
+            {
+                self.cancel_merge(cx, tab_id);
+                continue;
+            }
+            let choice = if item
+                .button(cx, &[id!(reload_external_button)])
+                .clicked(actions)
+            {
+                ExternalChangeChoice::Reload
+            } else if item.button(cx, &[id!(keep_mine_button)]).clicked(actions) {
+                ExternalChangeChoice::KeepMine
+            } else if item
+                .button(cx, &[id!(merge_external_button)])
+                .clicked(actions)
+            {
+                ExternalChangeChoice::Merge
+            } else {
+                continue;
+            };
+            self.resolve_external_change(cx, tab_id, choice);
+        }
+    }
+}
@@ -45,6 +45,7 @@ impl App {
         async_runtime::spawn_sse_subscriber(&runtime, client.clone());
         async_runtime::spawn_health_checker(&runtime, client.clone());
         async_runtime::spawn_project_loader(&runtime, client.clone());
+        async_runtime::spawn_path_loader(&runtime, client.clone());
 
         self.client = Some(client);
         self._runtime = Some(runtime);
@@ -102,4 +103,15 @@ impl App {
 
         async_runtime::spawn_message_loader(runtime, client, session_id, directory);
     }
+
+    pub(super) fn load_session_children(&mut self, session_id: String) {
+        let Some(client) = self.client.clone() else {
+            return;
+        };
+        let Some(runtime) = self._runtime.as_ref() else {
+            return;
+        };
+        let directory = self.get_session_directory(&session_id);
+        async_runtime::spawn_session_children_loader(runtime, client, session_id, directory);
+    }
 }
@@ -0,0 +1,203 @@
+use super::*;
+use crate::state::reducer;
+use crate::utils::permission_policy::PermissionPolicy;
+use openpad_protocol::PermissionRequest;
+
+/// Audit entries listed in the policy panel, newest first.
+const AUDIT_ROWS_SHOWN: usize = 15;
+
+impl App {
+    /// Re-reads the policy file. This also resets a dry run toggled from the
+    /// panel back to the file's `mode`.
+    pub(super) fn reload_permission_policy(&mut self) {
+        self.state.permission_policy = PermissionPolicy::load();
+    }
+
+    /// Applies the local policy to a permission request, whether asked over SSE
+    /// or listed by the pending-permissions load. Returns `true` when it was
+    /// answered here, or is held until its session loads, and should not reach
+    /// the UI yet.
+    pub(super) fn auto_reply_permission(
+        &mut self,
+        cx: &mut Cx,
+        request: &PermissionRequest,
+    ) -> bool {
+        let held = &self.state.permissions_awaiting_session;
+        if held.iter().any(|pending| pending.id == request.id) {
+            return true;
+        }
+        if self.state.permission_policy.has_project_rules()
+            && self.state.find_session(&request.session_id).is_none()
+        {
+            // Project rules need the directory, which only the session knows.
+            let first = !held
+                .iter()
+                .any(|pending| pending.session_id == request.session_id);
+            self.state
+                .permissions_awaiting_session
+                .push(request.clone());
+            if first {
+                self.load_permission_session(request.session_id.clone());
+            }
+            return true;
+        }
+        self.reply_by_policy(cx, request)
+    }
+
+    /// Runs the policy now, with whatever directory is known. Returns `true`
+    /// when a rule answered the request.
+    fn reply_by_policy(&mut self, cx: &mut Cx, request: &PermissionRequest) -> bool {
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0);
+        let Some(action) = reducer::apply_permission_policy(&mut self.state, request, now_ms)
+        else {
+            return false;
+        };
+        self.respond_to_permission(
+            cx,
+            request.session_id.clone(),
+            request.id.clone(),
+            action.reply(),
+        );
+        true
+    }
+
+    fn load_permission_session(&mut self, session_id: String) {
+        let Some(client) = self.client.clone() else {
+            return;
+        };
+        let Some(runtime) = self._runtime.as_ref() else {
+            return;
+        };
+        let directories = self
+            .state
+            .projects
+            .iter()
+            .filter(|project| !project.worktree.is_empty() && project.worktree != "/")
+            .map(|project| project.worktree.clone())
+            .collect();
+        async_runtime::spawn_permission_session_loader(runtime, client, session_id, directories);
+    }
+
+    /// Settles the requests held for `session_id` once its lookup finished:
+    /// the policy answers what it can and the rest go to the permission UI.
+    pub(super) fn release_held_permissions(&mut self, cx: &mut Cx, session_id: &str) {
+        let (held, others): (Vec<PermissionRequest>, Vec<PermissionRequest>) =
+            std::mem::take(&mut self.state.permissions_awaiting_session)
+                .into_iter()
+                .partition(|request| request.session_id == session_id);
+        self.state.permissions_awaiting_session = others;
+        for request in held {
+            if !self.reply_by_policy(cx, &request) {
+                state::handle_app_action(
+                    &mut self.state,
+                    &self.ui,
+                    cx,
+                    &AppAction::PendingPermissionReceived(request),
+                );
+            }
+        }
+    }
+
+    pub(super) fn refresh_policy_panel(&self, cx: &mut Cx) {
+        let policy = &self.state.permission_policy;
+        let button_text = if policy.dry_run {
+            "Policy (dry run)"
+        } else {
+            "Policy"
+        };
+        self.ui
+            .button(cx, &[id!(main_header), id!(policy_button)])
+            .set_text(cx, button_text);
+
+        let panel = self.ui.view(cx, &[id!(policy_panel)]);
+        let path = PermissionPolicy::path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "no config directory".to_string());
+        let mut status = match policy.rules.len() {
+            0 => format!("No rules. Add them to {}", path),
+            1 => format!("1 rule from {}", path),
+            n => format!("{} rules from {}", n, path),
+        };
+        for error in &policy.errors {
+            status.push_str("\n⚠ ");
+            status.push_str(error);
+        }
+        panel
+            .label(cx, &[id!(policy_status_label)])
+            .set_text(cx, &status);
+        panel.button(cx, &[id!(policy_dry_run_toggle)]).set_text(
+            cx,
+            if policy.dry_run {
+                "● Dry run"
+            } else {
+                "Dry run"
+            },
+        );
+
+        let audit = self
+            .state
+            .permission_audit
+            .iter()
+            .rev()
+            .take(AUDIT_ROWS_SHOWN)
+            .map(|entry| {
+                let session = self
+                    .state
+                    .find_session(&entry.session_id)
+                    .map(async_runtime::get_session_title)
+                    .unwrap_or_else(|| entry.session_id.clone());
+                format!(
+                    "{}  {}{} {} {}\n    {} · {}",
+                    crate::ui::format_timestamp(entry.time_ms),
+                    if entry.dry_run { "would " } else { "" },
+                    entry.action.label(),
+                    entry.permission,
+                    entry.patterns.join(", "),
+                    session,
+                    entry.rule
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        panel.label(cx, &[id!(policy_audit_label)]).set_text(
+            cx,
+            if audit.is_empty() {
+                "No automatic decisions yet."
+            } else {
+                &audit
+            },
+        );
+    }
+
+    pub(super) fn handle_policy_buttons(&mut self, cx: &mut Cx, actions: &Actions) {
+        let panel = self.ui.view(cx, &[id!(policy_panel)]);
+        if self
+            .ui
+            .button(cx, &[id!(main_header), id!(policy_button)])
+            .clicked(actions)
+        {
+            panel.set_visible(cx, !panel.visible());
+            self.refresh_policy_panel(cx);
+            self.ui.redraw(cx);
+        }
+        if panel
+            .button(cx, &[id!(policy_dry_run_toggle)])
+            .clicked(actions)
+        {
+            self.state.permission_policy.dry_run = !self.state.permission_policy.dry_run;
+            self.refresh_policy_panel(cx);
+            self.ui.redraw(cx);
+        }
+        if panel
+            .button(cx, &[id!(policy_reload_button)])
+            .clicked(actions)
+        {
+            self.reload_permission_policy();
+            self.refresh_policy_panel(cx);
+            self.ui.redraw(cx);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use super::*;
+use crate::state::reducer;
+use openpad_protocol::PermissionReply;
+
+const PERMISSION_TRAY_ROWS: [LiveId; 5] = [
+    live_id!(permission_row_0),
+    live_id!(permission_row_1),
+    live_id!(permission_row_2),
+    live_id!(permission_row_3),
+    live_id!(permission_row_4),
+];
+
+const METADATA_PREVIEW_CHARS: usize = 140;
+
+impl App {
+    /// Refresh the header count and the tray rows from `pending_permissions`.
+    pub(super) fn refresh_permission_tray(&mut self, cx: &mut Cx) {
+        let count = self.state.pending_permissions.len();
+        let visible_rows = count.min(PERMISSION_TRAY_ROWS.len());
+        self.permission_tray_selected = self
+            .permission_tray_selected
+            .min(visible_rows.saturating_sub(1));
+
+        let button = self
+            .ui
+            .button(cx, &[id!(main_header), id!(permissions_button)]);
+        button.set_visible(cx, count > 0);
+        let noun = if count == 1 {
+            "permission"
+        } else {
+            "permissions"
+        };
+        button.set_text(cx, &format!("⚠ {} {}", count, noun));
+
+        let tray = self.ui.view(cx, &[id!(permissions_tray)]);
+        if count == 0 {
+            tray.set_visible(cx, false);
+        }
+        for (i, row_id) in PERMISSION_TRAY_ROWS.iter().enumerate() {
+            let row = tray.view(cx, &[*row_id]);
+            let Some(request) = self.state.pending_permissions.get(i) else {
+                row.set_visible(cx, false);
+                continue;
+            };
+            let session = self
+                .state
+                .find_session(&request.session_id)
+                .map(async_runtime::get_session_title)
+                .unwrap_or_else(|| request.session_id.clone());
+            let marker = if i == self.permission_tray_selected {
+                "› "
+            } else {
+                ""
+            };
+            row.label(cx, &[id!(permission_session_label)])
+                .set_text(cx, &format!("{}{}", marker, session));
+            let patterns = if request.patterns.is_empty() {
+                String::new()
+            } else {
+                format!("  {}", request.patterns.join(", "))
+            };
+            row.label(cx, &[id!(permission_type_label)])
+                .set_text(cx, &format!("{}{}", request.permission, patterns));
+            let preview = reducer::permission_metadata_preview(request, METADATA_PREVIEW_CHARS);
+            row.label(cx, &[id!(permission_meta_label)])
+                .set_visible(cx, !preview.is_empty());
+            row.label(cx, &[id!(permission_meta_label)])
+                .set_text(cx, &preview);
+            row.set_visible(cx, true);
+        }
+        let hidden = count.saturating_sub(PERMISSION_TRAY_ROWS.len());
+        tray.label(cx, &[id!(permissions_more_label)])
+            .set_visible(cx, hidden > 0);
+        tray.label(cx, &[id!(permissions_more_label)])
+            .set_text(cx, &format!("+{} more waiting", hidden));
+        self.ui.redraw(cx);
+    }
+
+    fn reply_from_permission_tray(&mut self, cx: &mut Cx, index: usize, reply: PermissionReply) {
+        let Some(request) = self.state.pending_permissions.get(index).cloned() else {
+            return;
+        };
+        self.respond_to_permission(
+            cx,
+            request.session_id.clone(),
+            request.id.clone(),
+            reply.clone(),
+        );
+        state::handle_app_action(
+            &mut self.state,
+            &self.ui,
+            cx,
+            &AppAction::PermissionResponded {
+                session_id: request.session_id,
+                request_id: request.id,
+                reply,
+            },
+        );
+        self.refresh_open_center_tabs(cx);
+        self.sync_active_center_ui(cx);
+    }
+
+    pub(super) fn handle_permission_tray_buttons(&mut self, cx: &mut Cx, actions: &Actions) {
+        let tray = self.ui.view(cx, &[id!(permissions_tray)]);
+        if self
+            .ui
+            .button(cx, &[id!(main_header), id!(permissions_button)])
+            .clicked(actions)
+        {
+            tray.set_visible(cx, !tray.visible());
+            self.ui.redraw(cx);
+        }
+
+        for (i, row_id) in PERMISSION_TRAY_ROWS.iter().enumerate() {
+            let row = tray.view(cx, &[*row_id]);
+            let reply = if row
+                .button(cx, &[id!(permission_once_button)])
+                .clicked(actions)
+            {
+                PermissionReply::Once
+            } else if row
+                .button(cx, &[id!(permission_always_button)])
+                .clicked(actions)
+            {
+                PermissionReply::Always
+            } else if row
+                .button(cx, &[id!(permission_reject_button)])
+                .clicked(actions)
+            {
+                PermissionReply::Reject
+            } else {
+                continue;
+            };
+            self.permission_tray_selected = i;
+            self.reply_from_permission_tray(cx, i, reply);
+            break;
+        }
+    }
+
+    /// Cmd/Ctrl+Shift shortcuts while the tray is open: Up/Down pick a request,
+    /// Y allows once, A allows always and N rejects.
+    pub(super) fn handle_permission_tray_key(&mut self, cx: &mut Cx, ke: &KeyEvent) {
+        if !self.ui.view(cx, &[id!(permissions_tray)]).visible() {
+            return;
+        }
+        let rows = self
+            .state
+            .pending_permissions
+            .len()
+            .min(PERMISSION_TRAY_ROWS.len());
+        let selected = self.permission_tray_selected;
+        match ke.key_code {
+            KeyCode::ArrowUp => {
+                self.permission_tray_selected = selected.saturating_sub(1);
+                self.refresh_permission_tray(cx);
+            }
+            KeyCode::ArrowDown => {
+                self.permission_tray_selected = (selected + 1).min(rows.saturating_sub(1));
+                self.refresh_permission_tray(cx);
+            }
+            KeyCode::KeyY => self.reply_from_permission_tray(cx, selected, PermissionReply::Once),
+            KeyCode::KeyA => self.reply_from_permission_tray(cx, selected, PermissionReply::Always),
+            KeyCode::KeyN => self.reply_from_permission_tray(cx, selected, PermissionReply::Reject),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use super::*;
+use crate::components::quick_open::QuickOpenWidgetRefExt;
+use crate::state::QuickOpenState;
+
+impl App {
+    pub(super) fn open_quick_open(&mut self, cx: &mut Cx) {
+        let Some((project_id, directory)) = self.search_scope() else {
+            return;
+        };
+        self.ui
+            .quick_open(cx, &[id!(quick_open)])
+            .show(cx, &directory, self.state.file_access);
+        self.state.quick_open = QuickOpenState {
+            query: String::new(),
+            project_id,
+            directory,
+        };
+    }
+
+    pub(super) fn update_quick_open_query(&mut self, cx: &mut Cx, query: String) {
+        self.state.quick_open.query = query.clone();
+        let quick_open = self.ui.quick_open(cx, &[id!(quick_open)]);
+        if query.is_empty() {
+            quick_open.set_results(cx, &query, &[]);
+            return;
+        }
+        let Some(client) = self.client.clone() else {
+            return;
+        };
+        let Some(runtime) = self._runtime.as_ref() else {
+            return;
+        };
+        quick_open.set_searching(cx);
+        let directory = Some(self.state.quick_open.directory.clone());
+        async_runtime::spawn_file_finder(runtime, client, query, directory);
+    }
+
+    pub(super) fn load_quick_open_preview(&mut self, directory: String, file: String) {
+        let Some(client) = self.client.clone() else {
+            return;
+        };
+        let Some(runtime) = self._runtime.as_ref() else {
+            return;
+        };
+        async_runtime::spawn_quick_open_previewer(runtime, client, directory, file);
+    }
+
+    pub(super) fn open_quick_open_result(&mut self, cx: &mut Cx, relative_path: &str) {
+        let QuickOpenState {
+            project_id,
+            directory,
+            ..
+        } = std::mem::take(&mut self.state.quick_open);
+        let absolute_path = Path::new(&directory)
+            .join(relative_path)
+            .to_string_lossy()
+            .to_string();
+        self.queue_or_open_file(cx, project_id, absolute_path);
+    }
+}
@@ -0,0 +1,155 @@
+use super::*;
+use crate::state::RevertPreview;
+use openpad_protocol::SessionRevert;
+use openpad_widgets::diff_model::{parse_git_patch, patch_display_text};
+use openpad_widgets::diff_view::{DiffViewApi, DiffViewWidgetExt};
+
+fn revert_key(revert: &SessionRevert) -> (String, Option<String>, usize) {
+    (
+        revert.message_id.clone(),
+        revert.part_id.clone(),
+        revert.diff.as_ref().map_or(0, String::len),
+    )
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        format!("1 {}", word)
+    } else {
+        format!("{} {}s", count, word)
+    }
+}
+
+impl App {
+    /// Show what a pending revert rolled back above the chat. The patch is
+    /// parsed once per revert point, not on every refresh.
+    pub(super) fn render_revert_banner(&mut self, cx: &mut Cx, tab_id: LiveId, session_id: &str) {
+        let item = self.center_dock(cx).item(tab_id);
+        let Some(revert) = self
+            .state
+            .find_session(session_id)
+            .and_then(|session| session.revert.as_ref())
+        else {
+            self.state.revert_previews.remove(session_id);
+            item.view(cx, &[id!(revert_banner)]).set_visible(cx, false);
+            return;
+        };
+
+        let key = revert_key(revert);
+        let stale = self
+            .state
+            .revert_previews
+            .get(session_id)
+            .is_none_or(|preview| preview.revert_key != key);
+        if stale {
+            let files = parse_git_patch(revert.diff.as_deref().unwrap_or(""));
+            let diff_text = patch_display_text(&files);
+            self.state.revert_previews.insert(
+                session_id.to_string(),
+                RevertPreview {
+                    revert_key: key,
+                    files,
+                    diff_text,
+                    expanded: false,
+                },
+            );
+        }
+        let Some(preview) = self.state.revert_previews.get(session_id) else {
+            return;
+        };
+
+        let point = if preview.revert_key.1.is_some() {
+            "an earlier step"
+        } else {
+            "an earlier message"
+        };
+        let additions: usize = preview.files.iter().map(|file| file.additions).sum();
+        let deletions: usize = preview.files.iter().map(|file| file.deletions).sum();
+        let summary = if preview.files.is_empty() {
+            format!("Reverted to {}", point)
+        } else {
+            format!(
+                "Reverted to {} · {} rolled back (+{} -{})",
+                point,
+                plural(preview.files.len(), "file"),
+                additions,
+                deletions
+            )
+        };
+        let files = preview
+            .files
+            .iter()
+            .map(|file| format!("{} +{} -{}", file.path, file.additions, file.deletions))
+            .collect::<Vec<_>>()
+            .join("   ");
+
+        item.view(cx, &[id!(revert_banner)]).set_visible(cx, true);
+        item.label(cx, &[id!(revert_banner_label)])
+            .set_text(cx, &summary);
+        item.label(cx, &[id!(revert_files_label)])
+            .set_text(cx, &files);
+        item.widget(cx, &[id!(revert_files_label)])
+            .set_visible(cx, !files.is_empty());
+
+        let has_diff = !preview.diff_text.is_empty();
+        item.button(cx, &[id!(revert_diff_button)])
+            .set_visible(cx, has_diff);
+        item.button(cx, &[id!(revert_diff_button)]).set_text(
+            cx,
+            if preview.expanded {
+                "Hide diff"
+            } else {
+                "Show diff"
+            },
+        );
+        item.view(cx, &[id!(revert_diff_scroll)])
+            .set_visible(cx, has_diff && preview.expanded);
+        if has_diff && preview.expanded {
+            let diff_view = item.diff_view(cx, &[id!(revert_diff_view)]);
+            diff_view.set_diff_text(
+                cx,
+                &plural(preview.files.len(), "file"),
+                &format!("+{}", additions),
+                &format!("-{}", deletions),
+                &preview.diff_text,
+            );
+            diff_view.set_expanded(cx, true);
+        }
+    }
+
+    /// Asks before making the revert permanent; `handle_dialog_confirmed`
+    /// commits it on `commit_revert:<session_id>`.
+    fn confirm_commit_revert(&mut self, cx: &mut Cx, session_id: &str) {
+        self.ui.simple_dialog(cx, &[id!(simple_dialog)]).show_confirm(
+            cx,
+            "Commit Revert",
+            "Permanently delete the reverted messages? Unrevert will no longer be possible. A short note is added to the session.",
+            format!("commit_revert:{}", session_id),
+        );
+    }
+
+    pub(super) fn handle_revert_banner_buttons(&mut self, cx: &mut Cx, actions: &Actions) {
+        let reverted_tabs: Vec<(LiveId, String)> = self
+            .state
+            .tab_by_session
+            .iter()
+            .filter(|(session_id, _)| self.state.revert_previews.contains_key(*session_id))
+            .map(|(session_id, tab_id)| (*tab_id, session_id.clone()))
+            .collect();
+        for (tab_id, session_id) in reverted_tabs {
+            let item = self.center_dock(cx).item(tab_id);
+            if item.button(cx, &[id!(revert_diff_button)]).clicked(actions) {
+                if let Some(preview) = self.state.revert_previews.get_mut(&session_id) {
+                    preview.expanded = !preview.expanded;
+                }
+                self.render_revert_banner(cx, tab_id, &session_id);
+            }
+            if item
+                .button(cx, &[id!(commit_revert_button)])
+                .clicked(actions)
+            {
+                self.confirm_commit_revert(cx, &session_id);
+            }
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use super::*;
+use crate::state::{reducer, RulesTarget};
+use openpad_protocol::PermissionAction;
+
+const RULE_ROWS: [LiveId; 10] = [
+    live_id!(rule_row_0),
+    live_id!(rule_row_1),
+    live_id!(rule_row_2),
+    live_id!(rule_row_3),
+    live_id!(rule_row_4),
+    live_id!(rule_row_5),
+    live_id!(rule_row_6),
+    live_id!(rule_row_7),
+    live_id!(rule_row_8),
+    live_id!(rule_row_9),
+];
+
+fn action_label(action: PermissionAction) -> &'static str {
+    match action {
+        PermissionAction::Allow => "allow",
+        PermissionAction::Ask => "ask",
+        PermissionAction::Deny => "deny",
+    }
+}
+
+impl App {
+    pub(super) fn refresh_rules_editor(&self, cx: &mut Cx) {
+        let panel = self.ui.view(cx, &[id!(rules_editor)]);
+        let editor = &self.state.rules_editor;
+        panel
+            .button(cx, &[id!(rules_target_button)])
+            .set_text(cx, &format!("{} ▾", editor.target.label()));
+
+        for (i, row_id) in RULE_ROWS.iter().enumerate() {
+            let row = panel.view(cx, &[*row_id]);
+            let index = editor.offset + i;
+            let Some(rule) = editor.rules.get(index) else {
+                row.set_visible(cx, false);
+                continue;
+            };
+            row.label(cx, &[id!(rule_label)]).set_text(
+                cx,
+                &format!("{:>2}. {}  {}", index + 1, rule.permission, rule.pattern),
+            );
+            row.button(cx, &[id!(rule_action_button)])
+                .set_text(cx, action_label(rule.action));
+            row.set_visible(cx, true);
+        }
+
+        let total = editor.rules.len();
+        let page = if total == 0 {
+            "No rules".to_string()
+        } else {
+            let last = (editor.offset + RULE_ROWS.len()).min(total);
+            format!("{}–{} of {}", editor.offset + 1, last, total)
+        };
+        panel
+            .label(cx, &[id!(rules_page_label)])
+            .set_text(cx, &page);
+        panel
+            .button(cx, &[id!(rules_prev_button)])
+            .set_visible(cx, editor.offset > 0);
+        panel
+            .button(cx, &[id!(rules_next_button)])
+            .set_visible(cx, editor.offset + RULE_ROWS.len() < total);
+
+        let status = match &editor.status {
+            Some(status) => status.clone(),
+            None if editor.target == RulesTarget::NewSessions
+                && self.state.session_permission_override.is_none() =>
+            {
+                "New sessions use the selected agent's rules.".to_string()
+            }
+            None if editor.target != RulesTarget::NewSessions => {
+                "Rules from this agent's config; the server's defaults apply before them."
+                    .to_string()
+            }
+            None => String::new(),
+        };
+        panel
+            .label(cx, &[id!(rules_status_label)])
+            .set_text(cx, &status);
+    }
+
+    fn apply_rules_editor(&mut self) {
+        let Some((agent, rules)) = reducer::apply_rules_editor(&mut self.state) else {
+            return;
+        };
+        let Some(client) = self.client_or_error() else {
+            return;
+        };
+        let Some(runtime) = self._runtime.as_ref() else {
+            return;
+        };
+        async_runtime::spawn_agent_permission_updater(runtime, client, agent, rules);
+    }
+
+    fn add_rule_from_inputs(&mut self, cx: &mut Cx) {
+        let panel = self.ui.view(cx, &[id!(rules_editor)]);
+        let permission = panel.text_input(cx, &[id!(rules_permission_input)]).text();
+        if permission.trim().is_empty() {
+            self.state.rules_editor.status =
+                Some("Enter a permission such as bash, edit or read.".to_string());
+            return;
+        }
+        let pattern = panel.text_input(cx, &[id!(rules_pattern_input)]).text();
+        let editor = &mut self.state.rules_editor;
+        editor.add_rule(&permission, &pattern, PermissionAction::Ask);
+        editor.offset = (editor.rules.len() - 1) / RULE_ROWS.len() * RULE_ROWS.len();
+        editor.status = None;
+        panel
+            .text_input(cx, &[id!(rules_permission_input)])
+            .set_text(cx, "");
+        panel
+            .text_input(cx, &[id!(rules_pattern_input)])
+            .set_text(cx, "");
+    }
+
+    pub(super) fn handle_rules_editor_buttons(&mut self, cx: &mut Cx, actions: &Actions) {
+        let panel = self.ui.view(cx, &[id!(rules_editor)]);
+        let mut changed = false;
+
+        if self
+            .ui
+            .button(cx, &[id!(main_header), id!(rules_button)])
+            .clicked(actions)
+        {
+            let open = !panel.visible();
+            if open {
+                let target = self.state.rules_editor.target.clone();
+                reducer::load_rules_editor(&mut self.state, target);
+            }
+            panel.set_visible(cx, open);
+            changed = true;
+        }
+        if panel
+            .button(cx, &[id!(rules_target_button)])
+            .clicked(actions)
+        {
+            let target = reducer::next_rules_target(&self.state);
+            reducer::load_rules_editor(&mut self.state, target);
+            changed = true;
+        }
+        if panel
+            .button(cx, &[id!(rules_reset_button)])
+            .clicked(actions)
+        {
+            let target = self.state.rules_editor.target.clone();
+            if target == RulesTarget::NewSessions {
+                self.state.session_permission_override = None;
+            }
+            reducer::load_rules_editor(&mut self.state, target);
+            changed = true;
+        }
+        if panel
+            .button(cx, &[id!(rules_apply_button)])
+            .clicked(actions)
+        {
+            self.apply_rules_editor();
+            changed = true;
+        }
+        if panel.button(cx, &[id!(rules_add_button)]).clicked(actions) {
+            self.add_rule_from_inputs(cx);
+            changed = true;
+        }
+
+        let editor = &mut self.state.rules_editor;
+        if panel.button(cx, &[id!(rules_prev_button)]).clicked(actions) {
+            editor.offset = editor.offset.saturating_sub(RULE_ROWS.len());
+            changed = true;
+        }
+        if panel.button(cx, &[id!(rules_next_button)]).clicked(actions)
+            && editor.offset + RULE_ROWS.len() < editor.rules.len()
+        {
+            editor.offset += RULE_ROWS.len();
+            changed = true;
+        }
+        for (i, row_id) in RULE_ROWS.iter().enumerate() {
+            let row = panel.view(cx, &[*row_id]);
+            let index = editor.offset + i;
+            if row.button(cx, &[id!(rule_action_button)]).clicked(actions) {
+                editor.cycle_action(index);
+            } else if row.button(cx, &[id!(rule_up_button)]).clicked(actions) {
+                editor.move_rule(index, true);
+            } else if row.button(cx, &[id!(rule_down_button)]).clicked(actions) {
+                editor.move_rule(index, false);
+            } else if row.button(cx, &[id!(rule_delete_button)]).clicked(actions) {
+                editor.remove_rule(index);
+                if editor.offset >= editor.rules.len() {
+                    editor.offset = editor.offset.saturating_sub(RULE_ROWS.len());
+                }
+            } else {
+                continue;
+            }
+            editor.status = None;
+            changed = true;
+            break;
+        }
+
+        if changed {
+            self.refresh_rules_editor(cx);
+            self.ui.redraw(cx);
+        }
+    }
+}
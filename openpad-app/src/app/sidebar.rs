@@ -1,4 +1,7 @@
 use super::*;
+use crate::components::files_panel::FilesPanelWidgetRefExt;
+use crate::components::search_panel::SearchPanelWidgetRefExt;
+use crate::state::TextSearchState;
 
 /// Shared resize hit-testing helper. Processes a drag-resize interaction for a sidebar handle.
 /// Returns `Some(new_width)` when the width should be updated, `None` otherwise.
@@ -71,6 +74,7 @@ impl App {
 
     pub(super) fn update_sidebar_panel_visibility(&mut self, cx: &mut Cx) {
         let show_files = self.sidebar_mode == SidebarMode::Files;
+        let show_search = self.sidebar_mode == SidebarMode::Search;
         let show_settings = self.sidebar_mode == SidebarMode::Settings;
 
         // Update the header active tab state
@@ -79,10 +83,16 @@ impl App {
             .sidebar_header(cx, &[id!(side_panel), id!(sidebar_header)])
             .set_mode(cx, self.sidebar_mode);
 
-        // Use widget() for custom widgets (FilesPanel, SettingsDialog).
+        // Use widget() for custom widgets (FilesPanel, SearchPanel, SettingsDialog).
         self.ui
             .widget(cx, &[id!(side_panel), id!(files_panel)])
             .set_visible(cx, show_files);
+        self.ui
+            .view(cx, &[id!(side_panel), id!(files_toolbar)])
+            .set_visible(cx, show_files);
+        self.ui
+            .widget(cx, &[id!(side_panel), id!(search_panel)])
+            .set_visible(cx, show_search);
         self.ui
             .widget(cx, &[id!(side_panel), id!(settings_panel)])
             .set_visible(cx, show_settings);
@@ -190,4 +200,186 @@ impl App {
             self.right_sidebar_drag_start = drag_start_mut;
         }
     }
+
+    /// Scope for project-wide search: the current session's project, else the current project.
+    pub(super) fn search_scope(&self) -> Option<(String, String)> {
+        let project = self
+            .state
+            .project_for_current_session()
+            .or(self.state.current_project.as_ref())?;
+        Some((
+            project.id.clone(),
+            Self::normalize_project_directory(&project.worktree),
+        ))
+    }
+
+    pub(super) fn start_text_search(&mut self, cx: &mut Cx, query: String) {
+        let Some(client) = self.client_or_error() else {
+            return;
+        };
+        let Some(runtime) = self._runtime.as_ref() else {
+            return;
+        };
+        let Some((project_id, directory)) = self.search_scope() else {
+            return;
+        };
+
+        self.state.text_search = TextSearchState {
+            query: query.clone(),
+            project_id,
+            directory: directory.clone(),
+            loading: true,
+            groups: Vec::new(),
+        };
+        self.ui
+            .search_panel(cx, &[id!(side_panel), id!(search_panel)])
+            .set_loading(cx, &query);
+        async_runtime::spawn_text_searcher(runtime, client, query, Some(directory));
+    }
+
+    /// Fetch git status for a project's files panel. If a request is already in
+    /// flight, the project is marked stale and reloaded once it lands.
+    pub(super) fn load_file_status(&mut self, project_id: &str) {
+        if self.state.file_status_in_flight.contains(project_id) {
+            self.state.file_status_stale.insert(project_id.to_string());
+            return;
+        }
+        let Some(client) = self.client.clone() else {
+            return;
+        };
+        let Some(runtime) = self._runtime.as_ref() else {
+            return;
+        };
+        let Some(project) = self.state.projects.iter().find(|p| p.id == project_id) else {
+            return;
+        };
+        let directory = Self::normalize_project_directory(&project.worktree);
+        self.state
+            .file_status_in_flight
+            .insert(project_id.to_string());
+        async_runtime::spawn_file_status_loader(runtime, client, project_id.to_string(), directory);
+    }
+
+    /// Fetch a files panel listing from the server when the project isn't on this machine.
+    pub(super) fn list_remote_directory(&mut self, project_id: String, directory: String) {
+        let Some(client) = self.client.clone() else {
+            return;
+        };
+        let Some(runtime) = self._runtime.as_ref() else {
+            return;
+        };
+        let Some(project) = self.state.projects.iter().find(|p| p.id == project_id) else {
+            return;
+        };
+        let root = project.worktree.clone();
+        async_runtime::spawn_remote_dir_lister(runtime, client, project_id, root, directory);
+    }
+
+    /// Refresh git status for the projects containing `path`, or all projects
+    /// when the path doesn't belong to a known worktree.
+    pub(super) fn refresh_file_status_for_path(&mut self, path: &str) {
+        let projects: Vec<(String, String)> = self
+            .state
+            .projects
+            .iter()
+            .filter(|p| !p.worktree.is_empty() && p.worktree != "/")
+            .map(|p| (p.id.clone(), Self::normalize_project_directory(&p.worktree)))
+            .collect();
+        let owners: Vec<String> = projects
+            .iter()
+            .filter(|(_, dir)| Path::new(path).starts_with(dir))
+            .map(|(id, _)| id.clone())
+            .collect();
+        let targets = if owners.is_empty() {
+            projects.into_iter().map(|(id, _)| id).collect()
+        } else {
+            owners
+        };
+        for project_id in targets {
+            self.load_file_status(&project_id);
+        }
+    }
+
+    pub(super) fn toggle_changed_files_only(&mut self, cx: &mut Cx) {
+        self.files_changed_only = !self.files_changed_only;
+        self.ui
+            .files_panel(cx, &[id!(side_panel), id!(files_panel)])
+            .set_changed_only(cx, self.files_changed_only);
+        let label = if self.files_changed_only {
+            "Changed files only"
+        } else {
+            "All files"
+        };
+        self.ui
+            .button(cx, &[id!(side_panel), id!(changed_only_toggle)])
+            .set_text(cx, label);
+    }
+
+    /// Filter box, quick filters, sort and grouping above the sessions tree.
+    pub(super) fn handle_session_filter_bar(&mut self, cx: &mut Cx, actions: &Actions) {
+        let toolbar = self
+            .ui
+            .view(cx, &[id!(right_side_panel), id!(sessions_toolbar)]);
+        let mut changed = false;
+        if let Some(query) = toolbar
+            .text_input(cx, &[id!(session_filter_input)])
+            .changed(actions)
+        {
+            self.state.session_filter.query = query;
+            changed = true;
+        }
+
+        let filter = &mut self.state.session_filter;
+        let toggles = [
+            (id!(filter_working_toggle), "Working", &mut filter.working),
+            (
+                id!(filter_changes_toggle),
+                "Changes",
+                &mut filter.has_changes,
+            ),
+            (id!(filter_shared_toggle), "Shared", &mut filter.shared),
+            (
+                id!(filter_permission_toggle),
+                "Permission",
+                &mut filter.pending_permission,
+            ),
+        ];
+        for (button_id, text, enabled) in toggles {
+            let button = toolbar.button(cx, &[button_id]);
+            if button.clicked(actions) {
+                *enabled = !*enabled;
+                let label = if *enabled {
+                    format!("● {}", text)
+                } else {
+                    text.to_string()
+                };
+                button.set_text(cx, &label);
+                changed = true;
+            }
+        }
+
+        let sort_button = toolbar.button(cx, &[id!(session_sort_button)]);
+        if sort_button.clicked(actions) {
+            filter.sort = filter.sort.next();
+            sort_button.set_text(cx, filter.sort.label());
+            changed = true;
+        }
+        let group_button = toolbar.button(cx, &[id!(session_group_button)]);
+        if group_button.clicked(actions) {
+            filter.group_by_date = !filter.group_by_date;
+            group_button.set_text(
+                cx,
+                if filter.group_by_date {
+                    "By date"
+                } else {
+                    "By project"
+                },
+            );
+            changed = true;
+        }
+
+        if changed {
+            self.state.update_sessions_panel(&self.ui, cx);
+        }
+    }
 }
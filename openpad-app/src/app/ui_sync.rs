@@ -63,7 +63,7 @@ impl App {
                     .view(cx, &[id!(chat_composer)])
                     .set_visible(cx, false);
             }
-            Some(CenterTabKind::Home) | None => {
+            Some(CenterTabKind::Diff { .. }) | Some(CenterTabKind::Home) | None => {
                 self.ui
                     .view(cx, &[id!(session_info)])
                     .set_visible(cx, false);
@@ -100,9 +100,39 @@ impl App {
             .set_text(cx, &open_file.absolute_path);
         let current_text = item.editor_panel(cx, &[id!(editor_panel)]).get_text();
         let is_dirty = current_text != open_file.text_cache;
-        self.ui
-            .label(cx, &[id!(editor_dirty_dot)])
-            .set_text(cx, if is_dirty { "●" } else { "" });
+        self.ui.label(cx, &[id!(editor_dirty_dot)]).set_text(
+            cx,
+            if open_file.read_only {
+                "read-only"
+            } else if is_dirty {
+                "●"
+            } else {
+                ""
+            },
+        );
+        item.view(cx, &[id!(external_change_bar)])
+            .set_visible(cx, open_file.external_text.is_some());
+        let conflict = open_file.pending_merge.as_ref().and_then(|pending| {
+            pending
+                .current()
+                .map(|(index, mine, disk)| (index, pending.result.conflicts(), mine, disk))
+        });
+        let message = match conflict {
+            Some((index, count, mine, disk)) => format!(
+                "Merge conflict {} of {}: mine {} · disk {}",
+                index + 1,
+                count,
+                conflict_preview(mine),
+                conflict_preview(disk)
+            ),
+            None => "This file changed on disk while you had unsaved edits.".to_string(),
+        };
+        item.label(cx, &[id!(external_change_label)])
+            .set_text(cx, &message);
+        item.view(cx, &[id!(external_choice_buttons)])
+            .set_visible(cx, conflict.is_none());
+        item.view(cx, &[id!(merge_conflict_buttons)])
+            .set_visible(cx, conflict.is_some());
     }
 
     pub(super) fn render_chat_tab(&mut self, cx: &mut Cx, tab_id: LiveId, session_id: &str) {
@@ -136,6 +166,12 @@ impl App {
                     request_id: p.id.clone(),
                     permission: p.permission.clone(),
                     patterns: p.patterns.clone(),
+                    always: p.always.clone(),
+                    preview: openpad_widgets::message_logic::PermissionPreview::from_request(
+                        &p.permission,
+                        &p.patterns,
+                        &p.metadata,
+                    ),
                 },
             )
             .collect();
@@ -166,6 +202,7 @@ impl App {
             item.message_list(cx, &[id!(message_list)])
                 .set_session_diffs(cx, &[]);
         }
+        self.render_revert_banner(cx, tab_id, session_id);
     }
 
     pub(super) fn refresh_open_center_tabs(&mut self, cx: &mut Cx) {
@@ -179,7 +216,8 @@ impl App {
             match kind {
                 CenterTabKind::Chat { session_id } => self.render_chat_tab(cx, tab_id, &session_id),
                 CenterTabKind::File { .. } => self.update_editor_header_ui_for_tab(cx, tab_id),
-                CenterTabKind::Home => {}
+                // Re-rendered on activation and diff reloads, see `render_diff_tab`.
+                CenterTabKind::Diff { .. } | CenterTabKind::Home => {}
             }
         }
     }
@@ -191,6 +229,8 @@ impl App {
             Some(CenterTabKind::Chat { session_id }) => {
                 self.state.current_session_id = Some(session_id.clone());
                 self.state.selected_session_id = Some(session_id.clone());
+                self.state.attention_by_session.remove(&session_id);
+                self.sync_composer_selection(cx, &session_id);
                 self.state.update_files_panel(&self.ui, cx);
                 self.state.update_sessions_panel(&self.ui, cx);
                 self.state.update_session_title_ui(&self.ui, cx);
@@ -211,10 +251,34 @@ impl App {
                     self.update_editor_header_ui_for_tab(cx, tab_id);
                 }
             }
-            Some(CenterTabKind::Home) | None => {
+            Some(CenterTabKind::Diff { .. }) | Some(CenterTabKind::Home) | None => {
                 self.state.current_session_id = None;
                 crate::ui::state_updates::update_work_indicator(&self.ui, cx, false);
             }
         }
+        self.refresh_attention_ui(cx);
+        self.refresh_permission_tray(cx);
+        self.refresh_policy_panel(cx);
+        self.refresh_rules_editor(cx);
+    }
+}
+
+/// First non-blank line of one side of a conflict, for the external change bar.
+fn conflict_preview(text: &str) -> String {
+    let lines = text.lines().count();
+    let Some(first) = text.lines().map(str::trim).find(|line| !line.is_empty()) else {
+        return if lines == 0 {
+            "(removed)".to_string()
+        } else {
+            "(blank lines)".to_string()
+        };
+    };
+    let mut preview: String = first.chars().take(40).collect();
+    if first.chars().count() > 40 {
+        preview.push('…');
+    }
+    if lines > 1 {
+        preview.push_str(&format!(" (+{} lines)", lines - 1));
     }
+    format!("“{}”", preview)
 }
@@ -1,11 +1,16 @@
 pub mod tasks;
 
 pub use tasks::{
-    get_session_title, spawn_agents_loader, spawn_all_sessions_loader, spawn_auth_setter,
-    spawn_config_loader, spawn_health_checker, spawn_message_loader, spawn_message_reverter,
-    spawn_message_sender, spawn_pending_permissions_loader, spawn_permission_reply,
-    spawn_project_loader, spawn_providers_loader, spawn_session_aborter, spawn_session_brancher,
+    get_session_title, spawn_agent_permission_updater, spawn_agents_loader,
+    spawn_all_sessions_loader, spawn_auth_setter, spawn_config_loader, spawn_file_finder,
+    spawn_file_status_loader, spawn_health_checker, spawn_message_loader, spawn_message_reverter,
+    spawn_message_sender, spawn_path_loader, spawn_pending_permissions_loader,
+    spawn_permission_reply, spawn_permission_session_loader, spawn_project_loader,
+    spawn_providers_loader, spawn_quick_open_previewer, spawn_remote_dir_lister,
+    spawn_remote_file_reader, spawn_revert_committer, spawn_session_aborter,
+    spawn_session_archiver, spawn_session_brancher, spawn_session_children_loader,
     spawn_session_creator, spawn_session_deleter, spawn_session_diff_loader, spawn_session_sharer,
     spawn_session_summarizer, spawn_session_unreverter, spawn_session_unsharer,
-    spawn_session_updater, spawn_skills_loader, spawn_sse_subscriber,
+    spawn_session_updater, spawn_skills_loader, spawn_sse_subscriber, spawn_symbol_searcher,
+    spawn_text_searcher,
 };
@@ -1,4 +1,7 @@
-use crate::constants::{HEALTH_CHECK_INTERVAL_SECS, OPENCODE_SERVER_URL, SSE_RETRY_DELAY_SECS};
+use crate::constants::{
+    HEALTH_CHECK_INTERVAL_SECS, OPENCODE_SERVER_URL, QUICK_OPEN_FILE_LIMIT, SSE_RETRY_DELAY_SECS,
+};
+use crate::file_access;
 use crate::state::actions::AppAction;
 use crate::utils::path_utils::normalize_worktree_canonical;
 use makepad_widgets::{log, Cx};
@@ -124,6 +127,28 @@ pub fn spawn_all_sessions_loader(
     });
 }
 
+/// Spawns a task to load the child sessions (branches and subagent runs) of a session
+pub fn spawn_session_children_loader(
+    runtime: &tokio::runtime::Runtime,
+    client: Arc<OpenCodeClient>,
+    session_id: String,
+    directory: Option<String>,
+) {
+    runtime.spawn(async move {
+        let target_client = get_directory_client(client, directory);
+
+        match target_client.get_session_children(&session_id).await {
+            Ok(children) if !children.is_empty() => {
+                Cx::post_action(AppAction::SessionChildrenLoaded(children));
+            }
+            Ok(_) => {}
+            Err(e) => {
+                log!("Failed to load child sessions of {}: {}", session_id, e);
+            }
+        }
+    });
+}
+
 /// Spawns a task to load messages for a session
 pub fn spawn_message_loader(
     runtime: &tokio::runtime::Runtime,
@@ -148,12 +173,14 @@ pub fn spawn_message_loader(
 }
 
 /// Spawns a task to send a message (creating session if needed)
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_message_sender(
     runtime: &tokio::runtime::Runtime,
     client: Arc<OpenCodeClient>,
     session_id: Option<String>,
     text: String,
     model_spec: Option<ModelSpec>,
+    variant: Option<String>,
     agent: Option<String>,
     system: Option<String>,
     directory: Option<String>,
@@ -201,6 +228,7 @@ pub fn spawn_message_sender(
             system,
             parts,
             no_reply: None,
+            variant,
         };
         if let Err(e) = target_client.send_prompt_with_options(&sid, request).await {
             log!("Failed to send prompt on session {}: {}", sid, e);
@@ -300,6 +328,37 @@ pub fn spawn_pending_permissions_loader(
     });
 }
 
+/// Spawns a task to fetch the session of a permission request that arrived
+/// before the session itself. Sessions live per project, so each project
+/// directory is tried after the default one.
+pub fn spawn_permission_session_loader(
+    runtime: &tokio::runtime::Runtime,
+    client: Arc<OpenCodeClient>,
+    session_id: String,
+    directories: Vec<String>,
+) {
+    runtime.spawn(async move {
+        let mut session = client.get_session(&session_id).await.ok();
+        for directory in directories {
+            if session.is_some() {
+                break;
+            }
+            let target_client = get_directory_client(client.clone(), Some(directory));
+            session = target_client.get_session(&session_id).await.ok();
+        }
+        if session.is_none() {
+            log!(
+                "Could not find session {} for its permission request",
+                session_id
+            );
+        }
+        Cx::post_action(AppAction::PermissionSessionResolved {
+            session_id,
+            session,
+        });
+    });
+}
+
 /// Helper to get or create a session title from a session
 pub fn get_session_title(session: &Session) -> String {
     if !session.title.is_empty() {
@@ -353,6 +412,202 @@ pub fn spawn_skills_loader(runtime: &tokio::runtime::Runtime, client: Arc<OpenCo
     });
 }
 
+/// Spawns a task to run a project-wide text search
+pub fn spawn_text_searcher(
+    runtime: &tokio::runtime::Runtime,
+    client: Arc<OpenCodeClient>,
+    pattern: String,
+    directory: Option<String>,
+) {
+    use openpad_protocol::TextSearchRequest;
+
+    runtime.spawn(async move {
+        let target_client = get_directory_client(client, directory);
+        let request = TextSearchRequest {
+            pattern: pattern.clone(),
+        };
+        match target_client.search_text(request).await {
+            Ok(results) => {
+                Cx::post_action(AppAction::TextSearchCompleted {
+                    query: pattern,
+                    results,
+                });
+            }
+            Err(e) => {
+                post_error_action("Text search failed", e);
+                Cx::post_action(AppAction::TextSearchCompleted {
+                    query: pattern,
+                    results: Vec::new(),
+                });
+            }
+        }
+    });
+}
+
+/// Spawns a task to load working-tree git status for a project's files panel
+pub fn spawn_file_status_loader(
+    runtime: &tokio::runtime::Runtime,
+    client: Arc<OpenCodeClient>,
+    project_id: String,
+    directory: String,
+) {
+    runtime.spawn(async move {
+        let target_client = get_directory_client(client, Some(directory));
+        match target_client.get_file_status(None).await {
+            Ok(files) => {
+                Cx::post_action(AppAction::FileStatusLoaded { project_id, files });
+            }
+            Err(e) => {
+                log!("Failed to load file status for {}: {}", project_id, e);
+                Cx::post_action(AppAction::FileStatusLoaded {
+                    project_id,
+                    files: Vec::new(),
+                });
+            }
+        }
+    });
+}
+
+/// Spawns a task to find files by name for the quick-open finder
+pub fn spawn_file_finder(
+    runtime: &tokio::runtime::Runtime,
+    client: Arc<OpenCodeClient>,
+    query: String,
+    directory: Option<String>,
+) {
+    use openpad_protocol::FilesSearchRequest;
+
+    runtime.spawn(async move {
+        let target_client = get_directory_client(client, directory);
+        let request = FilesSearchRequest {
+            query: query.clone(),
+            type_filter: Some("file".to_string()),
+            directory: None,
+            limit: Some(QUICK_OPEN_FILE_LIMIT),
+            dirs: Some(false),
+        };
+        match target_client.search_files(request).await {
+            Ok(files) => {
+                Cx::post_action(AppAction::FilesFound { query, files });
+            }
+            Err(e) => {
+                log!("Failed to find files for {:?}: {}", query, e);
+                Cx::post_action(AppAction::FilesFound {
+                    query,
+                    files: Vec::new(),
+                });
+            }
+        }
+    });
+}
+
+/// Spawns a task to fetch the server's working paths
+pub fn spawn_path_loader(runtime: &tokio::runtime::Runtime, client: Arc<OpenCodeClient>) {
+    runtime.spawn(async move {
+        match client.get_path().await {
+            Ok(info) => {
+                Cx::post_action(AppAction::ServerPathLoaded(info));
+            }
+            Err(e) => {
+                log!("Failed to load server path: {}", e);
+            }
+        }
+    });
+}
+
+/// Spawns a task to list a directory through the server's file API
+pub fn spawn_remote_dir_lister(
+    runtime: &tokio::runtime::Runtime,
+    client: Arc<OpenCodeClient>,
+    project_id: String,
+    root: String,
+    directory: String,
+) {
+    runtime.spawn(async move {
+        let target_client = get_directory_client(client, Some(root.clone()));
+        let entries = match file_access::list_server_dir(&target_client, &root, &directory).await {
+            Ok(entries) => Some(entries),
+            Err(e) => {
+                post_error_action(&format!("Failed to list {}", directory), e);
+                None
+            }
+        };
+        Cx::post_action(AppAction::RemoteDirectoryListed {
+            project_id,
+            directory,
+            entries,
+        });
+    });
+}
+
+/// Spawns a task to read a file through the server's file API
+pub fn spawn_remote_file_reader(
+    runtime: &tokio::runtime::Runtime,
+    client: Arc<OpenCodeClient>,
+    project_id: String,
+    root: String,
+    absolute_path: String,
+    line: Option<usize>,
+) {
+    runtime.spawn(async move {
+        let target_client = get_directory_client(client, Some(root.clone()));
+        let result = file_access::read_server_text(&target_client, &root, &absolute_path).await;
+        Cx::post_action(AppAction::RemoteFileLoaded {
+            project_id,
+            absolute_path,
+            line,
+            result,
+        });
+    });
+}
+
+/// Spawns a task to read a quick open preview through the server's file API
+pub fn spawn_quick_open_previewer(
+    runtime: &tokio::runtime::Runtime,
+    client: Arc<OpenCodeClient>,
+    directory: String,
+    file: String,
+) {
+    runtime.spawn(async move {
+        let target_client = get_directory_client(client, Some(directory.clone()));
+        let path = std::path::Path::new(&directory)
+            .join(&file)
+            .to_string_lossy()
+            .to_string();
+        let result = file_access::read_server_text(&target_client, &directory, &path).await;
+        Cx::post_action(AppAction::QuickOpenPreviewLoaded { file, result });
+    });
+}
+
+/// Spawns a task to search workspace symbols for the composer's `#` trigger
+pub fn spawn_symbol_searcher(
+    runtime: &tokio::runtime::Runtime,
+    client: Arc<OpenCodeClient>,
+    query: String,
+    directory: Option<String>,
+) {
+    use openpad_protocol::SymbolsSearchRequest;
+
+    runtime.spawn(async move {
+        let target_client = get_directory_client(client, directory);
+        let request = SymbolsSearchRequest {
+            query: query.clone(),
+        };
+        match target_client.search_symbols(request).await {
+            Ok(symbols) => {
+                Cx::post_action(AppAction::SymbolsLoaded { query, symbols });
+            }
+            Err(e) => {
+                log!("Failed to search symbols for {:?}: {}", query, e);
+                Cx::post_action(AppAction::SymbolsLoaded {
+                    query,
+                    symbols: Vec::new(),
+                });
+            }
+        }
+    });
+}
+
 /// Spawns a task to delete a session
 pub fn spawn_session_deleter(
     runtime: &tokio::runtime::Runtime,
@@ -402,6 +657,50 @@ pub fn spawn_session_updater(
     });
 }
 
+/// Spawns a task to archive or unarchive a session. Archived sessions stay
+/// on the server and can be restored later.
+pub fn spawn_session_archiver(
+    runtime: &tokio::runtime::Runtime,
+    client: Arc<OpenCodeClient>,
+    session_id: String,
+    archived: bool,
+    directory: Option<String>,
+) {
+    use openpad_protocol::{SessionUpdateRequest, SessionUpdateTime};
+
+    runtime.spawn(async move {
+        let target_client = get_directory_client(client, directory);
+        // Unarchiving sends 0; the API has no way to clear the archive time.
+        let archived_at = if archived {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis() as f64)
+                .unwrap_or_default()
+        } else {
+            0.0
+        };
+        let request = SessionUpdateRequest {
+            title: None,
+            time: Some(SessionUpdateTime {
+                archived: archived_at,
+            }),
+        };
+        match target_client.update_session(&session_id, request).await {
+            Ok(session) => {
+                Cx::post_action(AppAction::SessionUpdated(session));
+            }
+            Err(e) => {
+                let context = if archived {
+                    "Failed to archive session"
+                } else {
+                    "Failed to unarchive session"
+                };
+                post_error_action(context, e);
+            }
+        }
+    });
+}
+
 /// Spawns a task to abort an ongoing session
 pub fn spawn_session_aborter(
     runtime: &tokio::runtime::Runtime,
@@ -535,12 +834,14 @@ pub fn spawn_session_diff_loader(
     });
 }
 
-/// Spawns a task to revert session to a specific message
+/// Spawns a task to revert session to a specific message, or to one of its
+/// parts (a step checkpoint) when `part_id` is set
 pub fn spawn_message_reverter(
     runtime: &tokio::runtime::Runtime,
     client: Arc<OpenCodeClient>,
     session_id: String,
     message_id: String,
+    part_id: Option<String>,
     directory: Option<String>,
 ) {
     use openpad_protocol::RevertRequest;
@@ -549,7 +850,10 @@ pub fn spawn_message_reverter(
         // Use session-specific directory if provided
         let target_client = get_directory_client(client, directory);
 
-        let request = RevertRequest { message_id };
+        let request = RevertRequest {
+            message_id,
+            part_id,
+        };
         match target_client.revert_message(&session_id, request).await {
             Ok(session) => {
                 Cx::post_action(AppAction::SessionUpdated(session));
@@ -597,6 +901,60 @@ pub fn spawn_session_unreverter(
     });
 }
 
+/// Spawns a task to make a pending revert permanent.
+///
+/// The API has no commit endpoint: `/revert` and `/unrevert` only move the
+/// revert point, and the server deletes the reverted messages and snapshot
+/// only while handling the next prompt. Sending a `no_reply` prompt runs
+/// that cleanup without starting an assistant turn. The prompt stays in the
+/// history as one short note, because the API has no way to delete a message
+/// (deleting its only part would leave an empty message). It is `synthetic`,
+/// so it is not shown as typed by the user, and `ignored`, so it stays out of
+/// the model's context.
+pub fn spawn_revert_committer(
+    runtime: &tokio::runtime::Runtime,
+    client: Arc<OpenCodeClient>,
+    session_id: String,
+    directory: Option<String>,
+) {
+    runtime.spawn(async move {
+        let target_client = get_directory_client(client, directory);
+
+        let request = PromptRequest {
+            model: None,
+            agent: None,
+            system: None,
+            parts: vec![PartInput::Text {
+                id: None,
+                text: "Reverted changes committed.".to_string(),
+                synthetic: Some(true),
+                ignored: Some(true),
+                time: None,
+                metadata: None,
+            }],
+            no_reply: Some(true),
+            variant: None,
+        };
+        if let Err(e) = target_client
+            .send_prompt_with_options(&session_id, request)
+            .await
+        {
+            post_error_action("Failed to commit revert", e);
+            return;
+        }
+        match target_client.get_session(&session_id).await {
+            Ok(session) => Cx::post_action(AppAction::SessionUpdated(session)),
+            Err(e) => post_error_action("Failed to reload session", e),
+        }
+        if let Ok(messages) = target_client.list_messages(&session_id).await {
+            Cx::post_action(AppAction::MessagesLoaded {
+                session_id: session_id.clone(),
+                messages,
+            });
+        }
+    });
+}
+
 /// Spawns a task to fetch global config
 pub fn spawn_config_loader(runtime: &tokio::runtime::Runtime, client: Arc<OpenCodeClient>) {
     runtime.spawn(async move {
@@ -611,6 +969,37 @@ pub fn spawn_config_loader(runtime: &tokio::runtime::Runtime, client: Arc<OpenCo
     });
 }
 
+/// Spawns a task to write an agent's permission rules into the config, then
+/// reloads the agents so their rulesets reflect it
+pub fn spawn_agent_permission_updater(
+    runtime: &tokio::runtime::Runtime,
+    client: Arc<OpenCodeClient>,
+    agent: String,
+    rules: PermissionRuleset,
+) {
+    runtime.spawn(async move {
+        match client.update_agent_permission(&agent, rules).await {
+            Ok(config) => {
+                Cx::post_action(AppAction::ConfigLoaded(config));
+                Cx::post_action(AppAction::AgentPermissionUpdated {
+                    agent,
+                    result: Ok(()),
+                });
+                match client.agents().await {
+                    Ok(agents) => Cx::post_action(AppAction::AgentsLoaded(agents)),
+                    Err(e) => eprintln!("Failed to reload agents: {}", e),
+                }
+            }
+            Err(e) => {
+                Cx::post_action(AppAction::AgentPermissionUpdated {
+                    agent,
+                    result: Err(e.to_string()),
+                });
+            }
+        }
+    });
+}
+
 /// Spawns a task to set auth for a provider
 pub fn spawn_auth_setter(
     runtime: &tokio::runtime::Runtime,
@@ -1,17 +1,40 @@
-use makepad_code_editor::code_editor::CodeEditorAction;
+use crate::utils::line_diff::{ChangeHunk, LineChangeKind};
+use makepad_code_editor::code_editor::{CodeEditorAction, KeepCursorInView};
 use makepad_code_editor::decoration::DecorationSet;
+use makepad_code_editor::text::Position;
 use makepad_code_editor::{CodeDocument, CodeEditor, CodeSession};
 use makepad_widgets::*;
 
+const CHANGE_ADDED: Vec4 = vec4(0.301, 0.792, 0.301, 1.0);
+const CHANGE_MODIFIED: Vec4 = vec4(0.533, 0.690, 0.859, 1.0);
+const CHANGE_DELETED: Vec4 = vec4(0.878, 0.376, 0.376, 1.0);
+const CHANGE_GUTTER_BG: Vec4 = vec4(0.0, 0.0, 0.0, 0.25);
+
+/// Width of the change marker strip left of the editor.
+const CHANGE_GUTTER_WIDTH: f64 = 8.0;
+/// Markers are at least this tall, so one-line hunks in long files stay visible.
+const MIN_MARKER_HEIGHT: f64 = 3.0;
+/// Height of the click target around each marker.
+const MARKER_HIT_HEIGHT: f64 = 8.0;
+
 script_mod! {
     use mod.prelude.widgets_internal.*
     use mod.widgets.*
     use mod.theme.*
 
+    set_type_default() do #(DrawChangeMarker::script_shader(vm)){
+        ..mod.draw.DrawQuad
+    }
+
     mod.widgets.EditorPanelBase = #(EditorPanel::register_widget(vm))
     mod.widgets.EditorPanel = mod.widgets.EditorPanelBase {
         width: Fill
         height: Fill
+        draw_marker +: {
+            pixel: fn() {
+                return self.color
+            }
+        }
         editor := CodeEditor {
             width: Fill
             height: Fill
@@ -27,10 +50,22 @@ script_mod! {
 #[derive(Clone, Debug, Default)]
 pub enum EditorPanelAction {
     TextDidChange,
+    /// A change marker was clicked; the index is into the hunks last passed
+    /// to `set_change_hunks`
+    ChangeMarkerClicked(usize),
     #[default]
     None,
 }
 
+#[derive(Script, ScriptHook)]
+#[repr(C)]
+struct DrawChangeMarker {
+    #[deref]
+    draw_super: DrawQuad,
+    #[live]
+    color: Vec4,
+}
+
 #[derive(Script, ScriptHook, WidgetRef, WidgetSet, WidgetRegister)]
 pub struct EditorPanel {
     #[uid]
@@ -39,6 +74,9 @@ pub struct EditorPanel {
     #[live]
     pub editor: CodeEditor,
 
+    #[live]
+    draw_marker: DrawChangeMarker,
+
     #[rust]
     session: Option<CodeSession>,
 
@@ -49,6 +87,36 @@ pub struct EditorPanel {
 
     #[rust]
     read_only: bool,
+
+    /// Zero-based line to move the cursor to and scroll into view on the next draw.
+    #[rust]
+    pending_reveal_line: Option<usize>,
+
+    /// Agent-changed line runs, drawn as markers in the change gutter.
+    #[rust]
+    change_hunks: Vec<ChangeHunk>,
+
+    /// Click targets of the markers drawn last frame, by hunk index.
+    #[rust]
+    marker_hits: Vec<(Rect, usize)>,
+}
+
+fn marker_color(kind: LineChangeKind) -> Vec4 {
+    match kind {
+        LineChangeKind::Added => CHANGE_ADDED,
+        LineChangeKind::Modified => CHANGE_MODIFIED,
+        LineChangeKind::Deleted => CHANGE_DELETED,
+    }
+}
+
+/// Vertical extent of `hunk`'s marker in a gutter of `height` that spans all
+/// `line_count` lines of the file, as `(offset, marker height)`. Deleted hunks
+/// get a short bar at the line the lines were removed above.
+fn marker_span(hunk: &ChangeHunk, line_count: usize, height: f64) -> (f64, f64) {
+    let line_count = line_count.max(1) as f64;
+    let offset = height * (hunk.start as f64 / line_count).min(1.0);
+    let marker = (height * hunk.len as f64 / line_count).max(MIN_MARKER_HEIGHT);
+    (offset.min(height - MIN_MARKER_HEIGHT).max(0.0), marker)
 }
 
 impl EditorPanel {
@@ -91,6 +159,46 @@ impl EditorPanel {
         }
     }
 
+    fn reveal_line_inner(&mut self, cx: &mut Cx, line_index: usize) {
+        self.pending_reveal_line = Some(line_index);
+        self.editor.redraw(cx);
+    }
+
+    fn set_change_hunks_inner(&mut self, cx: &mut Cx, hunks: &[ChangeHunk]) {
+        if self.change_hunks == hunks {
+            return;
+        }
+        // Markers are drawn beside the editor, so the session (and with it
+        // the cursor and undo history) is left alone.
+        self.change_hunks = hunks.to_vec();
+        self.editor.redraw(cx);
+    }
+
+    /// Draws one marker per hunk into `gutter`, scaled to the whole file.
+    fn draw_change_gutter(&mut self, cx: &mut Cx2d, gutter: Rect, line_count: usize) {
+        self.marker_hits.clear();
+        if self.change_hunks.is_empty() {
+            return;
+        }
+        self.draw_marker.color = CHANGE_GUTTER_BG;
+        self.draw_marker.draw_abs(cx, gutter);
+        for (index, hunk) in self.change_hunks.iter().enumerate() {
+            let (offset, height) = marker_span(hunk, line_count, gutter.size.y);
+            let rect = Rect {
+                pos: dvec2(gutter.pos.x, gutter.pos.y + offset),
+                size: dvec2(gutter.size.x, height),
+            };
+            self.draw_marker.color = marker_color(hunk.kind);
+            self.draw_marker.draw_abs(cx, rect);
+            let hit_height = height.max(MARKER_HIT_HEIGHT);
+            let hit = Rect {
+                pos: dvec2(rect.pos.x, rect.pos.y + (height - hit_height) / 2.0),
+                size: dvec2(rect.size.x, hit_height),
+            };
+            self.marker_hits.push((hit, index));
+        }
+    }
+
     fn mark_clean_inner(&mut self) {
         self.baseline_text = self.get_text_inner();
     }
@@ -129,17 +237,59 @@ impl WidgetNode for EditorPanel {
 impl Widget for EditorPanel {
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
         self.ensure_session();
+        cx.begin_turtle(
+            walk,
+            Layout {
+                flow: Flow::Right,
+                ..Layout::default()
+            },
+        );
+        let gutter_width = if self.change_hunks.is_empty() {
+            0.0
+        } else {
+            CHANGE_GUTTER_WIDTH
+        };
+        let gutter = cx.walk_turtle(Walk::new(Size::Fixed(gutter_width), Size::fill()));
+        let editor_walk = Walk::new(Size::fill(), Size::fill());
+        let line_count = self.session.as_ref().map_or(0, |session| {
+            session.document().as_text().to_string().lines().count()
+        });
+        self.draw_change_gutter(cx, gutter, line_count);
+
         if let Some(session) = self.session.as_mut() {
-            self.editor.draw_walk_editor(cx, session, walk);
+            if let Some(line_index) = self.pending_reveal_line.take() {
+                let position = Position {
+                    line_index: line_index.min(line_count.saturating_sub(1)),
+                    byte_index: 0,
+                };
+                self.editor.set_cursor_and_scroll(
+                    cx,
+                    position,
+                    session,
+                    KeepCursorInView::JumpToPosition,
+                );
+            }
+            self.editor.draw_walk_editor(cx, session, editor_walk);
         } else {
-            self.editor.draw_empty_editor(cx, walk);
+            self.editor.draw_empty_editor(cx, editor_walk);
         }
+        cx.end_turtle();
         DrawStep::done()
     }
 
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, _scope: &mut Scope) {
         self.ensure_session();
         let uid = self.widget_uid();
+        if let Event::MouseDown(mouse) = event {
+            if let Some((_, index)) = self
+                .marker_hits
+                .iter()
+                .find(|(hit, _)| hit.contains(mouse.abs))
+            {
+                cx.widget_action(uid, EditorPanelAction::ChangeMarkerClicked(*index));
+                return;
+            }
+        }
         let Some(session) = self.session.as_mut() else {
             return;
         };
@@ -195,6 +345,20 @@ impl EditorPanelRef {
         false
     }
 
+    /// Moves the cursor to the start of the given zero-based line and scrolls it into view.
+    pub fn reveal_line(&self, cx: &mut Cx, line_index: usize) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.reveal_line_inner(cx, line_index);
+        }
+    }
+
+    /// Shows the lines changed by the agent in this file in the change gutter.
+    pub fn set_change_hunks(&self, cx: &mut Cx, hunks: &[ChangeHunk]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_change_hunks_inner(cx, hunks);
+        }
+    }
+
     /// Marks the editor as clean (not dirty). Call this after saving.
     pub fn mark_clean(&self) {
         if let Some(mut inner) = self.borrow_mut() {
@@ -1,11 +1,17 @@
 //! Files panel for IDE-style left sidebar using Makepad's dedicated FileTree widget.
+//! Changed files carry git status markers (`M +3 -1`); folders containing changes get a dot.
 
+use crate::file_access::{
+    is_visible_entry, list_local_dir, sort_entries, DirEntry, FileAccessMode,
+};
 use crate::state::actions::ProjectsPanelAction;
+use crate::utils::git_status::GitStatusIndex;
 use makepad_widgets::file_tree::{FileTree, FileTreeAction};
 use makepad_widgets::*;
-use openpad_protocol::Project;
-use std::collections::HashMap;
-use std::path::Path;
+use openpad_protocol::{File, Project};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 script_mod! {
     use mod.prelude.widgets_internal.*
@@ -63,16 +69,70 @@ script_mod! {
     }
 }
 
-const IGNORED_DIRS: &[&str] = &[
-    ".git",
-    "node_modules",
-    "target",
-    "__pycache__",
-    ".venv",
-    "venv",
-    "dist",
-    "build",
-];
+/// How long a file created while the app is running keeps its `· new` marker.
+const NEW_FILE_MARKER_SECS: f64 = 4.0;
+
+#[derive(Clone, Debug, Default)]
+pub enum FilesPanelAction {
+    #[default]
+    None,
+    /// A folder shown open in server mode has no listing yet; the app fetches
+    /// it and hands it back through `set_dir_listing`.
+    ListDirectory {
+        project_id: String,
+        directory: String,
+    },
+}
+
+/// What a `file.watcher.updated` event reports for a path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatcherChange {
+    Added,
+    Changed,
+    Removed,
+}
+
+impl WatcherChange {
+    /// Parse the event's `add` / `change` / `unlink` kind.
+    pub fn from_event(event: &str) -> Option<Self> {
+        match event {
+            "add" => Some(Self::Added),
+            "change" => Some(Self::Changed),
+            "unlink" => Some(Self::Removed),
+            _ => None,
+        }
+    }
+}
+
+/// Add `path` to a cached listing of its parent folder, keeping the listing's
+/// order and visibility rules. Returns true when it wasn't listed yet.
+fn insert_listed_entry(entries: &mut Vec<DirEntry>, path: &Path, is_dir: bool) -> bool {
+    let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+        return false;
+    };
+    let path = path.to_string_lossy().to_string();
+    if !is_visible_entry(&name, is_dir) || entries.iter().any(|e| e.path == path) {
+        return false;
+    }
+    entries.push(DirEntry { name, path, is_dir });
+    sort_entries(entries);
+    true
+}
+
+/// The entry of folder `dir` that leads down to `path` (`path` itself when it
+/// is a direct child); `None` when `path` is not below `dir`.
+fn entry_toward(dir: &Path, path: &Path) -> Option<PathBuf> {
+    let first = path.strip_prefix(dir).ok()?.components().next()?;
+    Some(dir.join(first))
+}
+
+/// Drop `path` from a cached listing. Returns true when it was listed.
+fn remove_listed_entry(entries: &mut Vec<DirEntry>, path: &Path) -> bool {
+    let path = path.to_string_lossy();
+    let before = entries.len();
+    entries.retain(|e| e.path != path);
+    entries.len() != before
+}
 
 #[derive(Clone, Debug)]
 struct CachedDirEntry {
@@ -92,6 +152,8 @@ pub struct FilesPanel {
     projects: Vec<Project>,
     #[rust]
     file_node_to_path: HashMap<LiveId, (String, String)>,
+    #[rust]
+    folder_node_to_path: HashMap<LiveId, (String, String)>,
 
     #[rust]
     cached_entries: HashMap<(String, String), Vec<CachedDirEntry>>,
@@ -103,6 +165,29 @@ pub struct FilesPanel {
     project_node_ids: HashMap<String, LiveId>,
     #[rust]
     current_dir: String,
+
+    /// Git status per project id, keyed by absolute path.
+    #[rust]
+    git_status: HashMap<String, GitStatusIndex>,
+    /// Hide files and folders without working-tree changes.
+    #[rust]
+    changed_only: bool,
+
+    /// Files that appeared via watcher events, with when they were first seen.
+    #[rust]
+    recently_added: HashMap<String, Instant>,
+    #[rust]
+    new_marker_timer: Timer,
+
+    #[rust]
+    access_mode: FileAccessMode,
+    /// Server listings requested but not yet received.
+    #[rust]
+    pending_listings: HashSet<(String, String)>,
+    /// Folders below the project roots the user has opened, so their server
+    /// listings can be requested again after the cache is dropped.
+    #[rust]
+    open_folders: HashSet<(String, String)>,
 }
 
 impl FilesPanel {
@@ -176,45 +261,68 @@ impl FilesPanel {
 
     fn get_dir_entries(&mut self, project_id: &str, dir_path: &Path) -> Vec<CachedDirEntry> {
         let path_str = dir_path.to_string_lossy().to_string();
-        let cache_key = (project_id.to_string(), path_str.clone());
+        let cache_key = (project_id.to_string(), path_str);
         if let Some(entries) = self.cached_entries.get(&cache_key) {
             return entries.clone();
         }
 
-        let mut entries = Vec::new();
+        if self.access_mode.is_server() {
+            // Server listings are requested from event handling via
+            // `request_listing` and arrive through `set_dir_listing`.
+            return Vec::new();
+        }
+
         // Optimization: avoid repeated filesystem scanning by caching directory contents.
-        if let Ok(rd) = std::fs::read_dir(dir_path) {
-            for e in rd.flatten() {
-                let name = e.file_name().to_string_lossy().to_string();
-                if name.starts_with('.') && name != ".git" {
-                    continue;
-                }
-                let is_dir = e.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
-                if is_dir && IGNORED_DIRS.contains(&name.as_str()) {
-                    continue;
-                }
+        let entries = Self::to_cached_entries(project_id, list_local_dir(dir_path));
+        self.cached_entries.insert(cache_key, entries.clone());
+        entries
+    }
 
-                let full_path = dir_path.join(&name);
-                let full_path_str = full_path.to_string_lossy().to_string();
-                let node_id = Self::node_id(project_id, &full_path);
+    fn to_cached_entries(project_id: &str, entries: Vec<DirEntry>) -> Vec<CachedDirEntry> {
+        entries
+            .into_iter()
+            .map(|entry| CachedDirEntry {
+                node_id: Self::node_id(project_id, Path::new(&entry.path)),
+                name: entry.name,
+                is_dir: entry.is_dir,
+                full_path: entry.path,
+            })
+            .collect()
+    }
 
-                entries.push(CachedDirEntry {
-                    name,
-                    is_dir,
-                    node_id,
-                    full_path: full_path_str,
-                });
-            }
-            // Sort: folders first, then alphabetical (case-insensitive)
-            entries.sort_by(|a, b| match (a.is_dir, b.is_dir) {
-                (true, false) => std::cmp::Ordering::Less,
-                (false, true) => std::cmp::Ordering::Greater,
-                _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-            });
+    /// Ask the app for the server listing of `directory` unless it is cached or
+    /// already on its way. Local listings are read while drawing instead.
+    fn request_listing(&mut self, cx: &mut Cx, project_id: &str, directory: &str) {
+        if !self.access_mode.is_server() {
+            return;
         }
+        let key = (project_id.to_string(), directory.to_string());
+        if self.cached_entries.contains_key(&key) || !self.pending_listings.insert(key) {
+            return;
+        }
+        cx.action(FilesPanelAction::ListDirectory {
+            project_id: project_id.to_string(),
+            directory: directory.to_string(),
+        });
+    }
 
-        self.cached_entries.insert(cache_key, entries.clone());
-        entries
+    /// Request every listing the tree currently shows: the project roots, which
+    /// start open, and the folders opened below them.
+    fn request_open_listings(&mut self, cx: &mut Cx) {
+        let roots: Vec<(String, String)> = self
+            .projects
+            .iter()
+            .filter(|p| p.worktree != "/" && !p.worktree.is_empty())
+            .map(|p| (p.id.clone(), p.worktree.clone()))
+            .collect();
+        for (project_id, worktree) in roots {
+            let root = self.get_normalized_worktree(&worktree);
+            self.request_listing(cx, &project_id, &root);
+        }
+        let open: Vec<(String, String)> = self.open_folders.iter().cloned().collect();
+        for (project_id, directory) in open {
+            self.request_listing(cx, &project_id, &directory);
+        }
     }
 
     fn node_id(project_id: &str, path: &Path) -> LiveId {
@@ -222,16 +330,173 @@ impl FilesPanel {
         LiveId::from_str(&key)
     }
 
+    /// Display name for an entry, with its git decoration appended. Returns `None`
+    /// when the "changed files only" filter hides it.
+    fn decorated_name(&self, project_id: &str, entry: &CachedDirEntry) -> Option<String> {
+        let status = self.git_status.get(project_id);
+        if entry.is_dir {
+            let dirty = status.is_some_and(|s| s.dirty_dirs.contains(&entry.full_path));
+            return match (dirty, self.changed_only) {
+                (true, _) => Some(format!("{}  ●", entry.name)),
+                (false, true) => None,
+                (false, false) => Some(entry.name.clone()),
+            };
+        }
+        let is_new = self.recently_added.contains_key(&entry.full_path);
+        let name = match status.and_then(|s| s.files.get(&entry.full_path)) {
+            Some(decoration) => format!("{}  {}", entry.name, decoration.label()),
+            None if self.changed_only && !is_new => return None,
+            None => entry.name.clone(),
+        };
+        Some(if is_new {
+            format!("{}  · new", name)
+        } else {
+            name
+        })
+    }
+
+    /// Rewrite cached listings of `project_id` with `edit`, which works on plain
+    /// file access entries. Returns what `edit` returned.
+    fn edit_listing(
+        project_id: &str,
+        entries: &mut Vec<CachedDirEntry>,
+        edit: impl FnOnce(&mut Vec<DirEntry>) -> bool,
+    ) -> bool {
+        let mut listing: Vec<DirEntry> = entries
+            .iter()
+            .map(|e| DirEntry {
+                name: e.name.clone(),
+                path: e.full_path.clone(),
+                is_dir: e.is_dir,
+            })
+            .collect();
+        let changed = edit(&mut listing);
+        if changed {
+            *entries = Self::to_cached_entries(project_id, listing);
+        }
+        changed
+    }
+
+    /// Apply a watcher event on `path` to the cached listings, going by the event
+    /// kind rather than the local disk so it works the same for server projects.
+    /// An unknown kind re-lists the parent folder through the file access layer.
+    /// Returns the projects whose listings gained `path`.
+    fn apply_watcher_change(&mut self, path: &Path, change: Option<WatcherChange>) -> Vec<String> {
+        let path_str = path.to_string_lossy().to_string();
+        match change {
+            // Contents only; listings are unaffected.
+            Some(WatcherChange::Changed) => Vec::new(),
+            Some(WatcherChange::Removed) => {
+                let parent = path.parent();
+                for ((project_id, dir), entries) in self.cached_entries.iter_mut() {
+                    if parent == Some(Path::new(dir)) {
+                        Self::edit_listing(project_id, entries, |listing| {
+                            remove_listed_entry(listing, path)
+                        });
+                    }
+                }
+                // A removed folder takes its cached subfolders with it.
+                self.cached_entries
+                    .retain(|(_, dir), _| !Path::new(dir).starts_with(path));
+                self.recently_added.remove(&path_str);
+                Vec::new()
+            }
+            Some(WatcherChange::Added) => {
+                // Every cached ancestor listing gains the next folder on the way
+                // down, so a file in a brand-new folder shows up too. The event
+                // doesn't say whether `path` itself is a file or a folder, so its
+                // parent is re-listed through the file access layer instead.
+                let file_name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let mut added = Vec::new();
+                let mut stale = Vec::new();
+                for ((project_id, dir), entries) in self.cached_entries.iter_mut() {
+                    let Some(child) = entry_toward(Path::new(dir), path) else {
+                        continue;
+                    };
+                    if child == path {
+                        let listed = entries.iter().any(|e| e.full_path == path_str);
+                        if !listed && is_visible_entry(&file_name, false) {
+                            stale.push((project_id.clone(), dir.clone()));
+                            added.push(project_id.clone());
+                        }
+                        continue;
+                    }
+                    Self::edit_listing(project_id, entries, |listing| {
+                        insert_listed_entry(listing, &child, true)
+                    });
+                }
+                for key in &stale {
+                    self.cached_entries.remove(key);
+                }
+                added
+            }
+            None => {
+                if let Some(parent) = path.parent() {
+                    let parent = parent.to_string_lossy().to_string();
+                    self.cached_entries.retain(|(_, dir), _| *dir != parent);
+                    self.pending_listings.retain(|(_, dir)| *dir != parent);
+                }
+                Vec::new()
+            }
+        }
+    }
+
+    /// Open the folders between the project root and `path` so a new file is in view.
+    fn reveal_path(&mut self, cx: &mut Cx, project_id: &str, path: &Path) {
+        let Some(worktree) = self
+            .projects
+            .iter()
+            .find(|p| p.id == project_id)
+            .map(|p| p.worktree.clone())
+        else {
+            return;
+        };
+        let root = self.get_normalized_worktree(&worktree);
+        for dir in path.ancestors().skip(1) {
+            if dir == Path::new(&root) || !dir.starts_with(&root) {
+                break;
+            }
+            let node_id = Self::node_id(project_id, dir);
+            self.file_tree
+                .set_folder_is_open(cx, node_id, true, Animate::Yes);
+            let dir = dir.to_string_lossy().to_string();
+            self.request_listing(cx, project_id, &dir);
+            self.open_folders.insert((project_id.to_string(), dir));
+        }
+    }
+
+    fn expire_new_markers(&mut self, cx: &mut Cx) {
+        let ttl = Duration::from_secs_f64(NEW_FILE_MARKER_SECS);
+        let now = Instant::now();
+        self.recently_added
+            .retain(|_, seen| now.duration_since(*seen) < ttl);
+        if let Some(oldest) = self.recently_added.values().min() {
+            let remaining = ttl.saturating_sub(now.duration_since(*oldest));
+            self.new_marker_timer = cx.start_timeout(remaining.as_secs_f64());
+        }
+        self.file_tree.redraw(cx);
+    }
+
     fn draw_dir_recursive(&mut self, cx: &mut Cx2d, project_id: &str, dir: &Path) {
         let entries = self.get_dir_entries(project_id, dir);
         for i in 0..entries.len() {
             // Optimization: avoid holding a borrow to self.cached_entries while recursing
             // by cloning the specific entry we are currently processing.
             let entry = entries[i].clone();
+            let Some(name) = self.decorated_name(project_id, &entry) else {
+                continue;
+            };
             if entry.is_dir {
+                self.folder_node_to_path.insert(
+                    entry.node_id,
+                    (project_id.to_string(), entry.full_path.clone()),
+                );
                 if self
                     .file_tree
-                    .begin_folder(cx, entry.node_id, &entry.name)
+                    .begin_folder(cx, entry.node_id, &name)
                     .is_ok()
                 {
                     self.draw_dir_recursive(cx, project_id, Path::new(&entry.full_path));
@@ -242,13 +507,42 @@ impl FilesPanel {
                     entry.node_id,
                     (project_id.to_string(), entry.full_path.clone()),
                 );
-                self.file_tree.file(cx, entry.node_id, &entry.name);
+                self.file_tree.file(cx, entry.node_id, &name);
             }
         }
+        self.draw_deleted_files(cx, project_id, dir);
+    }
+
+    /// Deleted files are gone from disk, so `read_dir` never lists them; draw them
+    /// after the real entries. They are not clickable (no `file_node_to_path` entry).
+    fn draw_deleted_files(&mut self, cx: &mut Cx2d, project_id: &str, dir: &Path) {
+        let Some(status) = self.git_status.get(project_id) else {
+            return;
+        };
+        let dir_str = dir.to_string_lossy().to_string();
+        let Some(names) = status.deleted_by_dir.get(&dir_str) else {
+            return;
+        };
+        let nodes: Vec<(LiveId, String)> = names
+            .iter()
+            .map(|name| {
+                let path = dir.join(name);
+                let label = status
+                    .files
+                    .get(path.to_string_lossy().as_ref())
+                    .map(|decoration| format!("{}  {}", name, decoration.label()))
+                    .unwrap_or_else(|| name.clone());
+                (Self::node_id(project_id, &path), label)
+            })
+            .collect();
+        for (node_id, label) in nodes {
+            self.file_tree.file(cx, node_id, &label);
+        }
     }
 
     fn draw_tree(&mut self, cx: &mut Cx2d) {
         self.file_node_to_path.clear();
+        self.folder_node_to_path.clear();
 
         // Optimization: avoid cloning self.projects every frame by using indexed access
         // and only cloning the minimum necessary strings to satisfy the borrow checker.
@@ -264,7 +558,7 @@ impl FilesPanel {
             let display_name = self.get_project_name(&project_id, project_name.as_ref(), &worktree);
             let root = self.get_normalized_worktree(&worktree);
             let root_path = Path::new(&root);
-            if !root_path.is_dir() {
+            if !self.access_mode.is_server() && !root_path.is_dir() {
                 continue;
             }
 
@@ -304,22 +598,41 @@ impl Widget for FilesPanel {
     }
 
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if self.new_marker_timer.is_event(event).is_some() {
+            self.expire_new_markers(cx);
+        }
+
         let actions = cx.capture_actions(|cx| {
             self.file_tree.handle_event(cx, event, scope);
         });
 
         if let Some(item) = actions.find_widget_action(self.file_tree.widget_uid()) {
-            if let FileTreeAction::FileClicked(node_id) = item.cast() {
-                let Some((project_id, absolute_path)) = self.file_node_to_path.get(&node_id) else {
-                    return;
-                };
-                if absolute_path.is_empty() {
-                    return;
+            match item.cast() {
+                FileTreeAction::FileClicked(node_id) => {
+                    let Some((project_id, absolute_path)) = self.file_node_to_path.get(&node_id)
+                    else {
+                        return;
+                    };
+                    if absolute_path.is_empty() {
+                        return;
+                    }
+                    cx.action(ProjectsPanelAction::OpenFile {
+                        project_id: project_id.clone(),
+                        absolute_path: absolute_path.clone(),
+                    });
+                }
+                FileTreeAction::FolderClicked(node_id) => {
+                    let Some(key) = self.folder_node_to_path.get(&node_id).cloned() else {
+                        return;
+                    };
+                    // Clicking toggles the folder; a closed one needs no listing.
+                    if self.open_folders.remove(&key) {
+                        return;
+                    }
+                    self.request_listing(cx, &key.0, &key.1);
+                    self.open_folders.insert(key);
                 }
-                cx.action(ProjectsPanelAction::OpenFile {
-                    project_id: project_id.clone(),
-                    absolute_path: absolute_path.clone(),
-                });
+                _ => {}
             }
         }
     }
@@ -332,19 +645,196 @@ impl FilesPanelRef {
 
             // Optimization: invalidate all caches when new project data is set to ensure UI consistency.
             inner.cached_entries.clear();
+            inner.pending_listings.clear();
             inner.normalized_worktrees.clear();
             inner.derived_project_names.clear();
             inner.project_node_ids.clear();
             inner.current_dir.clear();
 
             let project_ids: Vec<String> = inner.projects.iter().map(|p| p.id.clone()).collect();
+            inner
+                .open_folders
+                .retain(|(project_id, _)| project_ids.contains(project_id));
             for project_id in project_ids {
                 let project_node_id = LiveId::from_str(&format!("project:{}", project_id));
                 inner
                     .file_tree
                     .set_folder_is_open(cx, project_node_id, true, Animate::No);
             }
+            inner.request_open_listings(cx);
+            inner.file_tree.redraw(cx);
+        }
+    }
+
+    /// Switch between reading the tree from local disk and from the server's file API.
+    pub fn set_access_mode(&self, cx: &mut Cx, mode: FileAccessMode) {
+        if let Some(mut inner) = self.borrow_mut() {
+            if inner.access_mode == mode {
+                return;
+            }
+            inner.access_mode = mode;
+            inner.cached_entries.clear();
+            inner.pending_listings.clear();
+            inner.request_open_listings(cx);
+            inner.file_tree.redraw(cx);
+        }
+    }
+
+    /// Store a directory listing fetched from the server. `None` means the
+    /// request failed; nothing is cached, so reopening the folder retries it.
+    pub fn set_dir_listing(
+        &self,
+        cx: &mut Cx,
+        project_id: &str,
+        directory: &str,
+        entries: Option<Vec<DirEntry>>,
+    ) {
+        if let Some(mut inner) = self.borrow_mut() {
+            let key = (project_id.to_string(), directory.to_string());
+            inner.pending_listings.remove(&key);
+            if let Some(entries) = entries {
+                let entries = FilesPanel::to_cached_entries(project_id, entries);
+                inner.cached_entries.insert(key, entries);
+            }
             inner.file_tree.redraw(cx);
         }
     }
+
+    /// Replace the git decorations for one project with a fresh `/file/status` result.
+    pub fn set_file_status(&self, cx: &mut Cx, project_id: &str, files: &[File]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            let Some(worktree) = inner
+                .projects
+                .iter()
+                .find(|p| p.id == project_id)
+                .map(|p| p.worktree.clone())
+            else {
+                return;
+            };
+            let root = inner.get_normalized_worktree(&worktree);
+            inner
+                .git_status
+                .insert(project_id.to_string(), GitStatusIndex::build(&root, files));
+            inner.file_tree.redraw(cx);
+        }
+    }
+
+    /// Apply a file watcher / edit event: update only the listings it affects,
+    /// then reveal and briefly mark newly created files. `path` may be relative
+    /// to a worktree; `change` is `None` for an event kind we don't know.
+    pub fn apply_file_change(&self, cx: &mut Cx, path: &str, change: Option<WatcherChange>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            let candidates: Vec<String> = if Path::new(path).is_absolute() {
+                vec![path.to_string()]
+            } else {
+                let worktrees: Vec<String> =
+                    inner.projects.iter().map(|p| p.worktree.clone()).collect();
+                worktrees
+                    .iter()
+                    .map(|w| {
+                        let root = inner.get_normalized_worktree(w);
+                        Path::new(&root).join(path).to_string_lossy().to_string()
+                    })
+                    .collect()
+            };
+
+            for candidate in candidates {
+                let path = Path::new(&candidate);
+                let projects = inner.apply_watcher_change(path, change);
+                for project_id in &projects {
+                    inner.reveal_path(cx, project_id, path);
+                }
+                if !projects.is_empty() {
+                    if inner.recently_added.is_empty() {
+                        inner.new_marker_timer = cx.start_timeout(NEW_FILE_MARKER_SECS);
+                    }
+                    inner.recently_added.insert(candidate, Instant::now());
+                }
+            }
+            // Listings dropped for an unknown event kind are fetched again.
+            inner.request_open_listings(cx);
+            inner.file_tree.redraw(cx);
+        }
+    }
+
+    pub fn set_changed_only(&self, cx: &mut Cx, changed_only: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.changed_only = changed_only;
+            inner.file_tree.redraw(cx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, is_dir: bool) -> DirEntry {
+        DirEntry {
+            name: name.to_string(),
+            path: format!("/repo/{}", name),
+            is_dir,
+        }
+    }
+
+    #[test]
+    fn test_watcher_change_kinds() {
+        assert_eq!(WatcherChange::from_event("add"), Some(WatcherChange::Added));
+        assert_eq!(
+            WatcherChange::from_event("change"),
+            Some(WatcherChange::Changed)
+        );
+        assert_eq!(
+            WatcherChange::from_event("unlink"),
+            Some(WatcherChange::Removed)
+        );
+        assert_eq!(WatcherChange::from_event("rename"), None);
+    }
+
+    #[test]
+    fn test_listing_follows_watcher_changes_without_disk_access() {
+        let mut entries = vec![entry("src", true), entry("b.rs", false)];
+
+        assert!(insert_listed_entry(
+            &mut entries,
+            Path::new("/repo/a.rs"),
+            false
+        ));
+        assert!(!insert_listed_entry(
+            &mut entries,
+            Path::new("/repo/a.rs"),
+            false
+        ));
+        assert!(!insert_listed_entry(
+            &mut entries,
+            Path::new("/repo/.env"),
+            false
+        ));
+        assert!(insert_listed_entry(
+            &mut entries,
+            Path::new("/repo/docs"),
+            true
+        ));
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["docs", "src", "a.rs", "b.rs"]);
+
+        assert!(remove_listed_entry(&mut entries, Path::new("/repo/b.rs")));
+        assert!(!remove_listed_entry(&mut entries, Path::new("/repo/b.rs")));
+        assert_eq!(entries.len(), 3);
+    }
+
+    #[test]
+    fn test_entry_toward_steps_one_folder_down() {
+        let path = Path::new("/repo/src/new/mod.rs");
+        assert_eq!(
+            entry_toward(Path::new("/repo"), path),
+            Some(PathBuf::from("/repo/src"))
+        );
+        assert_eq!(
+            entry_toward(Path::new("/repo/src/new"), path),
+            Some(PathBuf::from("/repo/src/new/mod.rs"))
+        );
+        assert_eq!(entry_toward(Path::new("/other"), path), None);
+        assert_eq!(entry_toward(path, path), None);
+    }
 }
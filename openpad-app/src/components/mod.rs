@@ -4,17 +4,25 @@
 
 pub mod editor_panel;
 pub mod files_panel;
+pub mod model_picker;
 pub mod projects_panel;
+pub mod quick_open;
+pub mod search_panel;
 pub mod session_context_menu;
 pub mod session_options_popup;
 pub mod sessions_panel;
 pub mod sidebar_header;
+pub mod symbol_picker;
 
 // Re-export the widget types that have Rust implementations
 pub use editor_panel::{EditorPanel, EditorPanelAction, EditorPanelRef};
-pub use files_panel::{FilesPanel, FilesPanelRef};
+pub use files_panel::{FilesPanel, FilesPanelAction, FilesPanelRef};
+pub use model_picker::{ModelPicker, ModelPickerAction, ModelPickerRef};
 pub use projects_panel::{PanelItemKind, ProjectsPanel, ProjectsPanelRef};
+pub use quick_open::{QuickOpen, QuickOpenAction, QuickOpenRef};
+pub use search_panel::{SearchPanel, SearchPanelAction, SearchPanelRef};
 pub use session_context_menu::SessionContextMenu;
 pub use session_options_popup::{SessionOptionsPopup, SessionOptionsPopupRef};
 pub use sessions_panel::{SessionsPanel, SessionsPanelRef};
 pub use sidebar_header::SidebarHeader;
+pub use symbol_picker::{SymbolPicker, SymbolPickerAction, SymbolPickerRef};
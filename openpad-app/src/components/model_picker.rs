@@ -0,0 +1,415 @@
+//! Modal model picker opened from the composer toolbar. Models are grouped
+//! into favorites, recents and provider sections, filtered as the user types,
+//! and show capability badges, pricing and context window. Enter or a click
+//! emits `ModelPickerAction::Selected`; the star toggles a favorite.
+
+use crate::utils::model_catalog::{self, ModelChoice, PickerRow};
+use makepad_widgets::*;
+
+script_mod! {
+    use mod.prelude.widgets_internal.*
+    use mod.widgets.*
+    use mod.theme.*
+
+    mod.widgets.ModelPicker = #(ModelPicker::register_widget(vm)) {
+        width: Fill
+        height: Fill
+        flow: Overlay
+        visible: false
+
+        backdrop := View {
+            width: Fill
+            height: Fill
+            show_bg: true
+            draw_bg +: {
+                color: #0008
+            }
+        }
+
+        View {
+            width: Fill
+            height: Fill
+            align: Align{x: 0.5 y: 0.15}
+
+            dialog_box := View {
+                width: 620
+                height: Fit
+                flow: Down
+                padding: Inset{left: 10 right: 10 top: 10 bottom: 10}
+                spacing: 8
+                show_bg: true
+                draw_bg +: {
+                    color: #1f2329
+                    border_color: #2b3138
+                    border_radius: 10.0
+                    border_size: 1.0
+                }
+
+                query_input := TextInput {
+                    width: Fill
+                    height: 32
+                    empty_text: "Search models..."
+                    padding: Inset{left: 8 right: 8 top: 8 bottom: 8}
+                    draw_text +: {
+                        color: #e6e9ee
+                        text_style: theme.font_regular {font_size: 11}
+                    }
+                    draw_bg +: {
+                        color: #15181d
+                        color_focus: #15181d
+                        border_radius: 8.0
+                        border_size: 0.0
+                    }
+                }
+
+                status_label := Label {
+                    width: Fill, height: Fit
+                    text: ""
+                    draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 9.5 } }
+                }
+
+                list := PortalList {
+                    width: Fill, height: 380
+                    scroll_bar: ScrollBar {
+                        bar_size: 2.5
+                        bar_side_margin: 2.0
+                        smoothing: 0.15
+                    }
+
+                    SectionRow := View {
+                        width: Fill, height: 24
+                        align: Align{ y: 0.5 }
+                        padding: Inset{ left: 4, top: 4 }
+
+                        section_label := Label {
+                            text: ""
+                            draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_bold { font_size: 9 } }
+                        }
+                    }
+
+                    ModelRow := View {
+                        width: Fill, height: 40
+                        flow: Right, align: Align{ y: 0.5 }
+
+                        View {
+                            width: Fill, height: Fill
+                            flow: Overlay
+
+                            View {
+                                width: Fill, height: Fill
+                                flow: Down, align: Align{ y: 0.5 }
+                                padding: Inset{ left: 4, right: 6 }
+                                spacing: 2
+
+                                View {
+                                    width: Fill, height: Fit
+                                    flow: Right, spacing: 6
+
+                                    marker_label := Label {
+                                        width: 10, height: Fit
+                                        text: ""
+                                        draw_text +: { color: theme.THEME_COLOR_ACCENT_BLUE, text_style: theme.font_bold { font_size: 10 } }
+                                    }
+                                    name_label := Label {
+                                        width: Fit, height: Fit
+                                        text: ""
+                                        draw_text +: { color: theme.THEME_COLOR_TEXT_PRIMARY, text_style: theme.font_regular { font_size: 10 } }
+                                    }
+                                    badges_label := Label {
+                                        width: Fill, height: Fit
+                                        text: ""
+                                        draw_text +: { color: theme.THEME_COLOR_ACCENT_PURPLE, text_style: theme.font_regular { font_size: 8.5 } }
+                                    }
+                                }
+                                details_label := Label {
+                                    width: Fill, height: Fit
+                                    margin: Inset{ left: 16 }
+                                    text: ""
+                                    draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 8.5 } }
+                                }
+                            }
+
+                            select_button := Button {
+                                width: Fill, height: Fill
+                                text: ""
+                                draw_bg +: {
+                                    color: theme.THEME_COLOR_TRANSPARENT
+                                    color_hover: theme.THEME_COLOR_HOVER_SUBTLE
+                                    color_active: theme.THEME_COLOR_HOVER_SUBTLE
+                                    border_radius: 4.0
+                                    border_size: 0.0
+                                }
+                            }
+                        }
+
+                        favorite_button := Button {
+                            width: 28, height: 28
+                            text: "☆"
+                            draw_bg +: {
+                                color: theme.THEME_COLOR_TRANSPARENT
+                                color_hover: theme.THEME_COLOR_HOVER_SUBTLE
+                                color_active: theme.THEME_COLOR_HOVER_SUBTLE
+                                border_radius: 4.0
+                                border_size: 0.0
+                            }
+                            draw_text +: { color: #f5c542, text_style: theme.font_regular { font_size: 11 } }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub enum ModelPickerAction {
+    #[default]
+    None,
+    Selected {
+        provider_id: String,
+        model_id: String,
+    },
+    Closed,
+}
+
+#[derive(Script, ScriptHook, Widget)]
+pub struct ModelPicker {
+    #[source]
+    source: ScriptObjectRef,
+
+    #[deref]
+    view: View,
+
+    #[rust]
+    choices: Vec<ModelChoice>,
+    #[rust]
+    favorites: Vec<String>,
+    #[rust]
+    recents: Vec<String>,
+    #[rust]
+    query: String,
+    #[rust]
+    rows: Vec<PickerRow>,
+    /// Highlighted row; always a model row when any are listed
+    #[rust]
+    selected: usize,
+    /// `provider/model` key of the composer's current model
+    #[rust]
+    current: Option<String>,
+}
+
+impl ModelPicker {
+    fn rebuild_rows(&mut self, cx: &mut Cx) {
+        self.rows =
+            model_catalog::picker_rows(&self.choices, &self.query, &self.favorites, &self.recents);
+        let models = self
+            .rows
+            .iter()
+            .filter(|row| matches!(row, PickerRow::Model { .. }))
+            .count();
+        let status = match models {
+            _ if self.choices.is_empty() => "No models loaded yet".to_string(),
+            0 => format!("No models matching \"{}\"", self.query),
+            1 => "1 model".to_string(),
+            n => format!("{} models", n),
+        };
+        self.view
+            .label(cx, &[id!(status_label)])
+            .set_text(cx, &status);
+    }
+
+    fn choice_at(&self, row: usize) -> Option<&ModelChoice> {
+        match self.rows.get(row) {
+            Some(PickerRow::Model { choice, .. }) => self.choices.get(*choice),
+            _ => None,
+        }
+    }
+
+    /// First model row at or after `from` when moving down, at or before it
+    /// when moving up.
+    fn model_row_from(&self, from: usize, forward: bool) -> Option<usize> {
+        if forward {
+            (from..self.rows.len()).find(|row| self.choice_at(*row).is_some())
+        } else {
+            (0..=from.min(self.rows.len().saturating_sub(1)))
+                .rev()
+                .find(|row| self.choice_at(*row).is_some())
+        }
+    }
+
+    fn move_selection(&mut self, cx: &mut Cx, forward: bool) {
+        let next = if forward {
+            self.model_row_from(self.selected + 1, true)
+        } else {
+            self.selected
+                .checked_sub(1)
+                .and_then(|from| self.model_row_from(from, false))
+        };
+        if let Some(next) = next {
+            self.selected = next;
+            self.view.redraw(cx);
+        }
+    }
+
+    fn close(&mut self, cx: &mut Cx) {
+        self.view.set_visible(cx, false);
+        self.view.redraw(cx);
+        cx.action(ModelPickerAction::Closed);
+    }
+
+    fn select(&mut self, cx: &mut Cx, row: usize) {
+        let Some(choice) = self.choice_at(row).cloned() else {
+            return;
+        };
+        model_catalog::push_recent(&mut self.recents, &choice.key());
+        model_catalog::save_recents(&self.recents);
+        self.view.set_visible(cx, false);
+        self.view.redraw(cx);
+        cx.action(ModelPickerAction::Selected {
+            provider_id: choice.provider_id,
+            model_id: choice.model_id,
+        });
+    }
+
+    fn toggle_favorite(&mut self, cx: &mut Cx, row: usize) {
+        let Some(key) = self.choice_at(row).map(ModelChoice::key) else {
+            return;
+        };
+        model_catalog::toggle_favorite(&mut self.favorites, &key);
+        model_catalog::save_favorites(&self.favorites);
+        self.rebuild_rows(cx);
+        self.selected = self.model_row_from(0, true).unwrap_or(0);
+        self.view.redraw(cx);
+    }
+}
+
+impl Widget for ModelPicker {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if !self.view.visible() {
+            return;
+        }
+
+        if let Event::KeyDown(ke) = event {
+            match ke.key_code {
+                KeyCode::Escape => {
+                    self.close(cx);
+                    return;
+                }
+                KeyCode::ArrowUp => self.move_selection(cx, false),
+                KeyCode::ArrowDown => self.move_selection(cx, true),
+                _ => {}
+            }
+        }
+
+        let actions = cx.capture_actions(|cx| {
+            self.view.handle_event(cx, event, scope);
+        });
+
+        let input = self.view.text_input(cx, &[id!(query_input)]);
+        if let Some(query) = input.changed(&actions) {
+            self.query = query.trim().to_string();
+            self.rebuild_rows(cx);
+            self.selected = self.model_row_from(0, true).unwrap_or(0);
+            self.view.redraw(cx);
+        }
+        if input.returned(&actions).is_some() {
+            self.select(cx, self.selected);
+            return;
+        }
+
+        let list = self.view.portal_list(cx, &[id!(list)]);
+        for (item_id, widget) in list.items_with_actions(&actions) {
+            if widget.button(cx, &[id!(favorite_button)]).clicked(&actions) {
+                self.toggle_favorite(cx, item_id);
+                return;
+            }
+            if widget.button(cx, &[id!(select_button)]).clicked(&actions) {
+                self.select(cx, item_id);
+                return;
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                list.set_item_range(cx, 0, self.rows.len());
+                while let Some(item_id) = list.next_visible_item(cx) {
+                    match self.rows.get(item_id) {
+                        Some(PickerRow::Section(title)) => {
+                            let item_widget = list.item(cx, item_id, live_id!(SectionRow));
+                            item_widget
+                                .label(cx, &[id!(section_label)])
+                                .set_text(cx, title);
+                            item_widget.draw_all(cx, scope);
+                        }
+                        Some(PickerRow::Model { choice, favorite }) => {
+                            let Some(model) = self.choices.get(*choice) else {
+                                continue;
+                            };
+                            let item_widget = list.item(cx, item_id, live_id!(ModelRow));
+                            let marker = if item_id == self.selected { "›" } else { "" };
+                            let name = if self.current.as_deref() == Some(model.key().as_str()) {
+                                format!("{}  ✓", model.name)
+                            } else {
+                                model.name.clone()
+                            };
+                            item_widget
+                                .label(cx, &[id!(marker_label)])
+                                .set_text(cx, marker);
+                            item_widget
+                                .label(cx, &[id!(name_label)])
+                                .set_text(cx, &name);
+                            item_widget
+                                .label(cx, &[id!(badges_label)])
+                                .set_text(cx, &model.badges());
+                            item_widget
+                                .label(cx, &[id!(details_label)])
+                                .set_text(cx, &model.details());
+                            item_widget
+                                .button(cx, &[id!(favorite_button)])
+                                .set_text(cx, if *favorite { "★" } else { "☆" });
+                            item_widget.draw_all(cx, scope);
+                        }
+                        None => {}
+                    }
+                }
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl ModelPickerRef {
+    /// Replaces the listed models, e.g. after providers reload.
+    pub fn set_models(&self, cx: &mut Cx, choices: Vec<ModelChoice>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.choices = choices;
+            inner.rebuild_rows(cx);
+            inner.selected = inner.model_row_from(0, true).unwrap_or(0);
+            inner.redraw(cx);
+        }
+    }
+
+    /// Opens the picker with an empty query, highlighting `current`.
+    pub fn show(&self, cx: &mut Cx, current: Option<(String, String)>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.favorites = model_catalog::load_favorites();
+            inner.recents = model_catalog::load_recents();
+            inner.current =
+                current.map(|(provider_id, model_id)| format!("{}/{}", provider_id, model_id));
+            inner.query.clear();
+            inner.rebuild_rows(cx);
+            inner.selected = inner.model_row_from(0, true).unwrap_or(0);
+            let input = inner.view.text_input(cx, &[id!(query_input)]);
+            input.set_text(cx, "");
+            input.set_key_focus(cx);
+            inner.view.set_visible(cx, true);
+            inner.redraw(cx);
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.borrow().is_some_and(|inner| inner.view.visible())
+    }
+}
@@ -442,8 +442,9 @@ impl ProjectsPanel {
         }
         let current_dir_name = &self.current_dir_name;
 
+        // Archived sessions are only browsable from the sessions panel.
         let mut grouped: HashMap<Option<String>, Vec<Session>> = HashMap::new();
-        for session in &self.sessions {
+        for session in self.sessions.iter().filter(|s| !s.time.is_archived()) {
             grouped
                 .entry(Some(session.project_id.clone()))
                 .or_default()
@@ -538,7 +539,7 @@ impl ProjectsPanel {
         let ungrouped: Vec<&Session> = self
             .sessions
             .iter()
-            .filter(|s| !project_ids.contains(&s.project_id))
+            .filter(|s| !s.time.is_archived() && !project_ids.contains(&s.project_id))
             .collect();
 
         if !ungrouped.is_empty() {
@@ -606,6 +607,12 @@ impl ProjectsPanel {
         self.dirty = false;
     }
 
+    fn is_archived(&self, session_id: &str) -> bool {
+        self.sessions
+            .iter()
+            .any(|s| s.id == session_id && s.time.is_archived())
+    }
+
     fn session_diff_stats(summary: &SessionSummary) -> Option<(String, String, String)> {
         let (files, additions, deletions) = if !summary.diffs.is_empty() {
             let additions: i64 = summary.diffs.iter().map(|d| d.additions).sum();
@@ -673,11 +680,13 @@ impl Widget for ProjectsPanel {
                             .get(&session_id)
                             .copied()
                             .unwrap_or(false);
+                        let archived = self.is_archived(&session_id);
                         cx.action(ProjectsPanelAction::OpenSessionContextMenu {
                             session_id: session_id.clone(),
                             x,
                             y,
                             working,
+                            archived,
                         });
                     }
 
@@ -719,11 +728,13 @@ impl Widget for ProjectsPanel {
                             .get(session_id)
                             .copied()
                             .unwrap_or(false);
+                        let archived = self.is_archived(session_id);
                         cx.action(ProjectsPanelAction::OpenSessionContextMenu {
                             session_id: session_id.clone(),
                             x,
                             y,
                             working,
+                            archived,
                         });
                         break;
                     }
@@ -0,0 +1,440 @@
+//! Modal fuzzy file finder (Ctrl/Cmd+P). Typing emits `QuickOpenAction::QueryChanged`;
+//! the app answers with `set_results`, which ranks the server's matches and
+//! previews the highlighted file. Enter or a click emits `QuickOpenAction::Open`.
+//! Previews of server-backed files are requested with
+//! `QuickOpenAction::PreviewRequested` and arrive through `set_preview`.
+
+use crate::file_access::{read_local_text, FileAccessMode, ReadTextError};
+use crate::utils::fuzzy::rank_fuzzy;
+use makepad_widgets::*;
+use std::path::Path;
+
+/// Number of leading lines shown in the preview pane.
+const PREVIEW_MAX_LINES: usize = 24;
+/// Preview lines are cut at this many chars to keep the pane readable.
+const PREVIEW_MAX_LINE_CHARS: usize = 120;
+
+script_mod! {
+    use mod.prelude.widgets_internal.*
+    use mod.widgets.*
+    use mod.theme.*
+
+    mod.widgets.QuickOpen = #(QuickOpen::register_widget(vm)) {
+        width: Fill
+        height: Fill
+        flow: Overlay
+        visible: false
+
+        backdrop := View {
+            width: Fill
+            height: Fill
+            show_bg: true
+            draw_bg +: {
+                color: #0008
+            }
+        }
+
+        View {
+            width: Fill
+            height: Fill
+            align: Align{x: 0.5 y: 0.15}
+
+            dialog_box := View {
+                width: 560
+                height: Fit
+                flow: Down
+                padding: Inset{left: 10 right: 10 top: 10 bottom: 10}
+                spacing: 8
+                show_bg: true
+                draw_bg +: {
+                    color: #1f2329
+                    border_color: #2b3138
+                    border_radius: 10.0
+                    border_size: 1.0
+                }
+
+                query_input := TextInput {
+                    width: Fill
+                    height: 32
+                    empty_text: "Go to file..."
+                    padding: Inset{left: 8 right: 8 top: 8 bottom: 8}
+                    draw_text +: {
+                        color: #e6e9ee
+                        text_style: theme.font_regular {font_size: 11}
+                    }
+                    draw_bg +: {
+                        color: #15181d
+                        color_focus: #15181d
+                        border_radius: 8.0
+                        border_size: 0.0
+                    }
+                }
+
+                status_label := Label {
+                    width: Fill, height: Fit
+                    text: "Type to search files"
+                    draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 9.5 } }
+                }
+
+                list := PortalList {
+                    width: Fill, height: 220
+                    scroll_bar: ScrollBar {
+                        bar_size: 2.5
+                        bar_side_margin: 2.0
+                        smoothing: 0.15
+                    }
+
+                    FileRow := View {
+                        width: Fill, height: 24
+                        flow: Overlay
+
+                        View {
+                            width: Fill, height: Fill
+                            flow: Right, align: Align{ y: 0.5 }
+                            padding: Inset{ left: 4, right: 6 }
+                            spacing: 6
+
+                            marker_label := Label {
+                                width: 10, height: Fit
+                                text: ""
+                                draw_text +: { color: theme.THEME_COLOR_ACCENT_BLUE, text_style: theme.font_bold { font_size: 10 } }
+                            }
+                            name_label := Label {
+                                width: Fit, height: Fit
+                                text: ""
+                                draw_text +: { color: theme.THEME_COLOR_TEXT_PRIMARY, text_style: theme.font_regular { font_size: 10 } }
+                            }
+                            dir_label := Label {
+                                width: Fill, height: Fit
+                                text: ""
+                                draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 9 } }
+                            }
+                        }
+
+                        open_button := Button {
+                            width: Fill, height: Fill
+                            text: ""
+                            draw_bg +: {
+                                color: theme.THEME_COLOR_TRANSPARENT
+                                color_hover: theme.THEME_COLOR_HOVER_SUBTLE
+                                color_active: theme.THEME_COLOR_HOVER_SUBTLE
+                                border_radius: 4.0
+                                border_size: 0.0
+                            }
+                        }
+                    }
+                }
+
+                preview_wrap := RoundedView {
+                    width: Fill, height: 200
+                    flow: Down
+                    padding: Inset{ left: 8, right: 8, top: 6, bottom: 6 }
+                    spacing: 4
+                    show_bg: true
+                    draw_bg +: {
+                        color: #15181d
+                        border_radius: 6.0
+                    }
+
+                    preview_title := Label {
+                        width: Fill, height: Fit
+                        text: ""
+                        draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 9 } }
+                    }
+                    preview_label := Label {
+                        width: Fill, height: Fill
+                        text: ""
+                        draw_text +: { color: theme.THEME_COLOR_TEXT_NORMAL, text_style: theme.font_code { font_size: 9 } }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub enum QuickOpenAction {
+    #[default]
+    None,
+    QueryChanged(String),
+    /// Open the chosen file; the path is relative to the searched directory.
+    Open(String),
+    Closed,
+    /// The highlighted file needs a preview read through the server; the
+    /// path is relative to the searched directory.
+    PreviewRequested {
+        directory: String,
+        file: String,
+    },
+}
+
+/// Cached display strings for a result row.
+#[derive(Clone, Debug)]
+struct QuickOpenRow {
+    name: String,
+    dir: String,
+}
+
+#[derive(Script, ScriptHook, Widget)]
+pub struct QuickOpen {
+    #[source]
+    source: ScriptObjectRef,
+
+    #[deref]
+    view: View,
+
+    #[rust]
+    directory: String,
+    #[rust]
+    access_mode: FileAccessMode,
+    #[rust]
+    files: Vec<String>,
+    #[rust]
+    rows: Vec<QuickOpenRow>,
+    #[rust]
+    selected: usize,
+}
+
+/// The first lines of a file read for the preview pane.
+fn preview_text(content: Result<String, ReadTextError>) -> String {
+    let text = match content {
+        Ok(text) => text,
+        Err(ReadTextError::Binary) => return "Binary file".to_string(),
+        Err(ReadTextError::Io(_)) => return "Preview unavailable".to_string(),
+    };
+    text.lines()
+        .take(PREVIEW_MAX_LINES)
+        .map(
+            |line| match line.char_indices().nth(PREVIEW_MAX_LINE_CHARS) {
+                Some((idx, _)) => format!("{}…", &line[..idx]),
+                None => line.to_string(),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl QuickOpen {
+    fn rebuild_rows(&mut self) {
+        // Optimization: split paths once here rather than on every draw.
+        self.rows = self
+            .files
+            .iter()
+            .map(|file| {
+                let (dir, name) = match file.rfind('/') {
+                    Some(idx) => (&file[..idx], &file[idx + 1..]),
+                    None => ("", file.as_str()),
+                };
+                QuickOpenRow {
+                    name: name.to_string(),
+                    dir: dir.to_string(),
+                }
+            })
+            .collect();
+    }
+
+    fn update_preview(&mut self, cx: &mut Cx) {
+        let (title, body) = match self.files.get(self.selected) {
+            Some(file) if self.access_mode.is_server() => {
+                cx.action(QuickOpenAction::PreviewRequested {
+                    directory: self.directory.clone(),
+                    file: file.clone(),
+                });
+                (file.clone(), "Loading preview…".to_string())
+            }
+            Some(file) => (
+                file.clone(),
+                preview_text(read_local_text(&Path::new(&self.directory).join(file))),
+            ),
+            None => (String::new(), String::new()),
+        };
+        self.view
+            .label(cx, &[id!(preview_title)])
+            .set_text(cx, &title);
+        self.view
+            .label(cx, &[id!(preview_label)])
+            .set_text(cx, &body);
+        self.view
+            .view(cx, &[id!(preview_wrap)])
+            .set_visible(cx, !self.files.is_empty());
+    }
+
+    fn move_selection(&mut self, cx: &mut Cx, forward: bool) {
+        if self.files.is_empty() {
+            return;
+        }
+        self.selected = if forward {
+            (self.selected + 1).min(self.files.len() - 1)
+        } else {
+            self.selected.saturating_sub(1)
+        };
+        self.update_preview(cx);
+        self.view.redraw(cx);
+    }
+
+    fn close(&mut self, cx: &mut Cx) {
+        self.view.set_visible(cx, false);
+        self.view.redraw(cx);
+        cx.action(QuickOpenAction::Closed);
+    }
+
+    fn open(&mut self, cx: &mut Cx, index: usize) {
+        let Some(file) = self.files.get(index).cloned() else {
+            return;
+        };
+        self.view.set_visible(cx, false);
+        self.view.redraw(cx);
+        cx.action(QuickOpenAction::Open(file));
+    }
+}
+
+impl Widget for QuickOpen {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if !self.view.visible() {
+            return;
+        }
+
+        if let Event::KeyDown(ke) = event {
+            match ke.key_code {
+                KeyCode::Escape => {
+                    self.close(cx);
+                    return;
+                }
+                KeyCode::ArrowUp => self.move_selection(cx, false),
+                KeyCode::ArrowDown => self.move_selection(cx, true),
+                _ => {}
+            }
+        }
+
+        let actions = cx.capture_actions(|cx| {
+            self.view.handle_event(cx, event, scope);
+        });
+
+        let input = self.view.text_input(cx, &[id!(query_input)]);
+        if let Some(query) = input.changed(&actions) {
+            cx.action(QuickOpenAction::QueryChanged(query.trim().to_string()));
+        }
+        if input.returned(&actions).is_some() {
+            self.open(cx, self.selected);
+            return;
+        }
+
+        let list = self.view.portal_list(cx, &[id!(list)]);
+        for (item_id, widget) in list.items_with_actions(&actions) {
+            if item_id < self.files.len()
+                && widget.button(cx, &[id!(open_button)]).clicked(&actions)
+            {
+                self.open(cx, item_id);
+                return;
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                list.set_item_range(cx, 0, self.rows.len());
+                while let Some(item_id) = list.next_visible_item(cx) {
+                    let Some(row) = self.rows.get(item_id) else {
+                        continue;
+                    };
+                    let item_widget = list.item(cx, item_id, live_id!(FileRow));
+                    let marker = if item_id == self.selected { "›" } else { "" };
+                    item_widget
+                        .label(cx, &[id!(marker_label)])
+                        .set_text(cx, marker);
+                    item_widget
+                        .label(cx, &[id!(name_label)])
+                        .set_text(cx, &row.name);
+                    item_widget
+                        .label(cx, &[id!(dir_label)])
+                        .set_text(cx, &row.dir);
+                    item_widget.draw_all(cx, scope);
+                }
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl QuickOpenRef {
+    /// Opens the finder with an empty query and focuses the input. `mode`
+    /// decides whether previews are read from disk or through the server.
+    pub fn show(&self, cx: &mut Cx, directory: &str, mode: FileAccessMode) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.directory = directory.to_string();
+            inner.access_mode = mode;
+            inner.files.clear();
+            inner.rows.clear();
+            inner.selected = 0;
+            let input = inner.view.text_input(cx, &[id!(query_input)]);
+            input.set_text(cx, "");
+            input.set_key_focus(cx);
+            inner
+                .view
+                .label(cx, &[id!(status_label)])
+                .set_text(cx, "Type to search files");
+            inner.update_preview(cx);
+            inner.view.set_visible(cx, true);
+            inner.redraw(cx);
+        }
+    }
+
+    pub fn hide(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.view.set_visible(cx, false);
+            inner.redraw(cx);
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.borrow().is_some_and(|inner| inner.view.visible())
+    }
+
+    /// Shows a server-read preview, unless the selection has moved on.
+    pub fn set_preview(&self, cx: &mut Cx, file: &str, content: Result<String, ReadTextError>) {
+        if let Some(inner) = self.borrow() {
+            if inner.files.get(inner.selected).map(String::as_str) != Some(file) {
+                return;
+            }
+            inner
+                .view
+                .label(cx, &[id!(preview_label)])
+                .set_text(cx, &preview_text(content));
+        }
+    }
+
+    pub fn set_searching(&self, cx: &mut Cx) {
+        if let Some(inner) = self.borrow() {
+            inner
+                .view
+                .label(cx, &[id!(status_label)])
+                .set_text(cx, "Searching...");
+        }
+    }
+
+    /// Ranks the server's matches for `query` and previews the best one.
+    pub fn set_results(&self, cx: &mut Cx, query: &str, files: &[String]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.files = if query.is_empty() {
+                Vec::new()
+            } else {
+                rank_fuzzy(query, files)
+            };
+            inner.selected = 0;
+            inner.rebuild_rows();
+            let status = match inner.files.len() {
+                _ if query.is_empty() => "Type to search files".to_string(),
+                0 => format!("No files matching \"{}\"", query),
+                1 => "1 file".to_string(),
+                n => format!("{} files", n),
+            };
+            inner
+                .view
+                .label(cx, &[id!(status_label)])
+                .set_text(cx, &status);
+            inner.update_preview(cx);
+            inner.redraw(cx);
+        }
+    }
+}
@@ -0,0 +1,354 @@
+//! Project-wide text search sidebar. Submitting the query box emits
+//! `SearchPanelAction::Search`; results are grouped per file and clicking a
+//! match emits `SearchPanelAction::OpenMatch`.
+
+use crate::state::SearchFileGroup;
+use makepad_widgets::*;
+use openpad_protocol::TextSearchResult;
+
+/// Longest prefix kept in front of a highlighted match so the hit stays visible.
+const MATCH_CONTEXT_CHARS: usize = 40;
+
+script_mod! {
+    use mod.prelude.widgets_internal.*
+    use mod.widgets.*
+    use mod.theme.*
+
+    mod.widgets.SearchPanel = #(SearchPanel::register_widget(vm)) {
+        width: Fill, height: Fill
+        flow: Down
+        padding: Inset{ left: 10, right: 8, top: 8, bottom: 8 }
+        spacing: 6
+        new_batch: true
+
+        query_input := TextInput {
+            width: Fill
+            height: 30
+            empty_text: "Search in project (Enter)"
+            draw_bg +: {
+                color: #15181d
+                color_focus: #15181d
+                border_radius: 6.0
+                border_size: 1.0
+                border_color: #333
+            }
+            draw_text +: {
+                color: #e6e9ee
+                text_style: theme.font_code {font_size: 10}
+            }
+        }
+
+        summary_label := Label {
+            width: Fill, height: Fit
+            text: ""
+            draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 9.5 } }
+        }
+
+        list := PortalList {
+            width: Fill, height: Fill
+            scroll_bar: ScrollBar {
+                bar_size: 2.5
+                bar_side_margin: 2.0
+                smoothing: 0.15
+            }
+
+            FileHeader := View {
+                width: Fill, height: 24
+                flow: Right, align: Align{ y: 0.5 }
+                padding: Inset{ top: 4, left: 2, right: 2 }
+                spacing: 6
+
+                file_label := Label {
+                    width: Fill, height: Fit
+                    text: ""
+                    draw_text +: { color: theme.THEME_COLOR_TEXT_PRIMARY, text_style: theme.font_bold { font_size: 10 } }
+                }
+
+                count_label := Label {
+                    width: Fit, height: Fit
+                    text: ""
+                    draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 9 } }
+                }
+            }
+
+            MatchRow := View {
+                width: Fill, height: 20
+                flow: Overlay
+
+                View {
+                    width: Fill, height: Fill
+                    flow: Right, align: Align{ y: 0.5 }
+                    padding: Inset{ left: 12, right: 4 }
+                    spacing: 0
+
+                    line_label := Label {
+                        width: 36, height: Fit
+                        text: ""
+                        draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED_DARK, text_style: theme.font_code { font_size: 9 } }
+                    }
+                    before_label := Label {
+                        width: Fit, height: Fit
+                        text: ""
+                        draw_text +: { color: theme.THEME_COLOR_TEXT_NORMAL, text_style: theme.font_code { font_size: 9 } }
+                    }
+                    hit_wrap := RoundedView {
+                        width: Fit, height: Fit
+                        show_bg: true
+                        draw_bg +: {
+                            color: #f59e0b33
+                            border_radius: 2.0
+                        }
+                        hit_label := Label {
+                            width: Fit, height: Fit
+                            text: ""
+                            draw_text +: { color: theme.THEME_COLOR_ACCENT_AMBER, text_style: theme.font_code { font_size: 9 } }
+                        }
+                    }
+                    after_label := Label {
+                        width: Fill, height: Fit
+                        text: ""
+                        draw_text +: { color: theme.THEME_COLOR_TEXT_NORMAL, text_style: theme.font_code { font_size: 9 } }
+                    }
+                }
+
+                open_button := Button {
+                    width: Fill, height: Fill
+                    text: ""
+                    draw_bg +: {
+                        color: theme.THEME_COLOR_TRANSPARENT
+                        color_hover: theme.THEME_COLOR_HOVER_SUBTLE
+                        color_active: theme.THEME_COLOR_HOVER_SUBTLE
+                        border_radius: 4.0
+                        border_size: 0.0
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub enum SearchPanelAction {
+    #[default]
+    None,
+    Search(String),
+    /// Open `absolute_path` with the zero-based `line` revealed.
+    OpenMatch {
+        absolute_path: String,
+        line: usize,
+    },
+}
+
+#[derive(Clone, Debug)]
+enum SearchRowKind {
+    FileHeader {
+        path: String,
+        count: String,
+    },
+    Match {
+        absolute_path: String,
+        line: usize,
+        line_label: String,
+        before: String,
+        hit: String,
+        after: String,
+    },
+}
+
+/// Splits a matched line into the text before, inside and after its first submatch.
+/// Offsets that don't fall on char boundaries are treated as "no highlight".
+fn split_match_line(result: &TextSearchResult) -> (String, String, String) {
+    let line = result.lines.trim_end_matches(['\n', '\r']);
+    let span = result
+        .submatches
+        .first()
+        .filter(|m| {
+            m.start <= m.end
+                && m.end <= line.len()
+                && line.is_char_boundary(m.start)
+                && line.is_char_boundary(m.end)
+        })
+        .map(|m| (m.start, m.end));
+    let Some((start, end)) = span else {
+        return (line.trim_start().to_string(), String::new(), String::new());
+    };
+
+    let before = line[..start].trim_start();
+    let before = match before.char_indices().rev().nth(MATCH_CONTEXT_CHARS) {
+        Some((idx, _)) => format!("…{}", &before[idx..]),
+        None => before.to_string(),
+    };
+    (
+        before,
+        line[start..end].to_string(),
+        line[end..].to_string(),
+    )
+}
+
+#[derive(Script, ScriptHook, Widget)]
+pub struct SearchPanel {
+    #[source]
+    source: ScriptObjectRef,
+
+    #[deref]
+    view: View,
+
+    #[rust]
+    rows: Vec<SearchRowKind>,
+}
+
+impl SearchPanel {
+    fn rebuild_rows(&mut self, groups: &[SearchFileGroup]) {
+        // Optimization: precompute every display string here instead of in draw_walk.
+        let mut rows = Vec::new();
+        for group in groups {
+            rows.push(SearchRowKind::FileHeader {
+                path: group.path.clone(),
+                count: group.matches.len().to_string(),
+            });
+            for result in &group.matches {
+                let (before, hit, after) = split_match_line(result);
+                rows.push(SearchRowKind::Match {
+                    absolute_path: group.absolute_path.clone(),
+                    line: result.line_number.saturating_sub(1),
+                    line_label: result.line_number.to_string(),
+                    before,
+                    hit,
+                    after,
+                });
+            }
+        }
+        self.rows = rows;
+    }
+}
+
+impl Widget for SearchPanel {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        let actions = cx.capture_actions(|cx| {
+            self.view.handle_event(cx, event, scope);
+        });
+
+        if let Some((query, _)) = self
+            .view
+            .text_input(cx, &[id!(query_input)])
+            .returned(&actions)
+        {
+            let query = query.trim().to_string();
+            if !query.is_empty() {
+                cx.action(SearchPanelAction::Search(query));
+            }
+        }
+
+        let list = self.view.portal_list(cx, &[id!(list)]);
+        for (item_id, widget) in list.items_with_actions(&actions) {
+            let Some(SearchRowKind::Match {
+                absolute_path,
+                line,
+                ..
+            }) = self.rows.get(item_id)
+            else {
+                continue;
+            };
+            if widget.button(cx, &[id!(open_button)]).clicked(&actions) {
+                cx.action(SearchPanelAction::OpenMatch {
+                    absolute_path: absolute_path.clone(),
+                    line: *line,
+                });
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                list.set_item_range(cx, 0, self.rows.len());
+                while let Some(item_id) = list.next_visible_item(cx) {
+                    let Some(row) = self.rows.get(item_id) else {
+                        continue;
+                    };
+                    match row {
+                        SearchRowKind::FileHeader { path, count } => {
+                            let item_widget = list.item(cx, item_id, live_id!(FileHeader));
+                            item_widget.label(cx, &[id!(file_label)]).set_text(cx, path);
+                            item_widget
+                                .label(cx, &[id!(count_label)])
+                                .set_text(cx, count);
+                            item_widget.draw_all(cx, scope);
+                        }
+                        SearchRowKind::Match {
+                            line_label,
+                            before,
+                            hit,
+                            after,
+                            ..
+                        } => {
+                            let item_widget = list.item(cx, item_id, live_id!(MatchRow));
+                            item_widget
+                                .label(cx, &[id!(line_label)])
+                                .set_text(cx, line_label);
+                            item_widget
+                                .label(cx, &[id!(before_label)])
+                                .set_text(cx, before);
+                            item_widget.label(cx, &[id!(hit_label)]).set_text(cx, hit);
+                            item_widget
+                                .view(cx, &[id!(hit_wrap)])
+                                .set_visible(cx, !hit.is_empty());
+                            item_widget
+                                .label(cx, &[id!(after_label)])
+                                .set_text(cx, after);
+                            item_widget.draw_all(cx, scope);
+                        }
+                    }
+                }
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl SearchPanelRef {
+    pub fn set_loading(&self, cx: &mut Cx, query: &str) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.rows.clear();
+            inner
+                .view
+                .label(cx, &[id!(summary_label)])
+                .set_text(cx, &format!("Searching for \"{}\"...", query));
+            inner.redraw(cx);
+        }
+    }
+
+    pub fn set_results(&self, cx: &mut Cx, query: &str, groups: &[SearchFileGroup]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.rebuild_rows(groups);
+            let matches: usize = groups.iter().map(|g| g.matches.len()).sum();
+            let summary = if matches == 0 {
+                format!("No results for \"{}\"", query)
+            } else {
+                let match_label = if matches == 1 { "match" } else { "matches" };
+                let file_label = if groups.len() == 1 { "file" } else { "files" };
+                format!(
+                    "{} {} in {} {}",
+                    matches,
+                    match_label,
+                    groups.len(),
+                    file_label
+                )
+            };
+            inner
+                .view
+                .label(cx, &[id!(summary_label)])
+                .set_text(cx, &summary);
+            inner.redraw(cx);
+        }
+    }
+
+    pub fn focus_query(&self, cx: &mut Cx) {
+        if let Some(inner) = self.borrow() {
+            inner
+                .view
+                .text_input(cx, &[id!(query_input)])
+                .set_key_focus(cx);
+        }
+    }
+}
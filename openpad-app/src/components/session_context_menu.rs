@@ -72,6 +72,18 @@ script_mod! {
                 draw_text +: { color: #e6e9ee, text_style: theme.font_regular { font_size: 10 } }
             }
 
+            menu_archive := Button {
+                width: Fit, height: 22
+                text: "Archive"
+                draw_bg +: {
+                    color: #0000
+                    color_hover: #333
+                    border_radius: 4.0
+                    border_size: 0.0
+                }
+                draw_text +: { color: #e6e9ee, text_style: theme.font_regular { font_size: 10 } }
+            }
+
             menu_abort := Button {
                 width: Fit, height: 22
                 text: "Abort"
@@ -112,6 +124,8 @@ pub struct SessionContextMenu {
     session_id: Option<String>,
     #[rust]
     working: bool,
+    #[rust]
+    archived: bool,
 }
 
 impl Widget for SessionContextMenu {
@@ -139,6 +153,13 @@ impl Widget for SessionContextMenu {
         } else if self.view.button(cx, &[id!(menu_branch)]).clicked(&actions) {
             cx.action(ProjectsPanelAction::BranchSession(session_id));
             cx.action(ProjectsPanelAction::CloseSessionContextMenu);
+        } else if self.view.button(cx, &[id!(menu_archive)]).clicked(&actions) {
+            if self.archived {
+                cx.action(ProjectsPanelAction::UnarchiveSession(session_id));
+            } else {
+                cx.action(ProjectsPanelAction::ArchiveSession(session_id));
+            }
+            cx.action(ProjectsPanelAction::CloseSessionContextMenu);
         } else if self.view.button(cx, &[id!(menu_abort)]).clicked(&actions) {
             cx.action(ProjectsPanelAction::AbortSession(session_id));
             cx.action(ProjectsPanelAction::CloseSessionContextMenu);
@@ -150,14 +171,27 @@ impl Widget for SessionContextMenu {
 }
 
 impl SessionContextMenuRef {
-    pub fn open(&self, cx: &mut Cx, session_id: String, _x: f32, _y: f32, working: bool) {
+    pub fn open(
+        &self,
+        cx: &mut Cx,
+        session_id: String,
+        _x: f32,
+        _y: f32,
+        working: bool,
+        archived: bool,
+    ) {
         if let Some(mut inner) = self.borrow_mut() {
             inner.session_id = Some(session_id);
             inner.working = working;
+            inner.archived = archived;
             inner
                 .view
                 .view(cx, &[id!(menu_box), id!(menu_abort)])
                 .set_visible(cx, working);
+            inner
+                .view
+                .button(cx, &[id!(menu_box), id!(menu_archive)])
+                .set_text(cx, if archived { "Unarchive" } else { "Archive" });
             inner.redraw(cx);
         }
     }
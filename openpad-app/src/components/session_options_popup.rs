@@ -80,6 +80,23 @@ script_mod! {
                     }
                 }
 
+                archive_btn := Button {
+                    width: Fill, height: 30
+                    new_batch: true
+                    text: "Archive"
+                    draw_bg +: {
+                        color: #364154
+                        color_hover: #50607c
+                        color_active: #50607c
+                        border_radius: 7.0
+                        border_size: 0.0
+                    }
+                    draw_text +: {
+                        color: #f8fafc
+                        text_style: theme.font_regular { font_size: 11 }
+                    }
+                }
+
                 abort_btn := Button {
                     width: Fill, height: 30
                     new_batch: true
@@ -231,6 +248,8 @@ pub struct SessionOptionsPopup {
     #[rust]
     working: bool,
     #[rust]
+    archived: bool,
+    #[rust]
     mode: PopupMode,
 }
 
@@ -270,6 +289,13 @@ impl Widget for SessionOptionsPopup {
                 {
                     cx.action(ProjectsPanelAction::BranchSession(session_id));
                     cx.action(ProjectsPanelAction::CloseSessionContextMenu);
+                } else if self.view.button(cx, &[id!(archive_btn)]).clicked(&actions) {
+                    if self.archived {
+                        cx.action(ProjectsPanelAction::UnarchiveSession(session_id));
+                    } else {
+                        cx.action(ProjectsPanelAction::ArchiveSession(session_id));
+                    }
+                    cx.action(ProjectsPanelAction::CloseSessionContextMenu);
                 } else if self.view.button(cx, &[id!(abort_btn)]).clicked(&actions) {
                     cx.action(ProjectsPanelAction::AbortSession(session_id));
                     cx.action(ProjectsPanelAction::CloseSessionContextMenu);
@@ -295,12 +321,13 @@ impl Widget for SessionOptionsPopup {
 }
 
 impl SessionOptionsPopupRef {
-    pub fn show(&self, cx: &mut Cx, session_id: String, working: bool) {
+    pub fn show(&self, cx: &mut Cx, session_id: String, working: bool, archived: bool) {
         if let Some(mut inner) = self.borrow_mut() {
             inner.mode = PopupMode::Session;
             inner.session_id = Some(session_id);
             inner.project_id = None;
             inner.working = working;
+            inner.archived = archived;
 
             inner
                 .view
@@ -318,6 +345,14 @@ impl SessionOptionsPopupRef {
                 .view
                 .button(cx, &[id!(secondary_btn)])
                 .set_visible(cx, true);
+            inner
+                .view
+                .button(cx, &[id!(archive_btn)])
+                .set_text(cx, if archived { "Unarchive" } else { "Archive" });
+            inner
+                .view
+                .button(cx, &[id!(archive_btn)])
+                .set_visible(cx, true);
             inner
                 .view
                 .button(cx, &[id!(abort_btn)])
@@ -354,6 +389,7 @@ impl SessionOptionsPopupRef {
             inner.session_id = None;
             inner.project_id = project_id;
             inner.working = false;
+            inner.archived = false;
 
             inner
                 .view
@@ -367,6 +403,10 @@ impl SessionOptionsPopupRef {
                 .view
                 .button(cx, &[id!(secondary_btn)])
                 .set_visible(cx, false);
+            inner
+                .view
+                .button(cx, &[id!(archive_btn)])
+                .set_visible(cx, false);
             inner
                 .view
                 .button(cx, &[id!(abort_btn)])
@@ -1,8 +1,14 @@
 //! Sessions panel for IDE-style right sidebar using Makepad's FileTree widget.
-//! Shows sessions grouped by project in a collapsible tree.
+//! Shows sessions grouped by project in a collapsible tree, with branches and
+//! subagent sessions nested under the session they were spawned from.
+//! Archived sessions are kept out of the project groups and listed under a
+//! collapsed "Archived" folder instead. Sessions arrive already filtered and
+//! sorted; they can be grouped by day of last update instead of by project.
 
 use crate::async_runtime;
 use crate::state::actions::ProjectsPanelAction;
+use crate::state::reducer::{date_bucket, session_ancestors};
+use crate::state::{AttentionKind, DateBucket};
 use makepad_widgets::*;
 use openpad_protocol::{Project, Session, SessionSummary};
 use openpad_widgets::{SessionTree, SessionTreeAction};
@@ -64,6 +70,20 @@ script_mod! {
     }
 }
 
+/// A tree row, collected from `&self` before drawing into the tree.
+enum TreeOp {
+    Session {
+        node_id: LiveId,
+        session_id: String,
+        label: String,
+    },
+    BeginChildren {
+        node_id: LiveId,
+        label: String,
+    },
+    EndChildren,
+}
+
 #[derive(Script, ScriptHook, Widget)]
 pub struct SessionsPanel {
     #[wrap]
@@ -78,10 +98,18 @@ pub struct SessionsPanel {
     selected_session_id: Option<String>,
     #[rust]
     working_by_session: HashMap<String, bool>,
+    /// Most urgent attention reason per session
+    #[rust]
+    attention: HashMap<String, AttentionKind>,
     #[rust]
     session_node_to_id: HashMap<LiveId, String>,
     #[rust]
     project_node_to_id: HashMap<LiveId, Option<String>>,
+    #[rust]
+    group_by_date: bool,
+    /// Reference time for the date buckets, taken when the data was set
+    #[rust]
+    now_ms: i64,
 
     #[rust]
     current_dir_name: String,
@@ -119,6 +147,144 @@ impl SessionsPanel {
         LiveId::from_str(&format!("sessions_session:{}", session_id))
     }
 
+    fn date_node_id(bucket: DateBucket) -> LiveId {
+        LiveId::from_str(&format!("sessions_date:{}", bucket.label()))
+    }
+
+    fn archived_node_id() -> LiveId {
+        LiveId::from_str("sessions_archived")
+    }
+
+    fn children_node_id(session_id: &str) -> LiveId {
+        LiveId::from_str(&format!("sessions_children:{}", session_id))
+    }
+
+    /// Child sessions keyed by parent id, plus the sessions to list at the top
+    /// level: those without a parent or whose parent is not loaded.
+    fn split_roots<'a>(
+        sessions: &[&'a Session],
+    ) -> (Vec<&'a Session>, HashMap<&'a str, Vec<&'a Session>>) {
+        let ids: HashSet<&str> = sessions.iter().map(|s| s.id.as_str()).collect();
+        let mut roots = Vec::new();
+        let mut children: HashMap<&str, Vec<&Session>> = HashMap::new();
+        for &session in sessions {
+            match session.parent_id.as_deref() {
+                Some(parent_id) if ids.contains(parent_id) => {
+                    children.entry(parent_id).or_default().push(session)
+                }
+                _ => roots.push(session),
+            }
+        }
+        (roots, children)
+    }
+
+    fn is_working(&self, session_id: &str) -> bool {
+        self.working_by_session
+            .get(session_id)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    fn subtree_working(&self, session_id: &str, children: &HashMap<&str, Vec<&Session>>) -> bool {
+        self.is_working(session_id)
+            || children.get(session_id).is_some_and(|kids| {
+                kids.iter()
+                    .any(|kid| self.subtree_working(&kid.id, children))
+            })
+    }
+
+    fn push_session_ops(
+        &self,
+        session: &Session,
+        children: &HashMap<&str, Vec<&Session>>,
+        ops: &mut Vec<TreeOp>,
+    ) {
+        ops.push(TreeOp::Session {
+            node_id: Self::session_node_id(&session.id),
+            session_id: session.id.clone(),
+            label: self.session_display_label(session),
+        });
+        let Some(kids) = children.get(session.id.as_str()) else {
+            return;
+        };
+        let working = kids
+            .iter()
+            .filter(|kid| self.subtree_working(&kid.id, children))
+            .count();
+        let mut label = if kids.len() == 1 {
+            "↳ 1 child session".to_string()
+        } else {
+            format!("↳ {} child sessions", kids.len())
+        };
+        if working > 0 {
+            label.push_str(&format!("   ● {} working", working));
+        }
+        ops.push(TreeOp::BeginChildren {
+            node_id: Self::children_node_id(&session.id),
+            label,
+        });
+        for kid in kids {
+            self.push_session_ops(kid, children, ops);
+        }
+        ops.push(TreeOp::EndChildren);
+    }
+
+    /// Takes the tree and node map separately so callers can keep borrowing
+    /// `self.sessions`.
+    fn draw_ops(
+        file_tree: &mut SessionTree,
+        session_node_to_id: &mut HashMap<LiveId, String>,
+        cx: &mut Cx2d,
+        ops: Vec<TreeOp>,
+    ) {
+        // Depth of collapsed child folders being skipped.
+        let mut skipped = 0usize;
+        for op in ops {
+            if skipped > 0 {
+                match op {
+                    TreeOp::BeginChildren { .. } => skipped += 1,
+                    TreeOp::EndChildren => skipped -= 1,
+                    TreeOp::Session { .. } => {}
+                }
+                continue;
+            }
+            match op {
+                TreeOp::Session {
+                    node_id,
+                    session_id,
+                    label,
+                } => {
+                    session_node_to_id.insert(node_id, session_id);
+                    file_tree.file(cx, node_id, &label);
+                }
+                TreeOp::BeginChildren { node_id, label } => {
+                    if file_tree.begin_folder(cx, node_id, &label).is_err() {
+                        skipped = 1;
+                    }
+                }
+                TreeOp::EndChildren => file_tree.end_folder(),
+            }
+        }
+    }
+
+    /// A folder holding `ops`, left out entirely when there is nothing to show.
+    fn draw_group(
+        file_tree: &mut SessionTree,
+        session_node_to_id: &mut HashMap<LiveId, String>,
+        cx: &mut Cx2d,
+        node_id: LiveId,
+        label: &str,
+        ops: Vec<TreeOp>,
+    ) {
+        if ops.is_empty() {
+            return;
+        }
+        if file_tree.begin_folder(cx, node_id, label).is_ok() {
+            Self::draw_ops(file_tree, session_node_to_id, cx, ops);
+            file_tree.end_folder();
+        }
+    }
+
     fn session_diff_stats(summary: &SessionSummary) -> Option<(String, String, String)> {
         let (files, additions, deletions) = if !summary.diffs.is_empty() {
             let additions: i64 = summary.diffs.iter().map(|d| d.additions).sum();
@@ -176,15 +342,15 @@ impl SessionsPanel {
             }
         }
 
-        if self
-            .working_by_session
-            .get(&session.id)
-            .copied()
-            .unwrap_or(false)
-        {
+        if self.is_working(&session.id) {
             label.push_str("   ●");
         }
 
+        if let Some(kind) = self.attention.get(&session.id) {
+            label.push_str("   ");
+            label.push_str(kind.badge());
+        }
+
         if self
             .selected_session_id
             .as_ref()
@@ -208,73 +374,105 @@ impl SessionsPanel {
                 .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
                 .unwrap_or_else(|| ".".to_string());
         }
-        let current_dir_name = &self.current_dir_name;
 
-        // Optimization: use references instead of cloning projects and sessions every frame.
+        // Optimization: use references instead of cloning sessions every frame.
         // This avoids $O(N)$ heap allocations in the draw loop.
-        let projects = &self.projects;
-        let sessions = &self.sessions;
-
-        let mut grouped: HashMap<String, Vec<&Session>> = HashMap::new();
-        for session in sessions {
-            grouped
-                .entry(session.project_id.clone())
-                .or_default()
-                .push(session);
-        }
-
-        let mut known_project_ids = HashSet::new();
-        for project in projects {
-            if project.worktree == "/" || project.worktree.is_empty() {
-                continue;
+        let (archived, active): (Vec<&Session>, Vec<&Session>) = self
+            .sessions
+            .iter()
+            .partition(|session| session.time.is_archived());
+        let (roots, children) = Self::split_roots(&active);
+        if self.group_by_date {
+            for bucket in DateBucket::ALL {
+                let mut ops = Vec::new();
+                for session in roots
+                    .iter()
+                    .filter(|session| date_bucket(session.time.updated, self.now_ms) == bucket)
+                {
+                    self.push_session_ops(session, &children, &mut ops);
+                }
+                Self::draw_group(
+                    &mut self.file_tree,
+                    &mut self.session_node_to_id,
+                    cx,
+                    Self::date_node_id(bucket),
+                    bucket.label(),
+                    ops,
+                );
+            }
+        } else {
+            let mut grouped: HashMap<&str, Vec<&Session>> = HashMap::new();
+            for &session in &roots {
+                grouped
+                    .entry(session.project_id.as_str())
+                    .or_default()
+                    .push(session);
             }
 
-            known_project_ids.insert(project.id.clone());
+            let mut known_project_ids = HashSet::new();
+            for project in &self.projects {
+                if project.worktree == "/" || project.worktree.is_empty() {
+                    continue;
+                }
+
+                known_project_ids.insert(project.id.as_str());
+
+                let project_name = Self::derive_project_name(project, &self.current_dir_name);
+                let project_node_id = Self::project_node_id(&project.id);
+                self.project_node_to_id
+                    .insert(project_node_id, Some(project.id.clone()));
 
-            let project_name = Self::derive_project_name(project, current_dir_name);
-            let project_node_id = Self::project_node_id(&project.id);
-            self.project_node_to_id
-                .insert(project_node_id, Some(project.id.clone()));
+                let mut ops = Vec::new();
+                for session in grouped.get(project.id.as_str()).into_iter().flatten() {
+                    self.push_session_ops(session, &children, &mut ops);
+                }
+                if self
+                    .file_tree
+                    .begin_folder(cx, project_node_id, &project_name)
+                    .is_ok()
+                {
+                    Self::draw_ops(&mut self.file_tree, &mut self.session_node_to_id, cx, ops);
+                    self.file_tree.end_folder();
+                }
+            }
 
-            if self
-                .file_tree
-                .begin_folder(cx, project_node_id, &project_name)
-                .is_ok()
+            // Walk `roots` rather than the map so the chosen sort order holds.
+            let ungrouped: Vec<&Session> = roots
+                .iter()
+                .copied()
+                .filter(|session| !known_project_ids.contains(session.project_id.as_str()))
+                .collect();
+
+            if !ungrouped.is_empty()
+                && self
+                    .file_tree
+                    .begin_folder(cx, Self::other_project_node_id(), "Other")
+                    .is_ok()
             {
-                if let Some(project_sessions) = grouped.get(&project.id) {
-                    for session in project_sessions {
-                        let node_id = Self::session_node_id(&session.id);
-                        let label = self.session_display_label(session);
-                        self.session_node_to_id.insert(node_id, session.id.clone());
-                        self.file_tree.file(cx, node_id, &label);
-                    }
+                self.project_node_to_id
+                    .insert(Self::other_project_node_id(), None);
+                let mut ops = Vec::new();
+                for session in &ungrouped {
+                    self.push_session_ops(session, &children, &mut ops);
                 }
+                Self::draw_ops(&mut self.file_tree, &mut self.session_node_to_id, cx, ops);
                 self.file_tree.end_folder();
             }
         }
 
-        let ungrouped: Vec<&Session> = grouped
-            .into_iter()
-            .filter(|(project_id, _)| !known_project_ids.contains(project_id))
-            .flat_map(|(_, sessions)| sessions)
-            .collect();
-
-        if !ungrouped.is_empty()
-            && self
-                .file_tree
-                .begin_folder(cx, Self::other_project_node_id(), "Other")
-                .is_ok()
-        {
-            self.project_node_to_id
-                .insert(Self::other_project_node_id(), None);
-            for session in &ungrouped {
-                let node_id = Self::session_node_id(&session.id);
-                let label = self.session_display_label(session);
-                self.session_node_to_id.insert(node_id, session.id.clone());
-                self.file_tree.file(cx, node_id, &label);
-            }
-            self.file_tree.end_folder();
+        let (archived_roots, archived_children) = Self::split_roots(&archived);
+        let mut ops = Vec::new();
+        for session in archived_roots {
+            self.push_session_ops(session, &archived_children, &mut ops);
         }
+        Self::draw_group(
+            &mut self.file_tree,
+            &mut self.session_node_to_id,
+            cx,
+            Self::archived_node_id(),
+            &format!("Archived ({})", archived.len()),
+            ops,
+        );
     }
 }
 
@@ -294,26 +492,25 @@ impl Widget for SessionsPanel {
             } else if let SessionTreeAction::FileRightClicked(node_id) = item.cast() {
                 log!("SessionTree action: FileRightClicked {:?}", node_id);
                 if let Some(session_id) = self.session_node_to_id.get(&node_id).cloned() {
-                    let working = self
-                        .working_by_session
-                        .get(&session_id)
-                        .copied()
-                        .unwrap_or(false);
+                    let working = self.is_working(&session_id);
+                    let archived = self
+                        .sessions
+                        .iter()
+                        .any(|s| s.id == session_id && s.time.is_archived());
                     cx.action(ProjectsPanelAction::OpenSessionContextMenu {
                         session_id,
                         x: 0.0,
                         y: 0.0,
                         working,
+                        archived,
                     });
                 }
             } else if let SessionTreeAction::FolderRightClicked(node_id) = item.cast() {
                 log!("SessionTree action: FolderRightClicked {:?}", node_id);
-                let project_id = self
-                    .project_node_to_id
-                    .get(&node_id)
-                    .cloned()
-                    .unwrap_or(None);
-                cx.action(ProjectsPanelAction::OpenProjectContextMenu { project_id });
+                // Child-session folders have no context menu.
+                if let Some(project_id) = self.project_node_to_id.get(&node_id).cloned() {
+                    cx.action(ProjectsPanelAction::OpenProjectContextMenu { project_id });
+                }
             } else if let SessionTreeAction::FolderLeftClicked(node_id) = item.cast() {
                 log!("SessionTree action: FolderLeftClicked {:?}", node_id);
             }
@@ -340,12 +537,43 @@ impl SessionsPanelRef {
         sessions: Vec<Session>,
         selected_session_id: Option<String>,
         working_by_session: HashMap<String, bool>,
+        attention: HashMap<String, AttentionKind>,
+        group_by_date: bool,
     ) {
         if let Some(mut inner) = self.borrow_mut() {
+            // Reveal a newly selected child session; later collapses stick.
+            if selected_session_id != inner.selected_session_id {
+                if let Some(selected) = selected_session_id.as_deref() {
+                    let archived = sessions
+                        .iter()
+                        .any(|s| s.id == selected && s.time.is_archived());
+                    if archived {
+                        inner.file_tree.set_folder_is_open(
+                            cx,
+                            SessionsPanel::archived_node_id(),
+                            true,
+                            Animate::No,
+                        );
+                    }
+                    for ancestor in session_ancestors(&sessions, selected) {
+                        inner.file_tree.set_folder_is_open(
+                            cx,
+                            SessionsPanel::children_node_id(&ancestor.id),
+                            true,
+                            Animate::No,
+                        );
+                    }
+                }
+            }
             inner.projects = projects;
             inner.sessions = sessions;
             inner.selected_session_id = selected_session_id;
             inner.working_by_session = working_by_session;
+            inner.attention = attention;
+            inner.group_by_date = group_by_date;
+            inner.now_ms = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as i64);
 
             let project_ids: Vec<String> = inner.projects.iter().map(|p| p.id.clone()).collect();
             for project_id in project_ids {
@@ -362,6 +590,14 @@ impl SessionsPanelRef {
                 true,
                 Animate::No,
             );
+            for bucket in DateBucket::ALL {
+                inner.file_tree.set_folder_is_open(
+                    cx,
+                    SessionsPanel::date_node_id(bucket),
+                    true,
+                    Animate::No,
+                );
+            }
 
             inner.file_tree.redraw(cx);
         }
@@ -39,6 +39,29 @@ script_mod! {
                 }
             }
 
+            search_tab := Button {
+                width: Fit, height: 28
+                text: "Search"
+                draw_bg +: {
+                    color: theme.THEME_COLOR_TRANSPARENT
+                    color_hover: theme.THEME_COLOR_HOVER_SUBTLE
+                    color_active: theme.THEME_COLOR_HOVER_SUBTLE
+                    border_radius: 6.0
+                    border_size: 0.0
+                }
+                draw_text +: {
+                    color: theme.THEME_COLOR_TEXT_PRIMARY
+                    text_style: theme.font_regular { font_size: 11 }
+                }
+                animator: Animator {
+                    selected: {
+                        default: off
+                        off: { apply: { draw_bg: { color: (theme.THEME_COLOR_TRANSPARENT) } } }
+                        on: { apply: { draw_bg: { color: (theme.THEME_COLOR_HOVER_SUBTLE) } } }
+                    }
+                }
+            }
+
             settings_tab := Button {
                 width: Fit, height: 28
                 text: "Settings"
@@ -94,28 +117,25 @@ impl Widget for SidebarHeader {
         if self.view.button(cx, &[id!(projects_tab)]).clicked(&actions) {
             cx.action(AppAction::SetSidebarMode(SidebarMode::Files));
         }
+        if self.view.button(cx, &[id!(search_tab)]).clicked(&actions) {
+            cx.action(AppAction::SetSidebarMode(SidebarMode::Search));
+        }
         if self.view.button(cx, &[id!(settings_tab)]).clicked(&actions) {
             cx.action(AppAction::SetSidebarMode(SidebarMode::Settings));
         }
     }
 
     fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
-        let is_files = self.mode == SidebarMode::Files;
-
-        if is_files {
-            self.view
-                .view(cx, &[id!(projects_tab)])
-                .animator_play(cx, &[id!(selected), id!(on)]);
-            self.view
-                .view(cx, &[id!(settings_tab)])
-                .animator_play(cx, &[id!(selected), id!(off)]);
-        } else {
-            self.view
-                .view(cx, &[id!(projects_tab)])
-                .animator_play(cx, &[id!(selected), id!(off)]);
+        let tabs = [
+            (id!(projects_tab), SidebarMode::Files),
+            (id!(search_tab), SidebarMode::Search),
+            (id!(settings_tab), SidebarMode::Settings),
+        ];
+        for (tab, mode) in tabs {
+            let state = if self.mode == mode { id!(on) } else { id!(off) };
             self.view
-                .view(cx, &[id!(settings_tab)])
-                .animator_play(cx, &[id!(selected), id!(on)]);
+                .view(cx, &[tab])
+                .animator_play(cx, &[id!(selected), state]);
         }
 
         self.view.draw_walk(cx, scope, walk)
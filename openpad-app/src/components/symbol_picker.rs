@@ -0,0 +1,224 @@
+//! Popup list of workspace symbols shown above the composer while the user
+//! types a `#query` token. Picking a row emits `SymbolPickerAction::Selected`.
+
+use makepad_widgets::*;
+use openpad_protocol::Symbol;
+
+/// Maximum number of symbol rows shown in the picker.
+pub const SYMBOL_PICKER_MAX_ROWS: usize = 8;
+
+script_mod! {
+    use mod.prelude.widgets_internal.*
+    use mod.widgets.*
+    use mod.theme.*
+
+    mod.widgets.SymbolPicker = #(SymbolPicker::register_widget(vm)) {
+        width: Fill, height: Fit
+        flow: Down
+        visible: false
+
+        RoundedView {
+            width: Fill, height: Fit
+            flow: Down
+            padding: Inset{ left: 4, right: 4, top: 4, bottom: 4 }
+            show_bg: true
+            draw_bg +: {
+                color: #1f242c
+                border_radius: 7.0
+                border_size: 1.0
+                border_color: #333
+            }
+
+            status_wrap := View {
+                width: Fill, height: Fit
+                padding: Inset{ left: 6, right: 6, top: 2, bottom: 2 }
+                status_label := Label {
+                    width: Fill, height: Fit
+                    text: "Searching symbols..."
+                    draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 9.5 } }
+                }
+            }
+
+            list := PortalList {
+                width: Fill, height: 176
+                scroll_bar: ScrollBar {
+                    bar_size: 2.5
+                    bar_side_margin: 2.0
+                    smoothing: 0.15
+                }
+
+                SymbolRow := View {
+                    width: Fill, height: 22
+                    flow: Right, align: Align{ y: 0.5 }
+                    spacing: 6
+
+                    symbol_button := Button {
+                        width: Fit, height: Fill
+                        padding: Inset{ left: 6, right: 6, top: 0, bottom: 0 }
+                        align: Align{ x: 0.0, y: 0.5 }
+                        text: ""
+                        draw_bg +: {
+                            color: theme.THEME_COLOR_TRANSPARENT
+                            color_hover: theme.THEME_COLOR_HOVER_SUBTLE
+                            color_active: theme.THEME_COLOR_HOVER_SUBTLE
+                            border_radius: 4.0
+                            border_size: 0.0
+                        }
+                        draw_text +: { color: theme.THEME_COLOR_TEXT_PRIMARY, text_style: theme.font_code { font_size: 10 } }
+                    }
+
+                    kind_label := Label {
+                        width: Fit, height: Fit
+                        text: ""
+                        draw_text +: { color: theme.THEME_COLOR_ACCENT_PURPLE, text_style: theme.font_regular { font_size: 9 } }
+                    }
+
+                    path_label := Label {
+                        width: Fill, height: Fit
+                        text: ""
+                        draw_text +: { color: theme.THEME_COLOR_TEXT_MUTED, text_style: theme.font_regular { font_size: 9 } }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub enum SymbolPickerAction {
+    #[default]
+    None,
+    Selected(Symbol),
+}
+
+/// Cached display strings for a symbol row.
+#[derive(Clone, Debug)]
+struct SymbolRowItem {
+    name: String,
+    kind: &'static str,
+    location: String,
+}
+
+#[derive(Script, ScriptHook, Widget)]
+pub struct SymbolPicker {
+    #[source]
+    source: ScriptObjectRef,
+
+    #[deref]
+    view: View,
+
+    #[rust]
+    symbols: Vec<Symbol>,
+    #[rust]
+    rows: Vec<SymbolRowItem>,
+}
+
+impl SymbolPicker {
+    fn rebuild_rows(&mut self) {
+        // Optimization: build display strings once instead of per draw.
+        self.rows = self
+            .symbols
+            .iter()
+            .map(|symbol| SymbolRowItem {
+                name: symbol.name.clone(),
+                kind: symbol.kind_label(),
+                location: format!(
+                    "{}:{}",
+                    symbol.path(),
+                    symbol.location.range.start.line as i64 + 1
+                ),
+            })
+            .collect();
+    }
+}
+
+impl Widget for SymbolPicker {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        let actions = cx.capture_actions(|cx| {
+            self.view.handle_event(cx, event, scope);
+        });
+
+        let list = self.view.portal_list(cx, &[id!(list)]);
+        for (item_id, widget) in list.items_with_actions(&actions) {
+            if item_id >= self.symbols.len() {
+                continue;
+            }
+            if widget.button(cx, &[id!(symbol_button)]).clicked(&actions) {
+                cx.action(SymbolPickerAction::Selected(self.symbols[item_id].clone()));
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                list.set_item_range(cx, 0, self.rows.len());
+                while let Some(item_id) = list.next_visible_item(cx) {
+                    let Some(row) = self.rows.get(item_id) else {
+                        continue;
+                    };
+                    let item_widget = list.item(cx, item_id, live_id!(SymbolRow));
+                    item_widget
+                        .button(cx, &[id!(symbol_button)])
+                        .set_text(cx, &row.name);
+                    item_widget
+                        .label(cx, &[id!(kind_label)])
+                        .set_text(cx, row.kind);
+                    item_widget
+                        .label(cx, &[id!(path_label)])
+                        .set_text(cx, &row.location);
+                    item_widget.draw_all(cx, scope);
+                }
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl SymbolPickerRef {
+    /// Show the picker in its "searching" state for the given query.
+    pub fn show_searching(&self, cx: &mut Cx, query: &str) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.symbols.clear();
+            inner.rows.clear();
+            inner
+                .view
+                .label(cx, &[id!(status_label)])
+                .set_text(cx, &format!("Searching symbols for \"{}\"...", query));
+            inner
+                .view
+                .view(cx, &[id!(status_wrap)])
+                .set_visible(cx, true);
+            inner.view.set_visible(cx, true);
+            inner.redraw(cx);
+        }
+    }
+
+    pub fn set_symbols(&self, cx: &mut Cx, mut symbols: Vec<Symbol>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            symbols.truncate(SYMBOL_PICKER_MAX_ROWS);
+            let empty = symbols.is_empty();
+            inner.symbols = symbols;
+            inner.rebuild_rows();
+            inner
+                .view
+                .label(cx, &[id!(status_label)])
+                .set_text(cx, "No matching symbols");
+            inner
+                .view
+                .view(cx, &[id!(status_wrap)])
+                .set_visible(cx, empty);
+            inner.view.set_visible(cx, true);
+            inner.redraw(cx);
+        }
+    }
+
+    pub fn hide(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.symbols.clear();
+            inner.rows.clear();
+            inner.view.set_visible(cx, false);
+            inner.redraw(cx);
+        }
+    }
+}
@@ -34,6 +34,12 @@ pub const PROJECT_CONTEXT_NO_PROJECT: &str = "No active project";
 // OpenCode server configuration
 pub const OPENCODE_SERVER_URL: &str = "http://localhost:4096";
 
+// Quick-open: max paths requested from `/find/files` (server caps at 200)
+pub const QUICK_OPEN_FILE_LIMIT: usize = 200;
+
+// Auto-approval audit entries kept in memory
+pub const PERMISSION_AUDIT_LIMIT: usize = 200;
+
 // Timing constants (in seconds)
 pub const HEALTH_CHECK_INTERVAL_SECS: u64 = 5;
 pub const SSE_RETRY_DELAY_SECS: u64 = 2;
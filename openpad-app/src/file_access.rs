@@ -0,0 +1,254 @@
+//! File access for the files panel and editor tabs.
+//!
+//! Projects on this machine are read and written straight from disk. When the
+//! server's working directory doesn't exist locally (e.g. opencode running on a
+//! devbox), listings and contents go through the server's `/file` API instead.
+//! That API has no write endpoint, so server-backed files open read-only.
+
+use openpad_protocol::{FileListRequest, FileReadRequest, OpenCodeClient, PathInfo};
+use std::path::Path;
+
+/// Directories never shown in the files panel.
+pub const IGNORED_DIRS: &[&str] = &[
+    ".git",
+    "node_modules",
+    "target",
+    "__pycache__",
+    ".venv",
+    "venv",
+    "dist",
+    "build",
+];
+
+/// Where project files are read from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FileAccessMode {
+    #[default]
+    Local,
+    Server,
+}
+
+impl FileAccessMode {
+    /// Use the local filesystem when the server's working directory is visible here.
+    pub fn detect(path_info: &PathInfo) -> Self {
+        let root = path_info.root();
+        if root.is_empty() || Path::new(root).is_dir() {
+            Self::Local
+        } else {
+            Self::Server
+        }
+    }
+
+    pub fn is_server(self) -> bool {
+        self == Self::Server
+    }
+}
+
+/// A directory listing entry, independent of where it came from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirEntry {
+    pub name: String,
+    /// Absolute path (on the server's filesystem for server listings)
+    pub path: String,
+    pub is_dir: bool,
+}
+
+pub fn is_probably_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(8192).any(|b| *b == 0)
+}
+
+/// Hidden entries and `IGNORED_DIRS` are left out of listings.
+pub fn is_visible_entry(name: &str, is_dir: bool) -> bool {
+    if name.starts_with('.') && name != ".git" {
+        return false;
+    }
+    !(is_dir && IGNORED_DIRS.contains(&name))
+}
+
+/// Folders first, then case-insensitive by name.
+pub fn sort_entries(entries: &mut [DirEntry]) {
+    entries.sort_by(|a, b| match (a.is_dir, b.is_dir) {
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+    });
+}
+
+/// `path` relative to `root`, as the server's file API expects ("" for the root itself).
+pub fn relative_to_root(root: &str, path: &str) -> String {
+    Path::new(path)
+        .strip_prefix(root)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+pub fn list_local_dir(dir: &Path) -> Vec<DirEntry> {
+    let Ok(rd) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<DirEntry> = rd
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let is_dir = e.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
+            is_visible_entry(&name, is_dir).then(|| DirEntry {
+                path: dir.join(&name).to_string_lossy().to_string(),
+                name,
+                is_dir,
+            })
+        })
+        .collect();
+    sort_entries(&mut entries);
+    entries
+}
+
+/// Why a file couldn't be opened as text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReadTextError {
+    /// The file exists but holds binary content.
+    Binary,
+    Io(String),
+}
+
+impl std::fmt::Display for ReadTextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Binary => f.write_str("binary file"),
+            Self::Io(err) => f.write_str(err),
+        }
+    }
+}
+
+pub fn read_local_text(path: &Path) -> Result<String, ReadTextError> {
+    if !path.is_file() {
+        return Err(ReadTextError::Io("not a file".to_string()));
+    }
+    let bytes = std::fs::read(path).map_err(|e| ReadTextError::Io(e.to_string()))?;
+    if is_probably_binary(&bytes) {
+        return Err(ReadTextError::Binary);
+    }
+    String::from_utf8(bytes).map_err(|_| ReadTextError::Io("file is not valid UTF-8".to_string()))
+}
+
+pub fn write_local_text(path: &Path, text: &str) -> Result<(), String> {
+    std::fs::write(path, text.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// List `dir` through `GET /file`. `client` must be scoped to the project `root`.
+pub async fn list_server_dir(
+    client: &OpenCodeClient,
+    root: &str,
+    dir: &str,
+) -> Result<Vec<DirEntry>, String> {
+    let request = FileListRequest {
+        path: relative_to_root(root, dir),
+    };
+    let nodes = client
+        .list_files(request)
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut entries: Vec<DirEntry> = nodes
+        .into_iter()
+        .filter(|node| !node.ignored && is_visible_entry(&node.name, node.is_dir()))
+        .map(|node| DirEntry {
+            is_dir: node.is_dir(),
+            name: node.name,
+            path: node.absolute,
+        })
+        .collect();
+    sort_entries(&mut entries);
+    Ok(entries)
+}
+
+/// Read a text file through `/file/content`. `client` must be scoped to the project `root`.
+pub async fn read_server_text(
+    client: &OpenCodeClient,
+    root: &str,
+    path: &str,
+) -> Result<String, ReadTextError> {
+    let request = FileReadRequest {
+        path: relative_to_root(root, path),
+    };
+    let response = client
+        .read_file(request)
+        .await
+        .map_err(|e| ReadTextError::Io(e.to_string()))?;
+    if response.type_name != "text" {
+        return Err(ReadTextError::Binary);
+    }
+    Ok(response.content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, is_dir: bool) -> DirEntry {
+        DirEntry {
+            name: name.to_string(),
+            path: format!("/repo/{}", name),
+            is_dir,
+        }
+    }
+
+    #[test]
+    fn test_relative_to_root() {
+        assert_eq!(
+            relative_to_root("/srv/repo", "/srv/repo/src/main.rs"),
+            "src/main.rs"
+        );
+        assert_eq!(relative_to_root("/srv/repo", "/srv/repo"), "");
+        assert_eq!(
+            relative_to_root("/srv/repo", "/elsewhere/a.rs"),
+            "/elsewhere/a.rs"
+        );
+    }
+
+    #[test]
+    fn test_sort_entries_puts_folders_first() {
+        let mut entries = vec![
+            entry("b.rs", false),
+            entry("src", true),
+            entry("A.md", false),
+            entry("docs", true),
+        ];
+        sort_entries(&mut entries);
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["docs", "src", "A.md", "b.rs"]);
+    }
+
+    fn path_info(directory: &str) -> PathInfo {
+        PathInfo {
+            path: String::new(),
+            exists: None,
+            home: None,
+            state: None,
+            config: None,
+            worktree: None,
+            directory: Some(directory.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_detect_uses_server_when_root_is_missing_locally() {
+        let remote = path_info("/definitely/not/on/this/machine/openpad");
+        assert_eq!(FileAccessMode::detect(&remote), FileAccessMode::Server);
+
+        let local = path_info(&std::env::temp_dir().to_string_lossy());
+        assert_eq!(FileAccessMode::detect(&local), FileAccessMode::Local);
+    }
+
+    #[test]
+    fn test_read_local_text_reports_binary_files() {
+        let path = std::env::temp_dir().join(format!("openpad-binary-{}", std::process::id()));
+        std::fs::write(&path, b"ab\0cd").unwrap();
+        let result = read_local_text(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result, Err(ReadTextError::Binary));
+
+        assert!(matches!(
+            read_local_text(Path::new("/definitely/not/here.txt")),
+            Err(ReadTextError::Io(_))
+        ));
+    }
+}
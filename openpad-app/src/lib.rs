@@ -6,6 +6,7 @@ pub mod app;
 pub mod async_runtime;
 pub mod components;
 pub mod constants;
+pub mod file_access;
 pub mod state;
 pub mod ui;
 pub mod utils;
@@ -1,7 +1,8 @@
+use crate::file_access::{DirEntry, ReadTextError};
 use openpad_protocol::{
-    Agent, AssistantError, Event as OcEvent, FileDiff, HealthResponse, Message, MessageWithParts,
-    Part, PermissionReply, PermissionRequest, Project, ProvidersResponse, SecretString, Session,
-    Skill,
+    Agent, AssistantError, Event as OcEvent, File, FileDiff, HealthResponse, Message,
+    MessageWithParts, Part, PathInfo, PermissionReply, PermissionRequest, Project,
+    ProvidersResponse, QuestionRequest, SecretString, Session, Skill, Symbol, TextSearchResult,
 };
 
 #[derive(Clone, Debug, Default)]
@@ -16,6 +17,8 @@ pub enum AppAction {
     SessionsLoaded(Vec<Session>),
     SessionCreated(Session),
     SessionLoaded(Session),
+    /// Branches and subagent sessions spawned from a session
+    SessionChildrenLoaded(Vec<Session>),
     SessionDeleted(String),
     SessionUpdated(Session),
     SessionDiffLoaded {
@@ -58,6 +61,11 @@ pub enum AppAction {
         value: SecretString,
     },
     PendingPermissionsLoaded(Vec<PermissionRequest>),
+    /// Session of a held permission request, `None` when no project has it
+    PermissionSessionResolved {
+        session_id: String,
+        session: Option<Session>,
+    },
     PendingPermissionReceived(PermissionRequest),
     PermissionDismissed {
         session_id: String,
@@ -67,10 +75,54 @@ pub enum AppAction {
         session_id: String,
         error: AssistantError,
     },
+    QuestionAsked(QuestionRequest),
+    /// A question was answered or rejected
+    QuestionResolved {
+        session_id: String,
+        request_id: String,
+    },
     ProvidersLoaded(ProvidersResponse),
     AgentsLoaded(Vec<Agent>),
     SkillsLoaded(Vec<Skill>),
+    TextSearchCompleted {
+        query: String,
+        results: Vec<TextSearchResult>,
+    },
+    FileStatusLoaded {
+        project_id: String,
+        files: Vec<File>,
+    },
+    FilesFound {
+        query: String,
+        files: Vec<String>,
+    },
+    ServerPathLoaded(PathInfo),
+    /// A server-side listing for the files panel; `None` if it failed
+    RemoteDirectoryListed {
+        project_id: String,
+        directory: String,
+        entries: Option<Vec<DirEntry>>,
+    },
+    RemoteFileLoaded {
+        project_id: String,
+        absolute_path: String,
+        line: Option<usize>,
+        result: Result<String, ReadTextError>,
+    },
+    QuickOpenPreviewLoaded {
+        file: String,
+        result: Result<String, ReadTextError>,
+    },
+    SymbolsLoaded {
+        query: String,
+        symbols: Vec<Symbol>,
+    },
     ConfigLoaded(openpad_protocol::Config),
+    /// `PATCH /config` with an agent's permission rules finished
+    AgentPermissionUpdated {
+        agent: String,
+        result: Result<(), String>,
+    },
     AuthSet {
         provider_id: String,
         success: bool,
@@ -82,6 +134,7 @@ pub enum AppAction {
 pub enum SidebarMode {
     #[default]
     Files,
+    Search,
     Settings,
 }
 
@@ -99,12 +152,15 @@ pub enum ProjectsPanelAction {
     RenameSession(String),
     AbortSession(String),
     BranchSession(String),
+    ArchiveSession(String),
+    UnarchiveSession(String),
     /// Open the session context menu at the given position (avoids full list redraw).
     OpenSessionContextMenu {
         session_id: String,
         x: f32,
         y: f32,
         working: bool,
+        archived: bool,
     },
     OpenProjectContextMenu {
         project_id: Option<String>,
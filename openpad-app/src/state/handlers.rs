@@ -1,44 +1,18 @@
-use super::{AppState, ModelDropdownEntry};
+use super::AppState;
+use crate::components::files_panel::FilesPanelWidgetRefExt;
+use crate::components::model_picker::ModelPickerWidgetRefExt;
+use crate::components::quick_open::QuickOpenWidgetRefExt;
+use crate::components::search_panel::SearchPanelWidgetRefExt;
+use crate::components::symbol_picker::SymbolPickerWidgetRefExt;
+use crate::file_access::FileAccessMode;
 use crate::state::actions::AppAction;
 use crate::ui::state_updates;
+use crate::utils::model_catalog;
 use makepad_widgets::*;
-use openpad_protocol::{Event as OcEvent, Provider};
+use openpad_protocol::Event as OcEvent;
 use openpad_widgets::settings_dialog::SettingsDialogWidgetRefExt;
 use openpad_widgets::UpDropDownWidgetRefExt;
 
-#[allow(dead_code)]
-fn build_model_entries(providers: &[Provider]) -> Vec<ModelDropdownEntry> {
-    /// Helper to get display label, preferring name over id
-    fn display_label(name: Option<&str>, id: &str) -> String {
-        name.unwrap_or(id).to_string()
-    }
-
-    let mut entries = vec![ModelDropdownEntry::default_option()];
-    for provider in providers {
-        let models = &provider.models;
-        if models.is_empty() {
-            continue;
-        }
-        let provider_label = display_label(Some(provider.name.as_str()), &provider.id);
-        let mut model_items: Vec<_> = models.iter().collect();
-        model_items.sort_by(|a, b| {
-            let a_label = display_label(Some(a.1.name.as_str()), &a.1.id);
-            let b_label = display_label(Some(b.1.name.as_str()), &b.1.id);
-            a_label.cmp(&b_label)
-        });
-        entries.push(ModelDropdownEntry::provider_header(provider_label.clone()));
-        for (_key, model) in model_items {
-            let model_label = format!("  {}", display_label(Some(model.name.as_str()), &model.id));
-            entries.push(ModelDropdownEntry::model_option(
-                provider.id.clone(),
-                model.id.clone(),
-                model_label,
-            ));
-        }
-    }
-    entries
-}
-
 /// Handles AppAction events
 pub fn handle_app_action(state: &mut AppState, ui: &WidgetRef, cx: &mut Cx, action: &AppAction) {
     let effects = crate::state::reducer::reduce_app_state(state, action);
@@ -74,7 +48,7 @@ pub fn handle_app_action(state: &mut AppState, ui: &WidgetRef, cx: &mut Cx, acti
         AppAction::SessionsLoaded(_) => {
             state.refresh_session_ui(ui, cx);
         }
-        AppAction::SessionLoaded(_) => {
+        AppAction::SessionLoaded(_) | AppAction::SessionChildrenLoaded(_) => {
             state.refresh_session_ui(ui, cx);
         }
         AppAction::SessionCreated(_) => {
@@ -97,7 +71,12 @@ pub fn handle_app_action(state: &mut AppState, ui: &WidgetRef, cx: &mut Cx, acti
         AppAction::MessagesLoaded {
             session_id: _,
             messages: _,
-        } => {}
+        } => {
+            // The sessions filter also searches cached message text.
+            if state.session_filter.is_active() {
+                state.update_sessions_panel(ui, cx);
+            }
+        }
         AppAction::MessageReceived(message) => {
             if matches!(message, openpad_protocol::Message::Assistant(_)) {
                 state.update_sessions_panel(ui, cx);
@@ -115,20 +94,19 @@ pub fn handle_app_action(state: &mut AppState, ui: &WidgetRef, cx: &mut Cx, acti
             state_updates::update_work_indicator(ui, cx, false);
             cx.redraw_all();
         }
-        AppAction::PendingPermissionsLoaded(_) => {
-            show_next_pending_permission(state, ui, cx);
-        }
-        AppAction::PendingPermissionReceived(_) => {
-            show_next_pending_permission(state, ui, cx);
-        }
-        AppAction::PermissionResponded { .. } => {
-            show_next_pending_permission(state, ui, cx);
-        }
-        AppAction::PermissionDismissed { .. } => {
+        AppAction::PendingPermissionsLoaded(_)
+        | AppAction::PendingPermissionReceived(_)
+        | AppAction::PermissionResponded { .. }
+        | AppAction::PermissionDismissed { .. } => {
             show_next_pending_permission(state, ui, cx);
+            state.update_sessions_panel(ui, cx);
         }
         AppAction::SessionErrorReceived { .. } => {
             state_updates::update_work_indicator(ui, cx, false);
+            state.update_sessions_panel(ui, cx);
+        }
+        AppAction::QuestionAsked(_) | AppAction::QuestionResolved { .. } => {
+            state.update_sessions_panel(ui, cx);
         }
         AppAction::ProvidersLoaded(providers_response) => {
             log!(
@@ -136,36 +114,9 @@ pub fn handle_app_action(state: &mut AppState, ui: &WidgetRef, cx: &mut Cx, acti
                 providers_response.providers.len()
             );
 
-            log!("Providers count: {}", state.providers.len());
-            log!("Provider labels count: {}", state.provider_labels.len());
-            log!("Provider labels: {:?}", state.provider_labels);
-
-            // Set provider dropdown
-            log!(
-                "Setting provider dropdown with {} labels",
-                state.provider_labels.len()
-            );
-            let provider_dd =
-                ui.up_drop_down(cx, &[id!(input_bar_toolbar), id!(provider_dropdown)]);
-            provider_dd.set_labels(cx, state.provider_labels.clone());
-            provider_dd.set_selected_item(cx, 0);
-            log!(
-                "Provider dropdown labels set to: {:?}",
-                state.provider_labels
-            );
-
-            // Set model dropdown
-            log!(
-                "Setting model dropdown with {} labels",
-                state.model_labels.len()
-            );
-            let model_dd = ui.up_drop_down(cx, &[id!(input_bar_toolbar), id!(model_dropdown)]);
-            model_dd.set_labels(cx, state.model_labels.clone());
-            model_dd.set_selected_item(cx, 0);
-
-            // Force redraw of the input bar area to ensure dropdowns are updated
-            ui.redraw(cx);
-            log!("Provider labels set: {:?}", state.provider_labels);
+            ui.model_picker(cx, &[id!(model_picker)])
+                .set_models(cx, model_catalog::model_choices(&state.providers));
+            state.update_model_ui(ui, cx);
 
             ui.settings_dialog(cx, &[id!(side_panel), id!(settings_panel)])
                 .set_providers(cx, state.providers.clone());
@@ -179,7 +130,7 @@ pub fn handle_app_action(state: &mut AppState, ui: &WidgetRef, cx: &mut Cx, acti
             ui.up_drop_down(cx, &[id!(input_bar_toolbar), id!(agent_dropdown)])
                 .set_labels(cx, labels);
             ui.up_drop_down(cx, &[id!(input_bar_toolbar), id!(agent_dropdown)])
-                .set_selected_item(cx, 0);
+                .set_selected_item(cx, state.selected_agent_idx.map_or(0, |idx| idx + 1));
             cx.redraw_all();
         }
         AppAction::SkillsLoaded(skills) => {
@@ -192,6 +143,46 @@ pub fn handle_app_action(state: &mut AppState, ui: &WidgetRef, cx: &mut Cx, acti
                 .set_selected_item(cx, 0);
             cx.redraw_all();
         }
+        AppAction::TextSearchCompleted { .. } => {
+            if !state.text_search.loading {
+                ui.search_panel(cx, &[id!(side_panel), id!(search_panel)])
+                    .set_results(cx, &state.text_search.query, &state.text_search.groups);
+            }
+        }
+        AppAction::FileStatusLoaded { project_id, files } => {
+            ui.files_panel(cx, &[id!(side_panel), id!(files_panel)])
+                .set_file_status(cx, project_id, files);
+        }
+        AppAction::ServerPathLoaded(info) => {
+            state.file_access = FileAccessMode::detect(info);
+            ui.files_panel(cx, &[id!(side_panel), id!(files_panel)])
+                .set_access_mode(cx, state.file_access);
+        }
+        AppAction::RemoteDirectoryListed {
+            project_id,
+            directory,
+            entries,
+        } => {
+            ui.files_panel(cx, &[id!(side_panel), id!(files_panel)])
+                .set_dir_listing(cx, project_id, directory, entries.clone());
+        }
+        AppAction::FilesFound { query, files } => {
+            if state.quick_open.query == *query {
+                ui.quick_open(cx, &[id!(quick_open)])
+                    .set_results(cx, query, files);
+            }
+        }
+        AppAction::QuickOpenPreviewLoaded { file, result } => {
+            ui.quick_open(cx, &[id!(quick_open)])
+                .set_preview(cx, file, result.clone());
+        }
+        AppAction::SymbolsLoaded { query, symbols } => {
+            // Ignore results for a query the user has already typed past.
+            if state.symbol_query.as_deref() == Some(query.as_str()) {
+                ui.symbol_picker(cx, &[id!(symbol_picker)])
+                    .set_symbols(cx, symbols.clone());
+            }
+        }
         AppAction::ConfigLoaded(config) => {
             ui.settings_dialog(cx, &[id!(side_panel), id!(settings_panel)])
                 .set_config(cx, config);
@@ -265,6 +256,41 @@ pub fn handle_opencode_event(state: &mut AppState, ui: &WidgetRef, cx: &mut Cx,
                 },
             );
         }
+        OcEvent::FileEdited { file } => {
+            // The agent may have created the file; an already listed file is left as is.
+            ui.files_panel(cx, &[id!(side_panel), id!(files_panel)])
+                .apply_file_change(cx, file, Some(WatcherChange::Added));
+        }
+        OcEvent::FileWatcherUpdated { file, event } => {
+            let change = WatcherChange::from_event(event);
+            if change.is_none() {
+                log!("Unknown file watcher event {:?} for {}", event, file);
+            }
+            ui.files_panel(cx, &[id!(side_panel), id!(files_panel)])
+                .apply_file_change(cx, file, change);
+        }
+        OcEvent::QuestionAsked(request) => {
+            handle_app_action(state, ui, cx, &AppAction::QuestionAsked(request.clone()));
+        }
+        OcEvent::QuestionReplied {
+            session_id,
+            request_id,
+            ..
+        }
+        | OcEvent::QuestionRejected {
+            session_id,
+            request_id,
+        } => {
+            handle_app_action(
+                state,
+                ui,
+                cx,
+                &AppAction::QuestionResolved {
+                    session_id: session_id.clone(),
+                    request_id: request_id.clone(),
+                },
+            );
+        }
         OcEvent::SessionError { session_id, error } => {
             handle_app_action(
                 state,
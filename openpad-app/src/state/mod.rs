@@ -17,53 +17,22 @@ use crate::async_runtime::tasks;
 use crate::components::files_panel::FilesPanelWidgetRefExt;
 use crate::components::sessions_panel::SessionsPanelWidgetRefExt;
 use crate::constants::*;
+use crate::file_access::FileAccessMode;
+use crate::utils::line_diff::ChangeHunk;
+use crate::utils::merge::PendingMerge;
+use crate::utils::permission_policy::{PermissionPolicy, PolicyAction};
 use crate::ui::state_updates;
 use makepad_widgets::*;
 use openpad_protocol::{
-    Agent, MessageWithParts, ModelSpec, PermissionRequest, PermissionRuleset, Project, Provider,
-    Session, Skill,
+    Agent, MessageWithParts, Model, ModelSpec, PermissionAction, PermissionRequest, PermissionRule,
+    PermissionRuleset, Project, Provider, QuestionRequest, Session, Skill, Symbol,
+    TextSearchResult,
 };
-use std::collections::HashMap;
+use openpad_widgets::UpDropDownWidgetRefExt;
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 // ── Types ────────────────────────────────────────────────────────────────────
 
-#[derive(Clone)]
-pub struct ModelDropdownEntry {
-    pub label: String,
-    pub provider_id: Option<String>,
-    pub model_id: Option<String>,
-    pub selectable: bool,
-}
-
-impl ModelDropdownEntry {
-    pub fn default_option() -> Self {
-        Self {
-            label: "Default".to_string(),
-            provider_id: None,
-            model_id: None,
-            selectable: true,
-        }
-    }
-
-    pub fn provider_header(label: String) -> Self {
-        Self {
-            label,
-            provider_id: None,
-            model_id: None,
-            selectable: false,
-        }
-    }
-
-    pub fn model_option(provider_id: String, model_id: String, label: String) -> Self {
-        Self {
-            label,
-            provider_id: Some(provider_id),
-            model_id: Some(model_id),
-            selectable: true,
-        }
-    }
-}
-
 #[derive(Clone, Debug, Default)]
 pub struct OpenFileState {
     pub project_id: String,
@@ -71,6 +40,17 @@ pub struct OpenFileState {
     pub display_name: String,
     pub text_cache: String,
     pub last_saved_revision: u64,
+    /// Served through the server file API, which can't write files back
+    pub read_only: bool,
+    /// Disk contents that changed under unsaved edits, awaiting reload / keep mine / merge
+    pub external_text: Option<String>,
+    /// Merge of `external_text` with conflicts still being settled; the buffer
+    /// is only replaced once every conflict has a side
+    pub pending_merge: Option<PendingMerge>,
+    /// Lines changed by the agent in the current session, against the diff's `before`
+    pub change_hunks: Vec<ChangeHunk>,
+    pub selected_hunk: usize,
+    pub show_original: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -78,6 +58,8 @@ pub enum CenterTabKind {
     Home,
     Chat { session_id: String },
     File { open_file: OpenFileState },
+    /// One file of a session's diff, by its path as reported in the diff
+    Diff { session_id: String, file: String },
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
@@ -93,10 +75,16 @@ pub enum PendingCenterIntent {
     OpenFile {
         project_id: String,
         absolute_path: String,
+        /// Zero-based line to reveal once the file is open
+        line: Option<usize>,
     },
     OpenSession {
         session_id: String,
     },
+    OpenDiff {
+        session_id: String,
+        file: String,
+    },
     SwitchTab {
         tab_id: LiveId,
     },
@@ -105,6 +93,254 @@ pub enum PendingCenterIntent {
     },
 }
 
+/// Text search matches for a single file, in the order the server returned them.
+#[derive(Clone, Debug)]
+pub struct SearchFileGroup {
+    /// Path as reported by the server (relative to the searched directory)
+    pub path: String,
+    pub absolute_path: String,
+    pub matches: Vec<TextSearchResult>,
+}
+
+/// State of the project-wide text search sidebar
+#[derive(Clone, Debug, Default)]
+pub struct TextSearchState {
+    pub query: String,
+    pub project_id: String,
+    pub directory: String,
+    pub loading: bool,
+    pub groups: Vec<SearchFileGroup>,
+}
+
+impl TextSearchState {
+    pub fn match_count(&self) -> usize {
+        self.groups.iter().map(|group| group.matches.len()).sum()
+    }
+}
+
+/// Scope and latest query of the quick-open file finder
+#[derive(Clone, Debug, Default)]
+pub struct QuickOpenState {
+    pub query: String,
+    pub project_id: String,
+    pub directory: String,
+}
+
+/// Order of sessions in the sessions panel, newest first
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SessionSort {
+    #[default]
+    Updated,
+    Created,
+    /// Total assistant cost, known only for sessions whose messages are loaded
+    Cost,
+}
+
+impl SessionSort {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Updated => Self::Created,
+            Self::Created => Self::Cost,
+            Self::Cost => Self::Updated,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Updated => "Last updated",
+            Self::Created => "Created",
+            Self::Cost => "Cost",
+        }
+    }
+}
+
+/// Day bucket of a session's last update, in UTC days
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateBucket {
+    Today,
+    Yesterday,
+    ThisWeek,
+    Older,
+}
+
+impl DateBucket {
+    pub const ALL: [DateBucket; 4] = [
+        DateBucket::Today,
+        DateBucket::Yesterday,
+        DateBucket::ThisWeek,
+        DateBucket::Older,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Today => "Today",
+            Self::Yesterday => "Yesterday",
+            Self::ThisWeek => "This week",
+            Self::Older => "Older",
+        }
+    }
+}
+
+/// Search box, quick filters and ordering of the sessions panel
+#[derive(Clone, Debug, Default)]
+pub struct SessionFilter {
+    /// Matched case-insensitively against title, slug and loaded message text
+    pub query: String,
+    pub working: bool,
+    pub has_changes: bool,
+    pub shared: bool,
+    pub pending_permission: bool,
+    pub sort: SessionSort,
+    pub group_by_date: bool,
+}
+
+impl SessionFilter {
+    /// Whether any filter can hide sessions; sorting and grouping never do.
+    pub fn is_active(&self) -> bool {
+        !self.query.trim().is_empty()
+            || self.working
+            || self.has_changes
+            || self.shared
+            || self.pending_permission
+    }
+}
+
+/// Why a session wants a look, most urgent first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AttentionKind {
+    Permission,
+    Question,
+    Error,
+    /// Stopped working while another session was in view
+    Finished,
+    /// Assistant output arrived while another session was in view
+    Unread,
+}
+
+impl AttentionKind {
+    pub fn badge(self) -> &'static str {
+        match self {
+            Self::Permission => "⚠",
+            Self::Question => "?",
+            Self::Error => "✕",
+            Self::Finished => "✓",
+            Self::Unread => "•",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Permission => "Waiting for permission",
+            Self::Question => "Asked a question",
+            Self::Error => "Failed",
+            Self::Finished => "Finished",
+            Self::Unread => "New output",
+        }
+    }
+}
+
+/// A permission request decided by the local policy, kept for the audit view
+#[derive(Clone, Debug)]
+pub struct PermissionAuditEntry {
+    pub time_ms: i64,
+    pub session_id: String,
+    pub permission: String,
+    pub patterns: Vec<String>,
+    pub action: PolicyAction,
+    /// `PolicyRule::describe` of the rule that fired
+    pub rule: String,
+    /// Recorded in dry-run mode; the request was left for the user
+    pub dry_run: bool,
+}
+
+/// Composer model, agent and skill picked for one session, kept by id so it
+/// survives provider, agent and skill reloads
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ComposerSelection {
+    /// `(provider_id, model_id)`
+    pub model: Option<(String, String)>,
+    /// One of the model's variants; `None` is the provider default
+    pub variant: Option<String>,
+    pub agent: Option<String>,
+    pub skill: Option<String>,
+}
+
+/// Where the permission rules editor applies its rules
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum RulesTarget {
+    /// `SessionCreateRequest.permission` of sessions created from now on
+    #[default]
+    NewSessions,
+    /// The `permission` config of the named agent
+    Agent(String),
+}
+
+impl RulesTarget {
+    pub fn label(&self) -> String {
+        match self {
+            Self::NewSessions => "New sessions".to_string(),
+            Self::Agent(name) => format!("Agent: {}", name),
+        }
+    }
+}
+
+/// Draft of the permission rules editor, in evaluation order
+#[derive(Clone, Debug, Default)]
+pub struct RulesEditorState {
+    pub target: RulesTarget,
+    pub rules: PermissionRuleset,
+    /// Index of the first rule on the visible page
+    pub offset: usize,
+    pub status: Option<String>,
+}
+
+impl RulesEditorState {
+    /// Appends a rule, which then takes precedence over every earlier match.
+    pub fn add_rule(&mut self, permission: &str, pattern: &str, action: PermissionAction) {
+        let pattern = if pattern.trim().is_empty() {
+            "*"
+        } else {
+            pattern.trim()
+        };
+        self.rules.push(PermissionRule {
+            permission: permission.trim().to_string(),
+            pattern: pattern.to_string(),
+            action,
+        });
+    }
+
+    /// Swaps the rule at `index` with its neighbour above (`up`) or below.
+    pub fn move_rule(&mut self, index: usize, up: bool) {
+        let other = if up {
+            index.checked_sub(1)
+        } else {
+            Some(index + 1)
+        };
+        if let Some(other) = other.filter(|other| *other < self.rules.len()) {
+            if index < self.rules.len() {
+                self.rules.swap(index, other);
+            }
+        }
+    }
+
+    pub fn remove_rule(&mut self, index: usize) {
+        if index < self.rules.len() {
+            self.rules.remove(index);
+        }
+    }
+
+    /// Steps the rule's action through allow, ask and deny.
+    pub fn cycle_action(&mut self, index: usize) {
+        if let Some(rule) = self.rules.get_mut(index) {
+            rule.action = match rule.action {
+                PermissionAction::Allow => PermissionAction::Ask,
+                PermissionAction::Ask => PermissionAction::Deny,
+                PermissionAction::Deny => PermissionAction::Allow,
+            };
+        }
+    }
+}
+
 /// Information about an attached file ready to be sent
 #[derive(Clone, Debug)]
 pub struct AttachedFile {
@@ -114,6 +350,22 @@ pub struct AttachedFile {
     /// For text/plain attachments, store the raw text to send as a PartInput::Text
     /// instead of a file attachment. This avoids server-side file processing artifacts.
     pub raw_text: Option<String>,
+    /// Workspace symbol picked via the composer's `#` trigger; sent as a symbol file part.
+    pub symbol: Option<Symbol>,
+    /// Character offset where the picker inserted the symbol's `#name` token.
+    pub symbol_picked_at: usize,
+}
+
+/// `Session.revert.diff` parsed for the chat's revert banner. Cached because
+/// chat tabs are re-rendered on every app action.
+#[derive(Clone, Debug, Default)]
+pub struct RevertPreview {
+    /// Message id, part id and diff length of the revert this was built from
+    pub revert_key: (String, Option<String>, usize),
+    pub files: Vec<openpad_widgets::diff_model::PatchFile>,
+    /// The patch in the form `DiffView` renders
+    pub diff_text: String,
+    pub expanded: bool,
 }
 
 // ── AppState ─────────────────────────────────────────────────────────────────
@@ -133,25 +385,54 @@ pub struct AppState {
     pub is_working: bool,
     pub working_by_session: HashMap<String, bool>,
     pub pending_permissions: Vec<PermissionRequest>,
+    pub pending_questions: Vec<QuestionRequest>,
+    /// Unread, finished and error marks of sessions not in view; permissions
+    /// and questions are read from the pending lists instead
+    pub attention_by_session: HashMap<String, BTreeSet<AttentionKind>>,
+    pub permission_policy: PermissionPolicy,
+    /// Newest last, capped at `PERMISSION_AUDIT_LIMIT`
+    pub permission_audit: Vec<PermissionAuditEntry>,
+    /// Requests for sessions not loaded yet, held back until the session (and
+    /// so the directory project-scoped policy rules need) is fetched
+    pub permissions_awaiting_session: Vec<PermissionRequest>,
+    pub rules_editor: RulesEditorState,
+    /// Rules applied from the editor to new sessions, replacing the selected
+    /// agent's ruleset
+    pub session_permission_override: Option<PermissionRuleset>,
     pub providers: Vec<Provider>,
     pub agents: Vec<Agent>,
     pub skills: Vec<Skill>,
-    /// Selected provider index (0 = Default/All providers)
-    pub selected_provider_idx: usize,
-    /// Available provider labels for the provider dropdown
-    pub provider_labels: Vec<String>,
-    /// Currently filtered model labels based on selected provider
-    pub model_labels: Vec<String>,
-    /// Maps model dropdown index to (provider_id, model_id)
+    /// Every provider's models as (provider_id, model_id), by model name
     pub model_entries: Vec<(String, String)>,
     pub selected_model_idx: usize,
+    /// Variant sent with prompts, always one of `selected_model_variants()`
+    pub selected_variant: Option<String>,
     pub selected_agent_idx: Option<usize>,
     pub selected_skill_idx: Option<usize>,
+    /// Composer selection last used in each session
+    pub composer_selection_by_session: HashMap<String, ComposerSelection>,
+    /// Session whose selection the composer dropdowns show; cleared when a
+    /// reload resets the dropdowns
+    pub composer_selection_session: Option<String>,
     pub attached_files: Vec<AttachedFile>,
+    /// Query of the `#symbol` token currently being completed in the composer
+    pub symbol_query: Option<String>,
+    pub text_search: TextSearchState,
+    pub quick_open: QuickOpenState,
+    pub file_access: FileAccessMode,
+    /// Projects with a `/file/status` request in flight
+    pub file_status_in_flight: HashSet<String>,
+    /// Projects whose files changed while a status request was in flight
+    pub file_status_stale: HashSet<String>,
     pub config: Option<openpad_protocol::Config>,
     pub center_tabs_by_id: HashMap<LiveId, CenterTabKind>,
     pub tab_by_session: HashMap<String, LiveId>,
     pub tab_by_file: HashMap<String, LiveId>,
+    /// Diff tabs keyed by `(session_id, file)`
+    pub tab_by_diff: HashMap<(String, String), LiveId>,
+    /// Revert banner previews keyed by session id
+    pub revert_previews: HashMap<String, RevertPreview>,
+    pub session_filter: SessionFilter,
     pub active_center_tab: Option<LiveId>,
     pub pending_center_intent: Option<PendingCenterIntent>,
 }
@@ -186,42 +467,49 @@ impl AppState {
             })
     }
 
-    /// Get models for the currently selected provider
-    pub fn get_models_for_selected_provider(&self) -> Vec<(String, String, String)> {
-        let mut models = Vec::new();
-
-        if self.selected_provider_idx == 0 {
-            // "Default" selected - show all models from all providers
-            for provider in &self.providers {
-                for model in provider.models.values() {
-                    let model_label = model.name.clone();
-                    models.push((provider.id.clone(), model.id.clone(), model_label));
-                }
-            }
-        } else if let Some(provider) = self.providers.get(self.selected_provider_idx - 1) {
-            // Specific provider selected
-            for model in provider.models.values() {
-                let model_label = model.name.clone();
-                models.push((provider.id.clone(), model.id.clone(), model_label));
-            }
-        }
-
-        // Sort by label
+    /// Rebuild `model_entries` from the loaded providers, resetting the
+    /// selection to the first model
+    pub fn update_model_entries(&mut self) {
+        let mut models: Vec<(String, String, String)> = self
+            .providers
+            .iter()
+            .flat_map(|provider| {
+                provider
+                    .models
+                    .values()
+                    .map(|model| (provider.id.clone(), model.id.clone(), model.name.clone()))
+            })
+            .collect();
         models.sort_by(|a, b| a.2.cmp(&b.2));
-        models
+        self.model_entries = models
+            .into_iter()
+            .map(|(provider_id, model_id, _)| (provider_id, model_id))
+            .collect();
+        self.selected_model_idx = 0;
+        self.selected_variant = None;
     }
 
-    /// Update model list based on selected provider
-    pub fn update_model_list_for_provider(&mut self) {
-        let models = self.get_models_for_selected_provider();
-        self.model_labels = models.iter().map(|(_, _, label)| label.clone()).collect();
-        self.model_entries = models
+    pub fn selected_model(&self) -> Option<&Model> {
+        let (provider_id, model_id) = self.model_entries.get(self.selected_model_idx)?;
+        self.providers
             .iter()
-            .map(|(pid, mid, _)| (pid.clone(), mid.clone()))
-            .collect();
+            .find(|provider| provider.id == *provider_id)
+            .and_then(|provider| provider.models.values().find(|m| m.id == *model_id))
+    }
 
-        // Reset model selection to first item (or 0 if empty)
-        self.selected_model_idx = 0;
+    /// Name of the selected model for the toolbar's picker button
+    pub fn selected_model_label(&self) -> String {
+        self.selected_model()
+            .map(|model| model.name.clone())
+            .unwrap_or_else(|| "Model".to_string())
+    }
+
+    /// Variants offered for the selected model; empty unless it reasons
+    pub fn selected_model_variants(&self) -> Vec<String> {
+        self.selected_model()
+            .filter(|model| model.capabilities.reasoning)
+            .map(|model| model.variant_names())
+            .unwrap_or_default()
     }
 
     pub fn selected_agent_name(&self) -> Option<String> {
@@ -236,6 +524,23 @@ impl AppState {
             .map(|agent| agent.permission.clone())
     }
 
+    /// Ruleset sent with `SessionCreateRequest.permission`
+    pub fn new_session_permission(&self) -> Option<PermissionRuleset> {
+        self.session_permission_override
+            .clone()
+            .or_else(|| self.selected_agent_permission())
+    }
+
+    /// The composer's current model, agent and skill
+    pub fn composer_selection(&self) -> ComposerSelection {
+        ComposerSelection {
+            model: self.model_entries.get(self.selected_model_idx).cloned(),
+            variant: self.selected_variant.clone(),
+            agent: self.selected_agent_name(),
+            skill: self.selected_skill().map(|skill| skill.name.clone()),
+        }
+    }
+
     pub fn selected_skill(&self) -> Option<&Skill> {
         self.selected_skill_idx.and_then(|idx| self.skills.get(idx))
     }
@@ -289,6 +594,20 @@ impl AppState {
         let (title, is_active) = self.get_session_title();
         state_updates::update_session_title_ui(ui, cx, &title, is_active);
 
+        let ancestors = self
+            .current_session_id
+            .as_deref()
+            .map(|sid| reducer::session_ancestors(&self.sessions, sid))
+            .unwrap_or_default();
+        let root_title = (ancestors.len() > 1).then(|| tasks::get_session_title(ancestors[0]));
+        let parent_title = ancestors.last().map(|s| tasks::get_session_title(s));
+        state_updates::update_session_breadcrumb(
+            ui,
+            cx,
+            root_title.as_deref(),
+            parent_title.as_deref(),
+        );
+
         // Update revert indicator
         let is_reverted = self.is_current_session_reverted();
         state_updates::update_revert_indicator(ui, cx, is_reverted);
@@ -317,6 +636,27 @@ impl AppState {
         state_updates::update_summary_ui(ui, cx, summary);
     }
 
+    /// Shows the selected model on the toolbar's picker button, and the
+    /// variant selector when the model reasons and offers variants.
+    pub fn update_model_ui(&self, ui: &WidgetRef, cx: &mut Cx) {
+        ui.button(cx, &[id!(input_bar_toolbar), id!(model_picker_button)])
+            .set_text(cx, &format!("{} ▾", self.selected_model_label()));
+
+        let variants = self.selected_model_variants();
+        ui.view(cx, &[id!(input_bar_toolbar), id!(variant_selector)])
+            .set_visible(cx, !variants.is_empty());
+        let selected = self
+            .selected_variant
+            .as_ref()
+            .and_then(|variant| variants.iter().position(|v| v == variant))
+            .map_or(0, |idx| idx + 1);
+        let mut labels = vec!["Default".to_string()];
+        labels.extend(variants);
+        let dropdown = ui.up_drop_down(cx, &[id!(input_bar_toolbar), id!(variant_dropdown)]);
+        dropdown.set_labels(cx, labels);
+        dropdown.set_selected_item(cx, selected);
+    }
+
     pub fn current_share_url(&self) -> Option<String> {
         self.current_session_id.as_ref().and_then(|sid| {
             self.find_session(sid)
@@ -350,13 +690,24 @@ impl AppState {
 
     /// Updates right sessions panel with current data
     pub fn update_sessions_panel(&self, ui: &WidgetRef, cx: &mut Cx) {
+        let sessions = reducer::visible_sessions(self);
+        let attention = reducer::attention_inbox(self).into_iter().collect();
+        let summary = if self.session_filter.is_active() {
+            format!("{} of {} sessions", sessions.len(), self.sessions.len())
+        } else {
+            String::new()
+        };
+        ui.label(cx, &[id!(right_side_panel), id!(session_filter_summary)])
+            .set_text(cx, &summary);
         ui.sessions_panel(cx, &[id!(right_side_panel), id!(sessions_panel)])
             .set_data(
                 cx,
                 self.projects.clone(),
-                self.sessions.clone(),
+                sessions,
                 self.selected_session_id.clone(),
                 self.working_by_session.clone(),
+                attention,
+                self.session_filter.group_by_date,
             );
     }
 }
@@ -1,11 +1,19 @@
+use crate::constants::PERMISSION_AUDIT_LIMIT;
 use crate::state::actions::AppAction;
 use crate::state::effects::StateEffect;
-use crate::state::{AppState, PendingCenterIntent};
+use crate::state::{
+    AppState, AttentionKind, ComposerSelection, DateBucket, PendingCenterIntent,
+    PermissionAuditEntry, RulesEditorState, RulesTarget, SearchFileGroup, SessionFilter,
+    SessionSort,
+};
+use crate::utils::permission_policy::PolicyAction;
 use makepad_widgets::LiveId;
 use openpad_protocol::{
     AssistantError, AssistantMessage, Message, MessageTime, MessageWithParts, Part,
+    PermissionConfig, PermissionRequest, PermissionRuleset, Session, TextSearchResult,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
 
 pub fn reduce_app_state(state: &mut AppState, action: &AppAction) -> Vec<StateEffect> {
     let mut effects = Vec::new();
@@ -50,6 +58,15 @@ pub fn reduce_app_state(state: &mut AppState, action: &AppAction) -> Vec<StateEf
                 state.sessions.push(session.clone());
             }
         }
+        AppAction::SessionChildrenLoaded(children) => {
+            for child in children {
+                if let Some(existing) = state.find_session_mut(&child.id) {
+                    *existing = child.clone();
+                } else {
+                    state.sessions.push(child.clone());
+                }
+            }
+        }
         AppAction::SessionCreated(session) => {
             state.current_session_id = Some(session.id.clone());
             state
@@ -76,7 +93,15 @@ pub fn reduce_app_state(state: &mut AppState, action: &AppAction) -> Vec<StateEf
 
             state.messages_by_session.remove(session_id);
             state.working_by_session.remove(session_id);
+            state.attention_by_session.remove(session_id);
+            state
+                .pending_questions
+                .retain(|question| question.session_id != *session_id);
             state.tab_by_session.remove(session_id);
+            state.revert_previews.remove(session_id);
+            state
+                .tab_by_diff
+                .retain(|(diff_session_id, _), _| diff_session_id != session_id);
             state.sessions.retain(|s| s.id != *session_id);
         }
         AppAction::SessionUpdated(session) => {
@@ -139,8 +164,26 @@ pub fn reduce_app_state(state: &mut AppState, action: &AppAction) -> Vec<StateEf
             remove_pending_permission(state, request_id);
         }
         AppAction::SessionErrorReceived { session_id, error } => {
+            flag_attention(state, session_id, AttentionKind::Error);
             reduce_session_error(state, session_id, error);
         }
+        AppAction::QuestionAsked(request) => {
+            if !state
+                .pending_questions
+                .iter()
+                .any(|pending| pending.id == request.id)
+            {
+                state.pending_questions.push(request.clone());
+            }
+        }
+        AppAction::QuestionResolved {
+            session_id: _,
+            request_id,
+        } => {
+            state
+                .pending_questions
+                .retain(|question| question.id != *request_id);
+        }
         AppAction::SendMessageFailed(err) => {
             state.error_message = Some(err.clone());
             state.is_working = false;
@@ -148,40 +191,79 @@ pub fn reduce_app_state(state: &mut AppState, action: &AppAction) -> Vec<StateEf
         AppAction::ProvidersLoaded(providers_response) => {
             state.providers = providers_response.providers.clone();
 
-            let mut provider_labels = vec!["Default".to_string()];
-            provider_labels.extend(
-                state
-                    .providers
-                    .iter()
-                    .map(|p| p.name.clone()),
-            );
-            state.provider_labels = provider_labels;
-            state.selected_provider_idx = 0;
-            state.update_model_list_for_provider();
+            state.update_model_entries();
+            state.composer_selection_session = None;
         }
         AppAction::AgentsLoaded(agents) => {
+            // Keep the selection across reloads, e.g. after saving an agent's rules.
+            let selected = state.selected_agent_name();
             state.agents = agents.clone();
-            state.selected_agent_idx = None;
+            state.selected_agent_idx =
+                selected.and_then(|name| agents.iter().position(|agent| agent.name == name));
         }
         AppAction::SkillsLoaded(skills) => {
             state.skills = skills.clone();
             state.selected_skill_idx = None;
+            state.composer_selection_session = None;
         }
         AppAction::ConfigLoaded(config) => {
             state.config = Some(config.clone());
         }
+        AppAction::AgentPermissionUpdated { agent, result } => {
+            if state.rules_editor.target == RulesTarget::Agent(agent.clone()) {
+                state.rules_editor.status = Some(match result {
+                    Ok(()) => format!("Saved to the {} agent config", agent),
+                    Err(error) => format!("Could not save: {}", error),
+                });
+            }
+        }
+        AppAction::TextSearchCompleted { query, results } => {
+            // Drop results for a search that has since been replaced.
+            if state.text_search.query == *query {
+                state.text_search.groups =
+                    group_text_search_results(&state.text_search.directory, results);
+                state.text_search.loading = false;
+            }
+        }
         _ => {}
     }
 
     effects
 }
 
+/// Marks a session for the attention inbox unless it is the one in view.
+fn flag_attention(state: &mut AppState, session_id: &str, kind: AttentionKind) {
+    if state.current_session_id.as_deref() == Some(session_id) {
+        return;
+    }
+    state
+        .attention_by_session
+        .entry(session_id.to_string())
+        .or_default()
+        .insert(kind);
+}
+
 fn reduce_message_received(state: &mut AppState, message: &Message) {
     let session_id = message.session_id().to_string();
 
     if let Message::Assistant(msg) = message {
         let working = msg.time.completed.is_none() && msg.error.is_none();
-        state.working_by_session.insert(session_id.clone(), working);
+        let was_working = state
+            .working_by_session
+            .insert(session_id.clone(), working)
+            .unwrap_or(false);
+        let is_new = !state
+            .messages_by_session
+            .get(&session_id)
+            .is_some_and(|messages| messages.iter().any(|m| m.info.id() == msg.id));
+        if msg.error.is_some() {
+            flag_attention(state, &session_id, AttentionKind::Error);
+        } else if was_working && !working {
+            flag_attention(state, &session_id, AttentionKind::Finished);
+        }
+        if is_new {
+            flag_attention(state, &session_id, AttentionKind::Unread);
+        }
     }
 
     if state.current_session_id.is_none() {
@@ -250,6 +332,7 @@ fn reduce_part_received(state: &mut AppState, part: &Part) {
     if should_update_work {
         state.is_working = true;
         if let Some(session_id) = work_session_id {
+            flag_attention(state, &session_id, AttentionKind::Unread);
             state.working_by_session.insert(session_id, true);
         }
     }
@@ -327,6 +410,36 @@ fn reduce_session_error(state: &mut AppState, session_id: &str, error: &Assistan
         });
 }
 
+/// Groups text search matches per file, keeping the server's file order.
+fn group_text_search_results(
+    directory: &str,
+    results: &[TextSearchResult],
+) -> Vec<SearchFileGroup> {
+    let mut groups: Vec<SearchFileGroup> = Vec::new();
+    let mut index_by_path: HashMap<&str, usize> = HashMap::new();
+    for result in results {
+        let idx = *index_by_path.entry(&result.path).or_insert_with(|| {
+            let path = Path::new(&result.path);
+            let absolute_path = if path.is_absolute() {
+                result.path.clone()
+            } else {
+                Path::new(directory)
+                    .join(path)
+                    .to_string_lossy()
+                    .to_string()
+            };
+            groups.push(SearchFileGroup {
+                path: result.path.clone(),
+                absolute_path,
+                matches: Vec::new(),
+            });
+            groups.len() - 1
+        });
+        groups[idx].matches.push(result.clone());
+    }
+    groups
+}
+
 pub fn upsert_session_tab(
     tab_by_session: &mut HashMap<String, LiveId>,
     session_id: String,
@@ -353,6 +466,487 @@ pub fn upsert_file_tab(
     }
 }
 
+/// The parent chain of `session_id`, root first. Stops at a parent that is
+/// not loaded and never loops on a cyclic `parent_id`.
+pub fn session_ancestors<'a>(sessions: &'a [Session], session_id: &str) -> Vec<&'a Session> {
+    let find = |id: &str| sessions.iter().find(|session| session.id == id);
+    let mut chain: Vec<&Session> = Vec::new();
+    let mut parent_id = find(session_id).and_then(|session| session.parent_id.as_deref());
+    while let Some(parent) = parent_id.and_then(find) {
+        if parent.id == session_id || chain.iter().any(|seen| seen.id == parent.id) {
+            break;
+        }
+        chain.push(parent);
+        parent_id = parent.parent_id.as_deref();
+    }
+    chain.reverse();
+    chain
+}
+
+/// Total cost of the assistant replies in a conversation.
+pub fn session_cost(messages: &[MessageWithParts]) -> f64 {
+    messages
+        .iter()
+        .map(|message| match &message.info {
+            Message::Assistant(assistant) => assistant.cost,
+            Message::User(_) => 0.0,
+        })
+        .sum()
+}
+
+fn session_has_changes(session: &Session) -> bool {
+    session.summary.as_ref().is_some_and(|summary| {
+        !summary.diffs.is_empty()
+            || summary.files > 0
+            || summary.additions != 0
+            || summary.deletions != 0
+    })
+}
+
+/// Whether a session passes the sessions panel filter. Sessions whose
+/// messages are not cached match the query on title and slug only.
+pub fn session_matches_filter(
+    session: &Session,
+    filter: &SessionFilter,
+    messages: Option<&[MessageWithParts]>,
+    working: bool,
+    pending_permission: bool,
+) -> bool {
+    if (filter.working && !working)
+        || (filter.has_changes && !session_has_changes(session))
+        || (filter.shared && session.share.is_none())
+        || (filter.pending_permission && !pending_permission)
+    {
+        return false;
+    }
+    let query = filter.query.trim().to_lowercase();
+    if query.is_empty() {
+        return true;
+    }
+    let matches = |text: &str| text.to_lowercase().contains(&query);
+    matches(&session.title)
+        || matches(&session.slug)
+        || messages.is_some_and(|messages| {
+            messages
+                .iter()
+                .flat_map(|message| &message.parts)
+                .filter_map(Part::text_content)
+                .any(matches)
+        })
+}
+
+/// The sessions listed in the sessions panel: filtered, then ordered newest
+/// or most expensive first.
+pub fn visible_sessions(state: &AppState) -> Vec<Session> {
+    let filter = &state.session_filter;
+    let pending: HashSet<&str> = state
+        .pending_permissions
+        .iter()
+        .map(|request| request.session_id.as_str())
+        .collect();
+    let mut sessions: Vec<Session> = state
+        .sessions
+        .iter()
+        .filter(|session| {
+            session_matches_filter(
+                session,
+                filter,
+                state
+                    .messages_by_session
+                    .get(&session.id)
+                    .map(Vec::as_slice),
+                state
+                    .working_by_session
+                    .get(&session.id)
+                    .copied()
+                    .unwrap_or(false),
+                pending.contains(session.id.as_str()),
+            )
+        })
+        .cloned()
+        .collect();
+    match filter.sort {
+        SessionSort::Updated => {
+            sessions.sort_by_key(|session| std::cmp::Reverse(session.time.updated))
+        }
+        SessionSort::Created => {
+            sessions.sort_by_key(|session| std::cmp::Reverse(session.time.created))
+        }
+        SessionSort::Cost => {
+            let costs: HashMap<String, f64> = sessions
+                .iter()
+                .map(|session| {
+                    let cost = state
+                        .messages_by_session
+                        .get(&session.id)
+                        .map_or(0.0, |messages| session_cost(messages));
+                    (session.id.clone(), cost)
+                })
+                .collect();
+            sessions.sort_by(|a, b| costs[&b.id].total_cmp(&costs[&a.id]));
+        }
+    }
+    sessions
+}
+
+/// Which local calendar-day bucket `timestamp_ms` falls in, seen from `now_ms`.
+/// Timestamps in the future count as today.
+pub fn date_bucket(timestamp_ms: i64, now_ms: i64) -> DateBucket {
+    date_bucket_in(&chrono::Local, timestamp_ms, now_ms)
+}
+
+/// `date_bucket` with calendar days taken in `tz`.
+pub fn date_bucket_in<Tz: chrono::TimeZone>(tz: &Tz, timestamp_ms: i64, now_ms: i64) -> DateBucket {
+    let day = |ms: i64| {
+        tz.timestamp_millis_opt(ms)
+            .single()
+            .map(|time| time.date_naive())
+    };
+    let (Some(then), Some(today)) = (day(timestamp_ms), day(now_ms)) else {
+        return DateBucket::Older;
+    };
+    match (today - then).num_days() {
+        i64::MIN..=0 => DateBucket::Today,
+        1 => DateBucket::Yesterday,
+        2..=6 => DateBucket::ThisWeek,
+        _ => DateBucket::Older,
+    }
+}
+
+/// Why `session_id` needs attention, most urgent first.
+pub fn session_attention(state: &AppState, session_id: &str) -> Vec<AttentionKind> {
+    let mut kinds: BTreeSet<AttentionKind> = state
+        .attention_by_session
+        .get(session_id)
+        .cloned()
+        .unwrap_or_default();
+    if state
+        .pending_permissions
+        .iter()
+        .any(|request| request.session_id == session_id)
+    {
+        kinds.insert(AttentionKind::Permission);
+    }
+    if state
+        .pending_questions
+        .iter()
+        .any(|request| request.session_id == session_id)
+    {
+        kinds.insert(AttentionKind::Question);
+    }
+    kinds.into_iter().collect()
+}
+
+/// Every session needing attention with its most urgent reason, ordered by
+/// urgency and then by latest activity.
+pub fn attention_inbox(state: &AppState) -> Vec<(String, AttentionKind)> {
+    let session_ids: BTreeSet<&str> = state
+        .attention_by_session
+        .keys()
+        .map(String::as_str)
+        .chain(
+            state
+                .pending_permissions
+                .iter()
+                .map(|request| request.session_id.as_str()),
+        )
+        .chain(
+            state
+                .pending_questions
+                .iter()
+                .map(|request| request.session_id.as_str()),
+        )
+        .collect();
+    let mut inbox: Vec<(String, AttentionKind)> = session_ids
+        .into_iter()
+        .filter_map(|session_id| {
+            session_attention(state, session_id)
+                .first()
+                .map(|kind| (session_id.to_string(), *kind))
+        })
+        .collect();
+    let updated = |session_id: &str| {
+        state
+            .find_session(session_id)
+            .map_or(0, |session| session.time.updated)
+    };
+    inbox.sort_by(|a, b| {
+        a.1.cmp(&b.1)
+            .then_with(|| updated(&b.0).cmp(&updated(&a.0)))
+    });
+    inbox
+}
+
+/// The inbox entry after `current`, wrapping around. Never `current` itself.
+pub fn next_attention_session<'a>(
+    inbox: &'a [(String, AttentionKind)],
+    current: Option<&str>,
+) -> Option<&'a str> {
+    let start = current
+        .and_then(|current| inbox.iter().position(|(id, _)| id == current))
+        .map_or(0, |index| index + 1);
+    inbox
+        .iter()
+        .cycle()
+        .skip(start)
+        .take(inbox.len())
+        .map(|(id, _)| id.as_str())
+        .find(|id| Some(*id) != current)
+}
+
+/// One-line `key: value` summary of a permission's metadata, sorted by key and
+/// cut at `max_chars`. Multi-line strings only contribute their first line.
+pub fn permission_metadata_preview(request: &PermissionRequest, max_chars: usize) -> String {
+    let mut entries: Vec<(&String, &serde_json::Value)> = request.metadata.iter().collect();
+    entries.sort_by_key(|(key, _)| *key);
+    let preview = entries
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(key, value)| match value {
+            serde_json::Value::String(text) => {
+                format!("{}: {}", key, text.lines().next().unwrap_or_default())
+            }
+            other => format!("{}: {}", key, other),
+        })
+        .collect::<Vec<_>>()
+        .join("  ·  ");
+    match preview.char_indices().nth(max_chars) {
+        Some((idx, _)) => format!("{}…", &preview[..idx]),
+        None => preview,
+    }
+}
+
+/// Directory a session's permission requests run in: the session's own, else
+/// the nearest ancestor's. `None` while the session isn't loaded.
+pub fn permission_directory<'a>(state: &'a AppState, session_id: &str) -> Option<&'a str> {
+    let session = state.find_session(session_id)?;
+    std::iter::once(session)
+        .chain(
+            session_ancestors(&state.sessions, session_id)
+                .into_iter()
+                .rev(),
+        )
+        .map(|session| session.directory.as_str())
+        .find(|directory| !directory.is_empty())
+}
+
+/// Runs `request` through the local permission policy and records any rule
+/// that fires in the audit log. Returns the action to reply with, or `None`
+/// when no rule matched or the policy is a dry run.
+pub fn apply_permission_policy(
+    state: &mut AppState,
+    request: &PermissionRequest,
+    now_ms: i64,
+) -> Option<PolicyAction> {
+    let directory = permission_directory(state, &request.session_id);
+    let rule =
+        state
+            .permission_policy
+            .evaluate(&request.permission, &request.patterns, directory)?;
+    let entry = PermissionAuditEntry {
+        time_ms: now_ms,
+        session_id: request.session_id.clone(),
+        permission: request.permission.clone(),
+        patterns: request.patterns.clone(),
+        action: rule.action,
+        rule: rule.describe(),
+        dry_run: state.permission_policy.dry_run,
+    };
+    let decision = (!entry.dry_run).then_some(entry.action);
+    state.permission_audit.push(entry);
+    let overflow = state
+        .permission_audit
+        .len()
+        .saturating_sub(PERMISSION_AUDIT_LIMIT);
+    state.permission_audit.drain(..overflow);
+    decision
+}
+
+/// Records the composer's current picks for the session in view.
+pub fn remember_composer_selection(state: &mut AppState) {
+    let Some(session_id) = state.current_session_id.clone() else {
+        return;
+    };
+    let selection = state.composer_selection();
+    state
+        .composer_selection_by_session
+        .insert(session_id.clone(), selection);
+    state.composer_selection_session = Some(session_id);
+}
+
+/// The picks to restore for `session_id`: the ones last used there, else the
+/// model and agent of its last assistant message. `None` when neither is known.
+pub fn session_composer_selection(state: &AppState, session_id: &str) -> Option<ComposerSelection> {
+    if let Some(selection) = state.composer_selection_by_session.get(session_id) {
+        return Some(selection.clone());
+    }
+    state
+        .messages_for_session(session_id)
+        .iter()
+        .rev()
+        .find_map(|message| match &message.info {
+            Message::Assistant(assistant) => Some(ComposerSelection {
+                model: (!assistant.model_id.is_empty())
+                    .then(|| (assistant.provider_id.clone(), assistant.model_id.clone())),
+                variant: assistant.variant.clone(),
+                agent: (!assistant.agent.is_empty()).then(|| assistant.agent.clone()),
+                skill: None,
+            }),
+            Message::User(_) => None,
+        })
+}
+
+/// Selects the model picked in the model picker. Returns `false`, leaving
+/// the selection alone, when the providers no longer list it. The variant is
+/// kept when the new model offers it too.
+pub fn select_model(state: &mut AppState, provider_id: &str, model_id: &str) -> bool {
+    let Some(index) = state
+        .model_entries
+        .iter()
+        .position(|(provider, model)| provider == provider_id && model == model_id)
+    else {
+        return false;
+    };
+    state.selected_model_idx = index;
+    let variant = state.selected_variant.take();
+    select_variant(state, variant);
+    true
+}
+
+/// Sets the variant sent with prompts, dropping one the model does not offer.
+pub fn select_variant(state: &mut AppState, variant: Option<String>) {
+    state.selected_variant =
+        variant.filter(|variant| state.selected_model_variants().contains(variant));
+}
+
+/// Points the composer at `selection`. Unknown models, variants, agents and
+/// skills fall back to the defaults.
+pub fn apply_composer_selection(state: &mut AppState, selection: &ComposerSelection) {
+    if let Some((provider_id, model_id)) = &selection.model {
+        if !select_model(state, provider_id, model_id) {
+            state.selected_model_idx = 0;
+        }
+    }
+    select_variant(state, selection.variant.clone());
+    state.selected_agent_idx = selection
+        .agent
+        .as_ref()
+        .and_then(|name| state.agents.iter().position(|agent| agent.name == *name));
+    state.selected_skill_idx = selection
+        .skill
+        .as_ref()
+        .and_then(|name| state.skills.iter().position(|skill| skill.name == *name));
+}
+
+/// The rules written in `agent`'s config entry. `Agent::permission` also
+/// holds the server's defaults, which must not be written back as config.
+fn configured_agent_rules(state: &AppState, agent: &str) -> PermissionRuleset {
+    state
+        .config
+        .as_ref()
+        .and_then(|config| config.agent.get(agent))
+        .and_then(|agent| agent.permission.as_ref())
+        .map(|permission| permission.0.clone())
+        .unwrap_or_default()
+}
+
+/// Replaces the rules editor draft with the rules `target` uses right now;
+/// for an agent, the rules from its config entry.
+pub fn load_rules_editor(state: &mut AppState, target: RulesTarget) {
+    let rules = match &target {
+        RulesTarget::NewSessions => state.new_session_permission(),
+        RulesTarget::Agent(name) => Some(configured_agent_rules(state, name)),
+    };
+    state.rules_editor = RulesEditorState {
+        target,
+        rules: rules.unwrap_or_default(),
+        offset: 0,
+        status: None,
+    };
+}
+
+/// The editor target after the current one: new sessions, then each visible
+/// agent, wrapping around.
+pub fn next_rules_target(state: &AppState) -> RulesTarget {
+    let agents: Vec<&str> = state
+        .agents
+        .iter()
+        .filter(|agent| agent.hidden != Some(true))
+        .map(|agent| agent.name.as_str())
+        .collect();
+    let next = match &state.rules_editor.target {
+        RulesTarget::NewSessions => agents.first(),
+        RulesTarget::Agent(name) => agents
+            .iter()
+            .position(|agent| agent == name)
+            .and_then(|index| agents.get(index + 1)),
+    };
+    next.map(|name| RulesTarget::Agent(name.to_string()))
+        .unwrap_or(RulesTarget::NewSessions)
+}
+
+/// Applies the editor draft. New sessions pick it up right away; for an agent
+/// this returns the name and rules to write into its config.
+pub fn apply_rules_editor(state: &mut AppState) -> Option<(String, PermissionRuleset)> {
+    let rules = state.rules_editor.rules.clone();
+    let name = match &state.rules_editor.target {
+        RulesTarget::NewSessions => {
+            state.session_permission_override = Some(rules);
+            state.rules_editor.status = Some("Applies to sessions created from now on".to_string());
+            return None;
+        }
+        RulesTarget::Agent(name) => name.clone(),
+    };
+    // The config keeps one entry per permission, which cannot express every
+    // order across permissions.
+    if let Some(at) = PermissionConfig(rules.clone()).reordered_rule() {
+        state.rules_editor.status = Some(format!(
+            "Not saved: the config cannot keep rule {} after the earlier rules it follows. \
+             Move it next to the other {} rules.",
+            at + 1,
+            rules[at].permission
+        ));
+        return None;
+    }
+    // The server merges config updates, so deleting a pattern cannot remove it.
+    let kept = configured_agent_rules(state, &name)
+        .iter()
+        .filter(|old| {
+            !rules
+                .iter()
+                .any(|rule| rule.permission == old.permission && rule.pattern == old.pattern)
+        })
+        .count();
+    let mut status = format!("Saving to the {} agent config…", name);
+    if kept > 0 {
+        status.push_str(&format!(
+            " {} deleted rule(s) stay until removed from the config file.",
+            kept
+        ));
+    }
+    state.rules_editor.status = Some(status);
+    Some((name, rules))
+}
+
+/// The file before or after `current` in a session diff, wrapping around.
+/// Falls back to the first file when `current` is no longer in the diff.
+pub fn adjacent_diff_file<'a>(
+    diffs: &'a [openpad_protocol::FileDiff],
+    current: &str,
+    forward: bool,
+) -> Option<&'a str> {
+    let len = diffs.len();
+    if len == 0 {
+        return None;
+    }
+    let next = match diffs.iter().position(|diff| diff.file == current) {
+        Some(at) if forward => (at + 1) % len,
+        Some(at) => (at + len - 1) % len,
+        None => 0,
+    };
+    Some(&diffs[next].file)
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum UnsavedDecision {
     Save,
@@ -375,8 +969,8 @@ mod tests {
     use super::*;
     use crate::state::{CenterTabKind, OpenFileState};
     use openpad_protocol::{
-        AssistantMessage, Message, MessageTime, MessageWithParts, PermissionRequest, SessionTime,
-        UserMessage,
+        AssistantMessage, Message, MessageTime, MessageWithParts, Model, ModelCapabilities,
+        ModelVariant, PermissionRequest, Provider, SessionTime, UserMessage,
     };
 
     fn user_message(session_id: &str, id: &str) -> MessageWithParts {
@@ -424,6 +1018,147 @@ mod tests {
         })
     }
 
+    fn agent(name: &str, permission: PermissionRuleset) -> openpad_protocol::Agent {
+        openpad_protocol::Agent {
+            name: name.to_string(),
+            description: None,
+            mode: "primary".to_string(),
+            native: None,
+            hidden: None,
+            top_p: None,
+            temperature: None,
+            color: None,
+            permission,
+            model: None,
+            variant: None,
+            prompt: None,
+            options: Default::default(),
+            steps: None,
+        }
+    }
+
+    #[test]
+    fn composer_selection_is_per_session_and_defaults_from_last_reply() {
+        let mut state = AppState::default();
+        state.model_entries = vec![
+            ("anthropic".to_string(), "claude".to_string()),
+            ("openai".to_string(), "gpt".to_string()),
+        ];
+        state.agents = vec![agent("build", vec![]), agent("plan", vec![])];
+        let mut reply = assistant_message("s1", "a1", Some(2));
+        if let Message::Assistant(assistant) = &mut reply {
+            assistant.provider_id = "openai".to_string();
+            assistant.model_id = "gpt".to_string();
+            assistant.agent = "plan".to_string();
+        }
+        state.messages_by_session.insert(
+            "s1".to_string(),
+            vec![
+                user_message("s1", "m1"),
+                MessageWithParts {
+                    info: reply,
+                    parts: vec![],
+                },
+            ],
+        );
+        assert_eq!(session_composer_selection(&state, "s2"), None);
+
+        let selection = session_composer_selection(&state, "s1").unwrap();
+        apply_composer_selection(&mut state, &selection);
+        assert_eq!(state.selected_model_idx, 1);
+        assert_eq!(state.selected_agent_idx, Some(1));
+
+        state.current_session_id = Some("s2".to_string());
+        state.selected_model_idx = 0;
+        state.selected_agent_idx = Some(0);
+        remember_composer_selection(&mut state);
+        assert_eq!(state.composer_selection_session.as_deref(), Some("s2"));
+
+        let selection = session_composer_selection(&state, "s1").unwrap();
+        apply_composer_selection(&mut state, &selection);
+        assert_eq!(
+            state.composer_selection().model,
+            Some(("openai".to_string(), "gpt".to_string()))
+        );
+        let selection = session_composer_selection(&state, "s2").unwrap();
+        apply_composer_selection(&mut state, &selection);
+        assert_eq!(state.selected_model_idx, 0);
+        assert_eq!(state.selected_agent_name().as_deref(), Some("build"));
+    }
+
+    fn provider_with_models(id: &str, models: &[(&str, bool)]) -> Provider {
+        let variant = |effort: &str| {
+            let variant = ModelVariant {
+                reasoning_effort: Some(effort.to_string()),
+                ..Default::default()
+            };
+            (effort.to_string(), variant)
+        };
+        let models = models
+            .iter()
+            .map(|(model, reasoning)| {
+                let model = Model {
+                    id: model.to_string(),
+                    provider_id: id.to_string(),
+                    name: model.to_string(),
+                    capabilities: ModelCapabilities {
+                        reasoning: *reasoning,
+                        ..Default::default()
+                    },
+                    variants: Some(HashMap::from([variant("high"), variant("low")])),
+                    ..Default::default()
+                };
+                (model.id.clone(), model)
+            })
+            .collect();
+        Provider {
+            id: id.to_string(),
+            name: id.to_string(),
+            models,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn variant_is_offered_only_for_reasoning_models() {
+        let mut state = AppState::default();
+        state.providers = vec![provider_with_models(
+            "openai",
+            &[("gpt", true), ("mini", false)],
+        )];
+        state.update_model_entries();
+        assert!(select_model(&mut state, "openai", "gpt"));
+        assert_eq!(state.selected_model_variants(), vec!["low", "high"]);
+
+        select_variant(&mut state, Some("high".to_string()));
+        assert_eq!(state.selected_variant.as_deref(), Some("high"));
+        select_variant(&mut state, Some("turbo".to_string()));
+        assert_eq!(state.selected_variant, None);
+
+        select_variant(&mut state, Some("high".to_string()));
+        assert!(select_model(&mut state, "openai", "mini"));
+        assert!(state.selected_model_variants().is_empty());
+        assert_eq!(state.selected_variant, None);
+
+        let mut reply = assistant_message("s1", "a1", Some(2));
+        if let Message::Assistant(assistant) = &mut reply {
+            assistant.provider_id = "openai".to_string();
+            assistant.model_id = "gpt".to_string();
+            assistant.variant = Some("high".to_string());
+        }
+        state.messages_by_session.insert(
+            "s1".to_string(),
+            vec![MessageWithParts {
+                info: reply,
+                parts: vec![],
+            }],
+        );
+        let selection = session_composer_selection(&state, "s1").unwrap();
+        apply_composer_selection(&mut state, &selection);
+        assert_eq!(state.selected_model_label(), "gpt");
+        assert_eq!(state.selected_variant.as_deref(), Some("high"));
+    }
+
     #[test]
     fn messages_loaded_routes_to_target_session_cache() {
         let mut state = AppState::default();
@@ -459,6 +1194,102 @@ mod tests {
         assert_eq!(state.messages_for_session("s1").len(), 1);
     }
 
+    #[test]
+    fn rules_editor_applies_to_new_sessions_or_agent_config() {
+        use openpad_protocol::{PermissionAction, PermissionRule};
+        let rule = |permission: &str, pattern: &str, action| PermissionRule {
+            permission: permission.to_string(),
+            pattern: pattern.to_string(),
+            action,
+        };
+        let mut state = AppState::default();
+        state.agents = vec![
+            agent(
+                "build",
+                vec![
+                    rule("*", "*", PermissionAction::Allow),
+                    rule("bash", "*", PermissionAction::Ask),
+                    rule("bash", "rm *", PermissionAction::Deny),
+                ],
+            ),
+            agent("plan", vec![]),
+        ];
+        state.selected_agent_idx = Some(0);
+        // Only the last two rules are in the config; the first is a default.
+        let mut config = openpad_protocol::Config {
+            model: None,
+            agent: HashMap::new(),
+            extra: HashMap::new(),
+        };
+        config.agent.insert(
+            "build".to_string(),
+            openpad_protocol::AgentConfig {
+                permission: Some(PermissionConfig(state.agents[0].permission[1..].to_vec())),
+                extra: HashMap::new(),
+            },
+        );
+        state.config = Some(config);
+
+        load_rules_editor(&mut state, RulesTarget::NewSessions);
+        let editor = &mut state.rules_editor;
+        editor.add_rule("edit", " ", PermissionAction::Allow);
+        editor.move_rule(2, true);
+        editor.move_rule(0, true);
+        editor.cycle_action(0);
+        assert_eq!(
+            editor.rules,
+            vec![
+                rule("bash", "*", PermissionAction::Deny),
+                rule("edit", "*", PermissionAction::Allow),
+                rule("bash", "rm *", PermissionAction::Deny),
+            ]
+        );
+        assert!(apply_rules_editor(&mut state).is_none());
+        assert_eq!(
+            state.new_session_permission(),
+            Some(state.rules_editor.rules.clone())
+        );
+
+        let target = next_rules_target(&state);
+        assert_eq!(target, RulesTarget::Agent("build".to_string()));
+        load_rules_editor(&mut state, target);
+        assert_eq!(state.rules_editor.rules.len(), 2);
+        state.rules_editor.add_rule("*", "*", PermissionAction::Ask);
+        state.rules_editor.move_rule(2, true);
+        assert!(apply_rules_editor(&mut state).is_none());
+        assert!(state
+            .rules_editor
+            .status
+            .as_deref()
+            .unwrap()
+            .contains("rule 3"));
+        state.rules_editor.remove_rule(1);
+        state.rules_editor.remove_rule(1);
+        let (name, rules) = apply_rules_editor(&mut state).unwrap();
+        assert_eq!(name, "build");
+        assert_eq!(rules, vec![rule("bash", "*", PermissionAction::Ask)]);
+        assert!(state
+            .rules_editor
+            .status
+            .as_deref()
+            .unwrap()
+            .contains("1 deleted rule(s) stay"));
+
+        reduce_app_state(
+            &mut state,
+            &AppAction::AgentPermissionUpdated {
+                agent: "build".to_string(),
+                result: Ok(()),
+            },
+        );
+        assert_eq!(
+            state.rules_editor.status.as_deref(),
+            Some("Saved to the build agent config")
+        );
+        state.rules_editor.target = RulesTarget::Agent("plan".to_string());
+        assert_eq!(next_rules_target(&state), RulesTarget::NewSessions);
+    }
+
     #[test]
     fn pending_permission_received_dedupes_by_id() {
         let mut state = AppState::default();
@@ -536,6 +1367,312 @@ mod tests {
         assert!(state.sessions.iter().any(|s| s.id == loaded.id));
     }
 
+    fn session(id: &str, parent: Option<&str>) -> Session {
+        Session {
+            id: id.to_string(),
+            slug: format!("{}-slug", id),
+            project_id: "p".to_string(),
+            directory: "/tmp".to_string(),
+            parent_id: parent.map(str::to_string),
+            title: id.to_string(),
+            version: String::new(),
+            time: SessionTime {
+                created: 1,
+                updated: 1,
+                compacting: None,
+                archived: None,
+            },
+            summary: None,
+            share: None,
+            permission: None,
+            revert: None,
+        }
+    }
+
+    fn session_at(id: &str, title: &str, created: i64, updated: i64) -> Session {
+        let mut session = session(id, None);
+        session.title = title.to_string();
+        session.time.created = created;
+        session.time.updated = updated;
+        session
+    }
+
+    #[test]
+    fn visible_sessions_filter_on_text_and_flags_then_sort() {
+        let mut state = AppState::default();
+        let mut shared = session_at("s1", "Fix parser", 10, 30);
+        shared.share = Some(openpad_protocol::SessionShare {
+            url: "https://example.com/s1".to_string(),
+        });
+        state.sessions = vec![
+            shared,
+            session_at("s2", "Write docs", 20, 10),
+            session_at("s3", "Refactor", 30, 20),
+        ];
+        let mut reply = MessageWithParts {
+            info: assistant_message("s3", "a1", Some(2)),
+            parts: vec![Part::Text {
+                id: "p1".to_string(),
+                session_id: "s3".to_string(),
+                message_id: "a1".to_string(),
+                text: "The tokenizer now handles Unicode".to_string(),
+                synthetic: None,
+                ignored: None,
+                time: None,
+                metadata: None,
+            }],
+        };
+        if let Message::Assistant(assistant) = &mut reply.info {
+            assistant.cost = 0.5;
+        }
+        state
+            .messages_by_session
+            .insert("s3".to_string(), vec![reply]);
+        state.working_by_session.insert("s2".to_string(), true);
+        let ids = |state: &AppState| -> Vec<String> {
+            visible_sessions(state)
+                .into_iter()
+                .map(|session| session.id)
+                .collect()
+        };
+
+        assert_eq!(ids(&state), vec!["s1", "s3", "s2"]);
+
+        state.session_filter.sort = SessionSort::Created;
+        assert_eq!(ids(&state), vec!["s3", "s2", "s1"]);
+
+        state.session_filter.sort = SessionSort::Cost;
+        assert_eq!(ids(&state)[0], "s3");
+
+        state.session_filter.query = "UNICODE".to_string();
+        assert_eq!(ids(&state), vec!["s3"]);
+
+        state.session_filter.query = "s2-slug".to_string();
+        assert_eq!(ids(&state), vec!["s2"]);
+
+        state.session_filter.query.clear();
+        state.session_filter.working = true;
+        assert_eq!(ids(&state), vec!["s2"]);
+
+        state.session_filter.working = false;
+        state.session_filter.shared = true;
+        assert_eq!(ids(&state), vec!["s1"]);
+        assert!(state.session_filter.is_active());
+    }
+
+    #[test]
+    fn permission_policy_replies_unless_dry_run_and_audits_both() {
+        let mut state = AppState::default();
+        let mut session = session("s1", None);
+        session.directory = "/repo".to_string();
+        state.sessions = vec![session];
+        state.permission_policy = crate::utils::permission_policy::PermissionPolicy::parse(
+            "reject bash rm -rf *\n[project /repo]\nallow read *\n",
+        );
+        let request = |id: &str, permission: &str, pattern: &str| PermissionRequest {
+            id: id.to_string(),
+            session_id: "s1".to_string(),
+            permission: permission.to_string(),
+            patterns: vec![pattern.to_string()],
+            metadata: Default::default(),
+            always: vec![],
+            tool: None,
+        };
+
+        assert_eq!(
+            apply_permission_policy(&mut state, &request("p1", "read", "/repo/a.rs"), 5),
+            Some(PolicyAction::Allow)
+        );
+        assert_eq!(
+            apply_permission_policy(&mut state, &request("p2", "bash", "make"), 6),
+            None
+        );
+        state.permission_policy.dry_run = true;
+        assert_eq!(
+            apply_permission_policy(&mut state, &request("p3", "bash", "rm -rf /"), 7),
+            None
+        );
+
+        assert_eq!(state.permission_audit.len(), 2);
+        let last = state.permission_audit.last().unwrap();
+        assert_eq!(last.action, PolicyAction::Reject);
+        assert!(last.dry_run);
+        assert_eq!(last.rule, "line 1: reject bash rm -rf *");
+    }
+
+    #[test]
+    fn permission_directory_falls_back_to_the_parent_session() {
+        let mut state = AppState::default();
+        let mut parent = session("parent", None);
+        parent.directory = "/repo".to_string();
+        let mut child = session("child", Some("parent"));
+        child.directory.clear();
+        state.sessions = vec![parent, child];
+
+        assert_eq!(permission_directory(&state, "child"), Some("/repo"));
+        assert_eq!(permission_directory(&state, "parent"), Some("/repo"));
+        assert_eq!(permission_directory(&state, "unloaded"), None);
+    }
+
+    #[test]
+    fn permission_metadata_preview_is_sorted_and_truncated() {
+        let mut request = PermissionRequest {
+            id: "perm-1".to_string(),
+            session_id: "s1".to_string(),
+            permission: "bash".to_string(),
+            patterns: vec!["git *".to_string()],
+            metadata: Default::default(),
+            always: vec![],
+            tool: None,
+        };
+        assert_eq!(permission_metadata_preview(&request, 80), "");
+
+        request.metadata.insert(
+            "command".to_string(),
+            serde_json::json!("git status\ngit diff"),
+        );
+        request
+            .metadata
+            .insert("timeout".to_string(), serde_json::json!(30));
+        request
+            .metadata
+            .insert("cwd".to_string(), serde_json::Value::Null);
+        assert_eq!(
+            permission_metadata_preview(&request, 80),
+            "command: git status  ·  timeout: 30"
+        );
+        assert_eq!(permission_metadata_preview(&request, 7), "command…");
+    }
+
+    #[test]
+    fn background_sessions_collect_attention_until_viewed() {
+        let mut state = AppState::default();
+        state.current_session_id = Some("viewed".to_string());
+        state.sessions = vec![
+            session_at("viewed", "Viewed", 1, 1),
+            session_at("bg", "Background", 1, 5),
+            session_at("broken", "Broken", 1, 3),
+        ];
+
+        reduce_app_state(
+            &mut state,
+            &AppAction::MessageReceived(assistant_message("viewed", "v1", None)),
+        );
+        reduce_app_state(
+            &mut state,
+            &AppAction::MessageReceived(assistant_message("bg", "a1", None)),
+        );
+        assert!(session_attention(&state, "viewed").is_empty());
+        assert_eq!(session_attention(&state, "bg"), vec![AttentionKind::Unread]);
+
+        reduce_app_state(
+            &mut state,
+            &AppAction::MessageReceived(assistant_message("bg", "a1", Some(2))),
+        );
+        assert_eq!(
+            session_attention(&state, "bg"),
+            vec![AttentionKind::Finished, AttentionKind::Unread]
+        );
+
+        reduce_app_state(
+            &mut state,
+            &AppAction::SessionErrorReceived {
+                session_id: "broken".to_string(),
+                error: AssistantError::UnknownError {
+                    message: "boom".to_string(),
+                },
+            },
+        );
+        state.pending_permissions.push(PermissionRequest {
+            id: "perm-1".to_string(),
+            session_id: "viewed".to_string(),
+            permission: "bash".to_string(),
+            patterns: vec![],
+            metadata: Default::default(),
+            always: vec![],
+            tool: None,
+        });
+
+        let inbox = attention_inbox(&state);
+        assert_eq!(
+            inbox,
+            vec![
+                ("viewed".to_string(), AttentionKind::Permission),
+                ("broken".to_string(), AttentionKind::Error),
+                ("bg".to_string(), AttentionKind::Finished),
+            ]
+        );
+        assert_eq!(
+            next_attention_session(&inbox, Some("viewed")),
+            Some("broken")
+        );
+        assert_eq!(next_attention_session(&inbox, Some("bg")), Some("viewed"));
+        assert_eq!(next_attention_session(&inbox, None), Some("viewed"));
+        assert_eq!(next_attention_session(&inbox[..1], Some("viewed")), None);
+    }
+
+    #[test]
+    fn date_buckets_follow_local_days() {
+        let hour = 3_600_000;
+        let day = 24 * hour;
+        let utc = chrono::FixedOffset::east_opt(0).unwrap();
+        let now = 10 * day + hour;
+
+        assert_eq!(date_bucket_in(&utc, now + 5_000, now), DateBucket::Today);
+        assert_eq!(date_bucket_in(&utc, 10 * day, now), DateBucket::Today);
+        assert_eq!(
+            date_bucket_in(&utc, 10 * day - 1, now),
+            DateBucket::Yesterday
+        );
+        assert_eq!(date_bucket_in(&utc, 4 * day, now), DateBucket::ThisWeek);
+        assert_eq!(date_bucket_in(&utc, 4 * day - 1, now), DateBucket::Older);
+
+        // At 01:00 UTC it is still the previous evening in New York, and at
+        // 23:30 UTC it is already the next morning in Tokyo.
+        let new_york = chrono::FixedOffset::west_opt(5 * 3600).unwrap();
+        assert_eq!(
+            date_bucket_in(&new_york, 10 * day - 2 * hour, now),
+            DateBucket::Today
+        );
+        assert_eq!(
+            date_bucket_in(&new_york, 10 * day - 20 * hour, now),
+            DateBucket::Yesterday
+        );
+        let tokyo = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+        let tokyo_now = 10 * day - hour / 2;
+        assert_eq!(
+            date_bucket_in(&tokyo, 10 * day - 8 * hour, tokyo_now),
+            DateBucket::Today
+        );
+        assert_eq!(
+            date_bucket_in(&tokyo, 10 * day - 10 * hour, tokyo_now),
+            DateBucket::Yesterday
+        );
+    }
+
+    #[test]
+    fn session_ancestors_walk_to_root_and_stop_on_cycles() {
+        let sessions = vec![
+            session("root", None),
+            session("branch", Some("root")),
+            session("subagent", Some("branch")),
+            session("orphan", Some("missing")),
+            session("a", Some("b")),
+            session("b", Some("a")),
+        ];
+        let ids = |id: &str| -> Vec<String> {
+            session_ancestors(&sessions, id)
+                .iter()
+                .map(|s| s.id.clone())
+                .collect()
+        };
+
+        assert_eq!(ids("subagent"), vec!["root", "branch"]);
+        assert!(ids("root").is_empty());
+        assert!(ids("orphan").is_empty());
+        assert_eq!(ids("a"), vec!["b"]);
+    }
+
     #[test]
     fn upsert_session_tab_dedupes_existing() {
         let mut map = HashMap::new();
@@ -584,6 +1721,27 @@ mod tests {
         assert_eq!(map.len(), 1);
     }
 
+    #[test]
+    fn adjacent_diff_file_wraps_around_session_files() {
+        let diffs: Vec<openpad_protocol::FileDiff> = ["a.rs", "b.rs", "c.rs"]
+            .iter()
+            .map(|file| openpad_protocol::FileDiff {
+                file: file.to_string(),
+                before: String::new(),
+                after: String::new(),
+                additions: 0,
+                deletions: 0,
+                status: None,
+            })
+            .collect();
+
+        assert_eq!(adjacent_diff_file(&diffs, "a.rs", true), Some("b.rs"));
+        assert_eq!(adjacent_diff_file(&diffs, "c.rs", true), Some("a.rs"));
+        assert_eq!(adjacent_diff_file(&diffs, "a.rs", false), Some("c.rs"));
+        assert_eq!(adjacent_diff_file(&diffs, "gone.rs", true), Some("a.rs"));
+        assert_eq!(adjacent_diff_file(&[], "a.rs", true), None);
+    }
+
     #[test]
     fn pending_intent_resolution_respects_cancel() {
         let intent = Some(PendingCenterIntent::CloseTab { tab_id: LiveId(9) });
@@ -632,6 +1790,12 @@ mod tests {
                     display_name: "a.rs".to_string(),
                     text_cache: "".to_string(),
                     last_saved_revision: 0,
+                    read_only: false,
+                    external_text: None,
+                    pending_merge: None,
+                    change_hunks: Vec::new(),
+                    selected_hunk: 0,
+                    show_original: false,
                 },
             },
         );
@@ -664,4 +1828,59 @@ mod tests {
         assert!(!state.connected);
         assert!(!state.is_working);
     }
+
+    fn search_result(path: &str, line_number: usize) -> TextSearchResult {
+        TextSearchResult {
+            path: path.to_string(),
+            lines: "let x = 1;".to_string(),
+            line_number,
+            absolute_offset: 0,
+            submatches: vec![],
+        }
+    }
+
+    #[test]
+    fn text_search_completed_groups_matches_per_file() {
+        let mut state = AppState::default();
+        state.text_search.query = "x".to_string();
+        state.text_search.directory = "/repo".to_string();
+        state.text_search.loading = true;
+
+        reduce_app_state(
+            &mut state,
+            &AppAction::TextSearchCompleted {
+                query: "x".to_string(),
+                results: vec![
+                    search_result("src/b.rs", 3),
+                    search_result("src/a.rs", 1),
+                    search_result("src/b.rs", 9),
+                ],
+            },
+        );
+
+        assert!(!state.text_search.loading);
+        assert_eq!(state.text_search.groups.len(), 2);
+        assert_eq!(state.text_search.groups[0].path, "src/b.rs");
+        assert_eq!(state.text_search.groups[0].absolute_path, "/repo/src/b.rs");
+        assert_eq!(state.text_search.groups[0].matches.len(), 2);
+        assert_eq!(state.text_search.match_count(), 3);
+    }
+
+    #[test]
+    fn text_search_completed_ignores_stale_query() {
+        let mut state = AppState::default();
+        state.text_search.query = "new".to_string();
+        state.text_search.loading = true;
+
+        reduce_app_state(
+            &mut state,
+            &AppAction::TextSearchCompleted {
+                query: "old".to_string(),
+                results: vec![search_result("a.rs", 1)],
+            },
+        );
+
+        assert!(state.text_search.loading);
+        assert!(state.text_search.groups.is_empty());
+    }
 }
@@ -44,6 +44,24 @@ pub fn update_session_title_ui(ui: &WidgetRef, cx: &mut Cx, title: &str, is_acti
         .set_text(cx, &format!("{marker} {title}"));
 }
 
+/// Shows the parent (and, for deeper sessions, the root) of a child session
+/// before its title.
+pub fn update_session_breadcrumb(
+    ui: &WidgetRef,
+    cx: &mut Cx,
+    root_title: Option<&str>,
+    parent_title: Option<&str>,
+) {
+    ui.view(cx, &[id!(session_breadcrumb)])
+        .set_visible(cx, parent_title.is_some());
+    ui.view(cx, &[id!(breadcrumb_root_wrap)])
+        .set_visible(cx, root_title.is_some());
+    ui.button(cx, &[id!(breadcrumb_root_button)])
+        .set_text(cx, root_title.unwrap_or(""));
+    ui.button(cx, &[id!(breadcrumb_parent_button)])
+        .set_text(cx, parent_title.unwrap_or(""));
+}
+
 /// Updates the revert indicator visibility based on session revert state
 pub fn update_revert_indicator(ui: &WidgetRef, cx: &mut Cx, is_reverted: bool) {
     ui.view(cx, &[id!(revert_indicator)])
@@ -0,0 +1,123 @@
+/// Scores `candidate` against a fuzzy `query`. Every query char must appear in
+/// order (case-insensitive); returns `None` otherwise. Higher is better:
+/// consecutive runs, segment starts and hits inside the file name are rewarded,
+/// long paths are slightly penalised.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let chars: Vec<char> = candidate.chars().collect();
+    let file_name_start = chars
+        .iter()
+        .rposition(|c| *c == '/' || *c == '\\')
+        .map(|idx| idx + 1)
+        .unwrap_or(0);
+
+    // Greedy matching from the first occurrence can lock onto a poor hit
+    // (e.g. the `r` in `src/` for "reducer"), so try every starting point.
+    (0..chars.len())
+        .filter(|&start| char_matches(chars[start], query[0]))
+        .filter_map(|start| score_from(&query, &chars, start, file_name_start))
+        .max()
+        .map(|score| score * 10 - chars.len() as i64)
+}
+
+fn char_matches(ch: char, lowered: char) -> bool {
+    ch.to_lowercase().eq(std::iter::once(lowered))
+}
+
+fn score_from(query: &[char], chars: &[char], start: usize, file_name_start: usize) -> Option<i64> {
+    let mut score = 0i64;
+    let mut query_idx = 0;
+    let mut prev_match: Option<usize> = None;
+    for (idx, &ch) in chars.iter().enumerate().skip(start) {
+        if query_idx == query.len() {
+            break;
+        }
+        if !char_matches(ch, query[query_idx]) {
+            continue;
+        }
+
+        score += 1;
+        if prev_match.is_some_and(|prev| prev + 1 == idx) {
+            score += 10;
+        }
+        let segment_start = match idx.checked_sub(1).map(|i| chars[i]) {
+            None => true,
+            Some(p) => {
+                matches!(p, '/' | '\\' | '_' | '-' | '.' | ' ')
+                    || (p.is_lowercase() && ch.is_uppercase())
+            }
+        };
+        if segment_start {
+            score += 8;
+        }
+        if idx >= file_name_start {
+            score += 3;
+        }
+        prev_match = Some(idx);
+        query_idx += 1;
+    }
+
+    (query_idx == query.len()).then_some(score)
+}
+
+/// Filters `candidates` to fuzzy matches of `query`, best first. Ties keep input order.
+pub fn rank_fuzzy(query: &str, candidates: &[String]) -> Vec<String> {
+    let mut scored: Vec<(i64, usize)> = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, candidate)| fuzzy_score(query, candidate).map(|score| (score, idx)))
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    scored
+        .into_iter()
+        .map(|(_, idx)| candidates[idx].clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score_requires_ordered_subsequence() {
+        assert!(fuzzy_score("mdrs", "src/main.rs").is_none());
+        assert!(fuzzy_score("mnrs", "src/main.rs").is_some());
+        assert!(fuzzy_score("MAIN", "src/main.rs").is_some());
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+    }
+
+    #[test]
+    fn test_fuzzy_score_prefers_file_name_and_segment_starts() {
+        let file_name_hit = fuzzy_score("app", "src/app.rs").unwrap();
+        let scattered_hit = fuzzy_score("app", "src/a_pretty_path.rs").unwrap();
+        assert!(file_name_hit > scattered_hit);
+
+        let camel = fuzzy_score("ep", "src/EditorPanel.rs").unwrap();
+        let buried = fuzzy_score("ep", "src/keeper.rs").unwrap();
+        assert!(camel > buried);
+    }
+
+    #[test]
+    fn test_rank_fuzzy_orders_best_first_and_drops_misses() {
+        let candidates = vec![
+            "openpad-app/src/state/reducer.rs".to_string(),
+            "README.md".to_string(),
+            "openpad-app/src/state/mod.rs".to_string(),
+            "docs/redux.md".to_string(),
+        ];
+        let ranked = rank_fuzzy("reducer", &candidates);
+        assert_eq!(ranked, vec!["openpad-app/src/state/reducer.rs".to_string()]);
+
+        let ranked = rank_fuzzy("red", &candidates);
+        assert_eq!(ranked[0], "docs/redux.md");
+        assert_eq!(ranked[1], "openpad-app/src/state/reducer.rs");
+    }
+}
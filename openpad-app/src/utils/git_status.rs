@@ -0,0 +1,206 @@
+use openpad_protocol::File;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Working-tree change kind reported by `/file/status`. The OpenAPI schema
+/// only defines `added`, `deleted` and `modified` (new untracked files come
+/// back as `added`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GitChangeKind {
+    Modified,
+    Added,
+    Deleted,
+}
+
+impl GitChangeKind {
+    /// Returns `None` for a status outside the schema, so a newer server
+    /// cannot get its files painted with a guessed marker.
+    pub fn from_status(status: &str) -> Option<Self> {
+        match status {
+            "modified" => Some(Self::Modified),
+            "added" => Some(Self::Added),
+            "deleted" => Some(Self::Deleted),
+            _ => None,
+        }
+    }
+
+    pub fn marker(self) -> &'static str {
+        match self {
+            Self::Modified => "M",
+            Self::Added => "A",
+            Self::Deleted => "D",
+        }
+    }
+}
+
+/// Decoration drawn next to a changed file in the files panel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileDecoration {
+    pub kind: GitChangeKind,
+    pub added: i64,
+    pub removed: i64,
+}
+
+impl FileDecoration {
+    /// Suffix appended to the file name, e.g. `M +3 -1`.
+    pub fn label(&self) -> String {
+        let mut label = self.kind.marker().to_string();
+        if self.added > 0 {
+            label.push_str(&format!(" +{}", self.added));
+        }
+        if self.removed > 0 {
+            label.push_str(&format!(" -{}", self.removed));
+        }
+        label
+    }
+}
+
+/// Git status of one project keyed by absolute path, with every ancestor
+/// folder of a changed file (up to the root) marked dirty.
+#[derive(Clone, Debug, Default)]
+pub struct GitStatusIndex {
+    pub files: HashMap<String, FileDecoration>,
+    pub dirty_dirs: HashSet<String>,
+    /// Deleted files no longer on disk, grouped by their parent folder.
+    pub deleted_by_dir: HashMap<String, Vec<String>>,
+}
+
+impl GitStatusIndex {
+    /// Entries with an unknown status are left out entirely: no decoration
+    /// and no dirty folders.
+    pub fn build(root: &str, files: &[File]) -> Self {
+        let root_path = Path::new(root);
+        let mut index = Self::default();
+        for file in files {
+            let Some(kind) = GitChangeKind::from_status(&file.status) else {
+                continue;
+            };
+            let path = Path::new(&file.path);
+            let absolute = if path.is_absolute() {
+                path.to_path_buf()
+            } else {
+                root_path.join(path)
+            };
+
+            let mut parent = absolute.parent();
+            while let Some(dir) = parent {
+                if !dir.starts_with(root_path) {
+                    break;
+                }
+                index.dirty_dirs.insert(dir.to_string_lossy().to_string());
+                if dir == root_path {
+                    break;
+                }
+                parent = dir.parent();
+            }
+
+            if kind == GitChangeKind::Deleted {
+                if let (Some(dir), Some(name)) = (absolute.parent(), absolute.file_name()) {
+                    index
+                        .deleted_by_dir
+                        .entry(dir.to_string_lossy().to_string())
+                        .or_default()
+                        .push(name.to_string_lossy().to_string());
+                }
+            }
+
+            index.files.insert(
+                absolute.to_string_lossy().to_string(),
+                FileDecoration {
+                    kind,
+                    added: file.added,
+                    removed: file.removed,
+                },
+            );
+        }
+        for names in index.deleted_by_dir.values_mut() {
+            names.sort_by_key(|name| name.to_lowercase());
+        }
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, status: &str, added: i64, removed: i64) -> File {
+        File {
+            path: path.to_string(),
+            added,
+            removed,
+            status: status.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_decoration_label() {
+        let decoration = FileDecoration {
+            kind: GitChangeKind::Modified,
+            added: 3,
+            removed: 1,
+        };
+        assert_eq!(decoration.label(), "M +3 -1");
+
+        let decoration = FileDecoration {
+            kind: GitChangeKind::Deleted,
+            added: 0,
+            removed: 0,
+        };
+        assert_eq!(decoration.label(), "D");
+    }
+
+    #[test]
+    fn test_status_index_rolls_up_dirty_folders() {
+        let index = GitStatusIndex::build(
+            "/repo",
+            &[
+                file("src/app/main.rs", "modified", 2, 0),
+                file("README.md", "added", 10, 0),
+            ],
+        );
+
+        assert_eq!(
+            index.files.get("/repo/src/app/main.rs").map(|d| d.kind),
+            Some(GitChangeKind::Modified)
+        );
+        assert!(index.dirty_dirs.contains("/repo"));
+        assert!(index.dirty_dirs.contains("/repo/src"));
+        assert!(index.dirty_dirs.contains("/repo/src/app"));
+        assert!(!index.dirty_dirs.contains("/"));
+    }
+
+    #[test]
+    fn test_status_index_tracks_deleted_files_by_folder() {
+        let index = GitStatusIndex::build(
+            "/repo",
+            &[
+                file("src/old.rs", "deleted", 0, 40),
+                file("src/Gone.rs", "deleted", 0, 5),
+            ],
+        );
+
+        assert_eq!(
+            index.deleted_by_dir.get("/repo/src"),
+            Some(&vec!["Gone.rs".to_string(), "old.rs".to_string()])
+        );
+        assert!(index.dirty_dirs.contains("/repo/src"));
+    }
+
+    #[test]
+    fn test_status_index_skips_unknown_statuses() {
+        assert_eq!(GitChangeKind::from_status("renamed"), None);
+
+        let index = GitStatusIndex::build(
+            "/repo",
+            &[
+                file("docs/new.md", "renamed", 0, 0),
+                file("src/lib.rs", "modified", 1, 1),
+            ],
+        );
+
+        assert!(!index.files.contains_key("/repo/docs/new.md"));
+        assert!(!index.dirty_dirs.contains("/repo/docs"));
+        assert!(index.files.contains_key("/repo/src/lib.rs"));
+    }
+}
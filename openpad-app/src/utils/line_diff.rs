@@ -0,0 +1,200 @@
+//! Line matching shared by the three-way merge and the editor's agent change gutter.
+
+use openpad_widgets::diff_model::{diff_ops, DiffOp};
+
+/// For each line of `old`, the index of the line it matches in `new` along the
+/// Myers shortest edit script.
+pub fn match_lines(old: &[&str], new: &[&str]) -> Vec<Option<usize>> {
+    let mut matches = vec![None; old.len()];
+    let (mut i, mut j) = (0, 0);
+    for op in diff_ops(old, new) {
+        match op {
+            DiffOp::Equal(_) => {
+                matches[i] = Some(j);
+                i += 1;
+                j += 1;
+            }
+            DiffOp::Delete(_) => i += 1,
+            DiffOp::Insert(_) => j += 1,
+        }
+    }
+    matches
+}
+
+/// Gutter marker kind for a run of changed lines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineChangeKind {
+    Added,
+    Modified,
+    /// Lines removed just above `ChangeHunk::start`
+    Deleted,
+}
+
+impl LineChangeKind {
+    pub fn marker(self) -> &'static str {
+        match self {
+            Self::Added => "+",
+            Self::Modified => "~",
+            Self::Deleted => "−",
+        }
+    }
+}
+
+/// One contiguous change between a file's original and current text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangeHunk {
+    pub kind: LineChangeKind,
+    /// Zero-based first line in the current text
+    pub start: usize,
+    /// Number of current lines covered (0 for deletions)
+    pub len: usize,
+    /// The original lines this hunk replaced, without line endings
+    pub original: Vec<String>,
+}
+
+/// Changed line runs from `before` to `current`, in document order.
+pub fn change_hunks(before: &str, current: &str) -> Vec<ChangeHunk> {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = current.lines().collect();
+    let to_new = match_lines(&old, &new);
+
+    let mut hunks = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && to_new[i] == Some(j) {
+            i += 1;
+            j += 1;
+            continue;
+        }
+        let old_start = i;
+        while i < old.len() && to_new[i].is_none() {
+            i += 1;
+        }
+        let new_end = to_new.get(i).copied().flatten().unwrap_or(new.len());
+        let kind = match (i > old_start, new_end > j) {
+            (true, true) => LineChangeKind::Modified,
+            (false, true) => LineChangeKind::Added,
+            _ => LineChangeKind::Deleted,
+        };
+        hunks.push(ChangeHunk {
+            kind,
+            start: j,
+            len: new_end - j,
+            original: old[old_start..i].iter().map(|s| s.to_string()).collect(),
+        });
+        j = new_end;
+    }
+    hunks
+}
+
+/// `current` with `hunk` replaced by its original lines. Untouched lines keep
+/// their own endings; restored lines use the file's (`\r\n` if it has any).
+pub fn revert_hunk(current: &str, hunk: &ChangeHunk) -> String {
+    let eol = if current.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let lines: Vec<&str> = current.split_inclusive('\n').collect();
+    let start = hunk.start.min(lines.len());
+    let end = (hunk.start + hunk.len).min(lines.len());
+    let mut text = String::with_capacity(current.len());
+    for line in &lines[..start] {
+        text.push_str(line);
+    }
+    if !hunk.original.is_empty() && !text.is_empty() && !text.ends_with('\n') {
+        text.push_str(eol);
+    }
+    for line in &hunk.original {
+        text.push_str(line);
+        text.push_str(eol);
+    }
+    for line in &lines[end..] {
+        text.push_str(line);
+    }
+    if !current.is_empty() && !current.ends_with('\n') && end == lines.len() {
+        if let Some(trimmed) = text.strip_suffix(eol) {
+            text.truncate(trimmed.len());
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_hunks_classifies_added_modified_and_deleted_lines() {
+        let before = "a\nb\nc\nd\n";
+        let current = "a\nB\nc\nnew\n";
+        let hunks = change_hunks(before, current);
+        assert_eq!(
+            hunks,
+            vec![
+                ChangeHunk {
+                    kind: LineChangeKind::Modified,
+                    start: 1,
+                    len: 1,
+                    original: vec!["b".to_string()],
+                },
+                ChangeHunk {
+                    kind: LineChangeKind::Modified,
+                    start: 3,
+                    len: 1,
+                    original: vec!["d".to_string()],
+                },
+            ]
+        );
+
+        let hunks = change_hunks("a\nb\nc\n", "a\nx\ny\nb\n");
+        assert_eq!(hunks[0].kind, LineChangeKind::Added);
+        assert_eq!((hunks[0].start, hunks[0].len), (1, 2));
+        assert_eq!(hunks[1].kind, LineChangeKind::Deleted);
+        assert_eq!((hunks[1].start, hunks[1].len), (4, 0));
+        assert_eq!(hunks[1].original, vec!["c".to_string()]);
+    }
+
+    #[test]
+    fn test_change_hunks_on_large_files_are_line_precise() {
+        let before: String = (0..5000).map(|n| format!("line {n}\n")).collect();
+        let current = before.replacen("line 2500\n", "changed\n", 1);
+        let hunks = change_hunks(&before, &current);
+        assert_eq!(
+            hunks,
+            vec![ChangeHunk {
+                kind: LineChangeKind::Modified,
+                start: 2500,
+                len: 1,
+                original: vec!["line 2500".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_revert_hunk_restores_original_lines() {
+        let before = "one\ntwo\nthree\n";
+        let current = "one\nTWO\n2b\nthree\n";
+        let hunks = change_hunks(before, current);
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(revert_hunk(current, &hunks[0]), before);
+
+        let current = "one\nthree\n";
+        let hunks = change_hunks(before, current);
+        assert_eq!(hunks[0].kind, LineChangeKind::Deleted);
+        assert_eq!(revert_hunk(current, &hunks[0]), before);
+    }
+
+    #[test]
+    fn test_revert_hunk_keeps_crlf_line_endings() {
+        let before = "a\r\nb\r\nc\r\n";
+        let current = "a\r\nB\r\nc\r\n";
+        let hunks = change_hunks(before, current);
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(revert_hunk(current, &hunks[0]), before);
+
+        let current = "a\r\nc";
+        let hunks = change_hunks("a\r\nc\r\nd", current);
+        assert_eq!(revert_hunk(current, &hunks[0]), "a\r\nc\r\nd");
+    }
+}
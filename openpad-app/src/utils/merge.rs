@@ -0,0 +1,216 @@
+//! Line-based three-way merge (diff3) used when a file changes on disk while
+//! its editor tab has unsaved edits.
+
+use super::line_diff::match_lines;
+
+/// A run of merged lines: settled, or changed differently on both sides.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MergeChunk {
+    Clean(String),
+    Conflict { mine: String, disk: String },
+}
+
+/// Which version a conflict is resolved to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictSide {
+    Mine,
+    Disk,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MergeResult {
+    pub chunks: Vec<MergeChunk>,
+}
+
+impl MergeResult {
+    pub fn conflicts(&self) -> usize {
+        self.chunks
+            .iter()
+            .filter(|chunk| matches!(chunk, MergeChunk::Conflict { .. }))
+            .count()
+    }
+
+    /// Merged text with conflict `n` taken from `sides[n]`; `None` until every
+    /// conflict has a side. Never contains conflict markers.
+    pub fn resolve(&self, sides: &[ConflictSide]) -> Option<String> {
+        let mut sides = sides.iter();
+        let mut out = String::new();
+        for chunk in &self.chunks {
+            match chunk {
+                MergeChunk::Clean(text) => out.push_str(text),
+                MergeChunk::Conflict { mine, disk } => match sides.next()? {
+                    ConflictSide::Mine => out.push_str(mine),
+                    ConflictSide::Disk => out.push_str(disk),
+                },
+            }
+        }
+        Some(out)
+    }
+
+    fn push_clean(&mut self, line: &str) {
+        if let Some(MergeChunk::Clean(text)) = self.chunks.last_mut() {
+            text.push_str(line);
+        } else {
+            self.chunks.push(MergeChunk::Clean(line.to_string()));
+        }
+    }
+}
+
+/// Merge `mine` and `theirs`, both derived from `base`. Regions changed on only
+/// one side are taken from that side; regions changed on both sides become
+/// conflicts for the user to settle.
+pub fn three_way_merge(base: &str, mine: &str, theirs: &str) -> MergeResult {
+    let base: Vec<&str> = base.split_inclusive('\n').collect();
+    let mine: Vec<&str> = mine.split_inclusive('\n').collect();
+    let theirs: Vec<&str> = theirs.split_inclusive('\n').collect();
+    let to_mine = match_lines(&base, &mine);
+    let to_theirs = match_lines(&base, &theirs);
+
+    let mut result = MergeResult::default();
+    let (mut i, mut a, mut b) = (0, 0, 0);
+    while i < base.len() || a < mine.len() || b < theirs.len() {
+        // Lines unchanged on both sides.
+        if i < base.len() && to_mine[i] == Some(a) && to_theirs[i] == Some(b) {
+            result.push_clean(base[i]);
+            i += 1;
+            a += 1;
+            b += 1;
+            continue;
+        }
+
+        // Find the next base line kept by both sides; everything before it is changed.
+        let (next_i, next_a, next_b) = (i..base.len())
+            .find_map(|k| match (to_mine[k], to_theirs[k]) {
+                (Some(ma), Some(mb)) => Some((k, ma, mb)),
+                _ => None,
+            })
+            .unwrap_or((base.len(), mine.len(), theirs.len()));
+        let base_chunk = &base[i..next_i];
+        let mine_chunk = &mine[a..next_a];
+        let theirs_chunk = &theirs[b..next_b];
+
+        if mine_chunk == base_chunk || mine_chunk == theirs_chunk {
+            theirs_chunk.iter().for_each(|line| result.push_clean(line));
+        } else if theirs_chunk == base_chunk {
+            mine_chunk.iter().for_each(|line| result.push_clean(line));
+        } else {
+            result.chunks.push(MergeChunk::Conflict {
+                mine: mine_chunk.concat(),
+                disk: theirs_chunk.concat(),
+            });
+        }
+        i = next_i;
+        a = next_a;
+        b = next_b;
+    }
+    result
+}
+
+/// A merge with conflicts, held outside the editor buffer while the user
+/// picks a side for each conflict in turn.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingMerge {
+    /// Buffer text the merge was computed from
+    pub mine: String,
+    pub result: MergeResult,
+    pub sides: Vec<ConflictSide>,
+}
+
+impl PendingMerge {
+    pub fn new(mine: String, result: MergeResult) -> Self {
+        Self {
+            mine,
+            result,
+            sides: Vec::new(),
+        }
+    }
+
+    /// The first conflict still without a side, as `(index, mine, disk)`.
+    pub fn current(&self) -> Option<(usize, &str, &str)> {
+        self.result
+            .chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                MergeChunk::Conflict { mine, disk } => Some((mine.as_str(), disk.as_str())),
+                MergeChunk::Clean(_) => None,
+            })
+            .enumerate()
+            .nth(self.sides.len())
+            .map(|(index, (mine, disk))| (index, mine, disk))
+    }
+
+    /// Settle the current conflict; returns the merged text once none are left.
+    pub fn choose(&mut self, side: ConflictSide) -> Option<String> {
+        if self.current().is_some() {
+            self.sides.push(side);
+        }
+        self.result.resolve(&self.sides)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_takes_non_overlapping_edits_from_both_sides() {
+        let base = "a\nb\nc\nd\ne\n";
+        let mine = "a\nB\nc\nd\ne\n";
+        let theirs = "a\nb\nc\nd\nE\nf\n";
+        let merged = three_way_merge(base, mine, theirs);
+        assert_eq!(merged.conflicts(), 0);
+        assert_eq!(merged.resolve(&[]).as_deref(), Some("a\nB\nc\nd\nE\nf\n"));
+    }
+
+    #[test]
+    fn test_merge_keeps_overlapping_edits_as_conflicts() {
+        let base = "fn main() {\n    run();\n}\n";
+        let mine = "fn main() {\n    run_fast();\n}\n";
+        let theirs = "fn main() {\n    run_safe();\n}\n";
+        let merged = three_way_merge(base, mine, theirs);
+        assert_eq!(merged.conflicts(), 1);
+        assert_eq!(
+            merged.chunks[1],
+            MergeChunk::Conflict {
+                mine: "    run_fast();\n".to_string(),
+                disk: "    run_safe();\n".to_string(),
+            }
+        );
+        assert_eq!(merged.resolve(&[]), None);
+        assert_eq!(
+            merged.resolve(&[ConflictSide::Disk]).as_deref(),
+            Some("fn main() {\n    run_safe();\n}\n")
+        );
+    }
+
+    #[test]
+    fn test_merge_accepts_identical_edits_and_missing_trailing_newline() {
+        let merged = three_way_merge("x\ny", "x\nz", "x\nz");
+        assert_eq!(merged.conflicts(), 0);
+        assert_eq!(merged.resolve(&[]).as_deref(), Some("x\nz"));
+
+        let merged = three_way_merge("x\ny", "x\nmine", "x\ntheirs");
+        assert_eq!(merged.conflicts(), 1);
+        assert_eq!(
+            merged.resolve(&[ConflictSide::Mine]).as_deref(),
+            Some("x\nmine")
+        );
+    }
+
+    #[test]
+    fn test_pending_merge_walks_conflicts_in_order() {
+        let base = "a\nb\nc\nd\ne\n";
+        let mine = "a\nB1\nc\nd\nE1\n";
+        let theirs = "a\nB2\nc\nd\nE2\n";
+        let mut pending = PendingMerge::new(mine.to_string(), three_way_merge(base, mine, theirs));
+
+        assert_eq!(pending.current(), Some((0, "B1\n", "B2\n")));
+        assert_eq!(pending.choose(ConflictSide::Mine), None);
+        assert_eq!(pending.current(), Some((1, "E1\n", "E2\n")));
+        assert_eq!(
+            pending.choose(ConflictSide::Disk).as_deref(),
+            Some("a\nB1\nc\nd\nE2\n")
+        );
+        assert_eq!(pending.current(), None);
+    }
+}
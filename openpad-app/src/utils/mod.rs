@@ -1 +1,7 @@
+pub mod fuzzy;
+pub mod git_status;
+pub mod line_diff;
+pub mod merge;
+pub mod model_catalog;
 pub mod path_utils;
+pub mod permission_policy;
@@ -0,0 +1,336 @@
+//! Models offered by the composer's model picker: one `ModelChoice` per
+//! provider model with its capabilities, pricing and context window, laid out
+//! as favorites, recents and provider sections and narrowed by a fuzzy query.
+//! Favorites and recents are kept as `provider/model` keys in the user's
+//! preferences file.
+
+use crate::utils::fuzzy::fuzzy_score;
+use makepad_widgets::log;
+use openpad_protocol::Provider;
+use openpad_widgets::utils::preferences;
+
+const FAVORITES_PREFERENCE: &str = "model_favorites";
+const RECENTS_PREFERENCE: &str = "model_recents";
+/// Recently picked models listed above the provider sections.
+pub const RECENT_MODELS_LIMIT: usize = 5;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModelChoice {
+    pub provider_id: String,
+    pub provider_name: String,
+    pub model_id: String,
+    pub name: String,
+    pub reasoning: bool,
+    pub toolcall: bool,
+    pub image_input: bool,
+    /// USD per million input tokens
+    pub input_cost: f64,
+    /// USD per million output tokens
+    pub output_cost: f64,
+    /// Context window in tokens; 0 when unknown
+    pub context: f64,
+}
+
+impl ModelChoice {
+    /// `provider/model`, the form favorites and recents are stored in
+    pub fn key(&self) -> String {
+        format!("{}/{}", self.provider_id, self.model_id)
+    }
+
+    pub fn badges(&self) -> String {
+        [
+            (self.reasoning, "reasoning"),
+            (self.toolcall, "tools"),
+            (self.image_input, "image"),
+        ]
+        .iter()
+        .filter(|(on, _)| *on)
+        .map(|(_, badge)| *badge)
+        .collect::<Vec<_>>()
+        .join(" · ")
+    }
+
+    /// Pricing per million tokens and context window, e.g.
+    /// `$3.00 in · $15.00 out per 1M · 200K context`.
+    pub fn details(&self) -> String {
+        let price = if self.input_cost == 0.0 && self.output_cost == 0.0 {
+            "free".to_string()
+        } else {
+            format!(
+                "${:.2} in · ${:.2} out per 1M",
+                self.input_cost, self.output_cost
+            )
+        };
+        if self.context > 0.0 {
+            format!("{} · {} context", price, format_token_count(self.context))
+        } else {
+            price
+        }
+    }
+}
+
+/// `200K`, `1M`, `1.5M`; counts under a thousand are shown as is.
+pub fn format_token_count(tokens: f64) -> String {
+    let (value, suffix) = if tokens >= 1_000_000.0 {
+        (tokens / 1_000_000.0, "M")
+    } else if tokens >= 1_000.0 {
+        (tokens / 1_000.0, "K")
+    } else {
+        return format!("{}", tokens.round());
+    };
+    let text = format!("{:.1}", value);
+    format!("{}{}", text.trim_end_matches(".0"), suffix)
+}
+
+/// Every model of every provider, by provider name and then model name.
+pub fn model_choices(providers: &[Provider]) -> Vec<ModelChoice> {
+    let mut choices: Vec<ModelChoice> = providers
+        .iter()
+        .flat_map(|provider| {
+            provider.models.values().map(move |model| ModelChoice {
+                provider_id: provider.id.clone(),
+                provider_name: provider.name.clone(),
+                model_id: model.id.clone(),
+                name: model.name.clone(),
+                reasoning: model.capabilities.reasoning,
+                toolcall: model.capabilities.toolcall,
+                image_input: model.capabilities.input.image,
+                input_cost: model.cost.input,
+                output_cost: model.cost.output,
+                context: model.limit.context,
+            })
+        })
+        .collect();
+    choices.sort_by(|a, b| {
+        a.provider_name
+            .cmp(&b.provider_name)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    choices
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PickerRow {
+    Section(String),
+    /// Index into the choices the rows were built from
+    Model {
+        choice: usize,
+        favorite: bool,
+    },
+}
+
+/// Lays out the picker: favorites, then recents not already favorited, then
+/// one section per provider. A non-empty `query` drops models that do not
+/// fuzzy-match and orders the rest, and the provider sections, best first.
+pub fn picker_rows(
+    choices: &[ModelChoice],
+    query: &str,
+    favorites: &[String],
+    recents: &[String],
+) -> Vec<PickerRow> {
+    let query = query.trim();
+    let mut scored: Vec<(usize, i64)> = choices
+        .iter()
+        .enumerate()
+        .filter_map(|(index, choice)| {
+            let haystack = format!(
+                "{} {} {}",
+                choice.provider_name, choice.name, choice.model_id
+            );
+            fuzzy_score(query, &haystack).map(|score| (index, score))
+        })
+        .collect();
+    if !query.is_empty() {
+        scored.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    }
+
+    let is_favorite = |index: usize| favorites.contains(&choices[index].key());
+    let mut rows = Vec::new();
+    let push_section = |rows: &mut Vec<PickerRow>, title: &str, keys: &[String]| {
+        let models: Vec<PickerRow> = keys
+            .iter()
+            .filter_map(|key| {
+                scored
+                    .iter()
+                    .find(|(index, _)| choices[*index].key() == *key)
+            })
+            .map(|(index, _)| PickerRow::Model {
+                choice: *index,
+                favorite: is_favorite(*index),
+            })
+            .collect();
+        if !models.is_empty() {
+            rows.push(PickerRow::Section(title.to_string()));
+            rows.extend(models);
+        }
+    };
+    push_section(&mut rows, "Favorites", favorites);
+    let recents: Vec<String> = recents
+        .iter()
+        .filter(|key| !favorites.contains(key))
+        .take(RECENT_MODELS_LIMIT)
+        .cloned()
+        .collect();
+    push_section(&mut rows, "Recent", &recents);
+
+    let mut providers: Vec<&str> = Vec::new();
+    for (index, _) in &scored {
+        let provider = choices[*index].provider_id.as_str();
+        if !providers.contains(&provider) {
+            providers.push(provider);
+        }
+    }
+    for provider in providers {
+        let indices: Vec<usize> = scored
+            .iter()
+            .map(|(index, _)| *index)
+            .filter(|index| choices[*index].provider_id == provider)
+            .collect();
+        rows.push(PickerRow::Section(
+            choices[indices[0]].provider_name.clone(),
+        ));
+        rows.extend(indices.into_iter().map(|index| PickerRow::Model {
+            choice: index,
+            favorite: is_favorite(index),
+        }));
+    }
+    rows
+}
+
+/// Adds `key` to `favorites`, or removes it when already there.
+pub fn toggle_favorite(favorites: &mut Vec<String>, key: &str) {
+    match favorites.iter().position(|favorite| favorite == key) {
+        Some(index) => {
+            favorites.remove(index);
+        }
+        None => favorites.push(key.to_string()),
+    }
+}
+
+/// Moves `key` to the front of `recents`, keeping a few more than are shown so
+/// favorited models do not crowd out the list.
+pub fn push_recent(recents: &mut Vec<String>, key: &str) {
+    recents.retain(|recent| recent != key);
+    recents.insert(0, key.to_string());
+    recents.truncate(RECENT_MODELS_LIMIT * 2);
+}
+
+fn load_keys(preference: &str) -> Vec<String> {
+    preferences::get(preference)
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn save_keys(preference: &str, keys: &[String]) {
+    if let Err(err) = preferences::set(preference, &keys.join(",")) {
+        log!("Could not save {}: {}", preference, err);
+    }
+}
+
+pub fn load_favorites() -> Vec<String> {
+    load_keys(FAVORITES_PREFERENCE)
+}
+
+pub fn save_favorites(favorites: &[String]) {
+    save_keys(FAVORITES_PREFERENCE, favorites);
+}
+
+pub fn load_recents() -> Vec<String> {
+    load_keys(RECENTS_PREFERENCE)
+}
+
+pub fn save_recents(recents: &[String]) {
+    save_keys(RECENTS_PREFERENCE, recents);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn choice(provider: &str, model: &str, name: &str) -> ModelChoice {
+        ModelChoice {
+            provider_id: provider.to_lowercase(),
+            provider_name: provider.to_string(),
+            model_id: model.to_string(),
+            name: name.to_string(),
+            reasoning: false,
+            toolcall: true,
+            image_input: false,
+            input_cost: 3.0,
+            output_cost: 15.0,
+            context: 200_000.0,
+        }
+    }
+
+    #[test]
+    fn details_show_price_per_million_and_context() {
+        let mut model = choice("Anthropic", "claude-sonnet", "Claude Sonnet");
+        model.reasoning = true;
+        assert_eq!(model.badges(), "reasoning · tools");
+        assert_eq!(
+            model.details(),
+            "$3.00 in · $15.00 out per 1M · 200K context"
+        );
+        model.input_cost = 0.0;
+        model.output_cost = 0.0;
+        model.context = 1_048_576.0;
+        assert_eq!(model.details(), "free · 1M context");
+        assert_eq!(format_token_count(1_500_000.0), "1.5M");
+        assert_eq!(format_token_count(512.0), "512");
+    }
+
+    #[test]
+    fn rows_pin_favorites_and_recents_then_group_by_provider() {
+        let choices = vec![
+            choice("Anthropic", "claude-haiku", "Claude Haiku"),
+            choice("Anthropic", "claude-sonnet", "Claude Sonnet"),
+            choice("OpenAI", "gpt-5", "GPT-5"),
+        ];
+        let favorites = vec!["openai/gpt-5".to_string()];
+        let recents = vec![
+            "openai/gpt-5".to_string(),
+            "anthropic/claude-sonnet".to_string(),
+            "gone/model".to_string(),
+        ];
+        let model = |choice, favorite| PickerRow::Model { choice, favorite };
+        let section = |title: &str| PickerRow::Section(title.to_string());
+
+        assert_eq!(
+            picker_rows(&choices, "", &favorites, &recents),
+            vec![
+                section("Favorites"),
+                model(2, true),
+                section("Recent"),
+                model(1, false),
+                section("Anthropic"),
+                model(0, false),
+                model(1, false),
+                section("OpenAI"),
+                model(2, true),
+            ]
+        );
+        assert_eq!(
+            picker_rows(&choices, "sonnet", &favorites, &[]),
+            vec![section("Anthropic"), model(1, false)]
+        );
+    }
+
+    #[test]
+    fn favorites_toggle_and_recents_move_to_front() {
+        let mut favorites = vec!["a/x".to_string()];
+        toggle_favorite(&mut favorites, "b/y");
+        toggle_favorite(&mut favorites, "a/x");
+        assert_eq!(favorites, vec!["b/y".to_string()]);
+
+        let mut recents = vec!["a/x".to_string(), "b/y".to_string()];
+        push_recent(&mut recents, "b/y");
+        assert_eq!(recents, vec!["b/y".to_string(), "a/x".to_string()]);
+    }
+}
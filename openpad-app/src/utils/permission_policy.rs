@@ -0,0 +1,330 @@
+//! Local auto-approval rules for permission requests, read from
+//! `~/.config/openpad/permission-policy`:
+//!
+//! ```text
+//! # action  permission  pattern (rest of the line)
+//! reject    bash        rm -rf *
+//! allow     read        *
+//!
+//! [project /home/me/repo]
+//! always    edit        /home/me/repo/src/*
+//! ```
+//!
+//! Actions are `allow` (reply once), `always` and `reject`; `*` in the
+//! permission column matches any permission. Rules under `[project <dir>]` only
+//! apply to sessions inside `<dir>` until the next section or `[global]`.
+//! `mode = dry-run` records decisions without replying.
+//!
+//! Reject rules win whenever any of the request's patterns matches. Otherwise
+//! the first allow/always rule matching every pattern decides.
+
+use openpad_protocol::PermissionReply;
+use openpad_widgets::utils::preferences;
+use std::path::{Path, PathBuf};
+
+pub const POLICY_FILE_NAME: &str = "permission-policy";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PolicyAction {
+    Allow,
+    Always,
+    Reject,
+}
+
+impl PolicyAction {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "allow" | "once" => Some(Self::Allow),
+            "always" => Some(Self::Always),
+            "reject" | "deny" => Some(Self::Reject),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Always => "always",
+            Self::Reject => "reject",
+        }
+    }
+
+    pub fn reply(self) -> PermissionReply {
+        match self {
+            Self::Allow => PermissionReply::Once,
+            Self::Always => PermissionReply::Always,
+            Self::Reject => PermissionReply::Reject,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolicyRule {
+    pub action: PolicyAction,
+    pub permission: String,
+    pub pattern: String,
+    pub project: Option<PathBuf>,
+    /// One-based line in the policy file, for the audit log
+    pub line: usize,
+}
+
+impl PolicyRule {
+    pub fn describe(&self) -> String {
+        let scope = self
+            .project
+            .as_ref()
+            .map(|project| format!(" in {}", project.display()))
+            .unwrap_or_default();
+        format!(
+            "line {}: {} {} {}{}",
+            self.line,
+            self.action.label(),
+            self.permission,
+            self.pattern,
+            scope
+        )
+    }
+
+    fn applies_to(&self, permission: &str, directory: Option<&str>) -> bool {
+        (self.permission == "*" || self.permission == permission)
+            && self.project.as_ref().is_none_or(|project| {
+                directory.is_some_and(|dir| Path::new(dir).starts_with(project))
+            })
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PermissionPolicy {
+    pub rules: Vec<PolicyRule>,
+    pub dry_run: bool,
+    /// Lines that could not be parsed, as `line N: reason`
+    pub errors: Vec<String>,
+}
+
+impl PermissionPolicy {
+    pub fn path() -> Option<PathBuf> {
+        preferences::config_path(POLICY_FILE_NAME)
+    }
+
+    /// Reads the policy file; a missing file is an empty policy.
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|text| Self::parse(&text))
+            .unwrap_or_default()
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let mut policy = Self::default();
+        let mut project: Option<PathBuf> = None;
+        for (index, raw) in text.lines().enumerate() {
+            let line_no = index + 1;
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                match section.trim().split_once(char::is_whitespace) {
+                    Some(("project", dir)) => project = Some(PathBuf::from(dir.trim())),
+                    None if section.trim() == "global" => project = None,
+                    _ => policy
+                        .errors
+                        .push(format!("line {}: unknown section [{}]", line_no, section)),
+                }
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim() == "mode" {
+                    match value.trim() {
+                        "dry-run" => policy.dry_run = true,
+                        "apply" => policy.dry_run = false,
+                        other => policy
+                            .errors
+                            .push(format!("line {}: unknown mode {}", line_no, other)),
+                    }
+                    continue;
+                }
+            }
+
+            // Columns may be padded with any run of whitespace; the pattern is
+            // the rest of the line and keeps its inner spacing.
+            let (action, rest) = next_field(line);
+            let (permission, pattern) = next_field(rest);
+            let action = PolicyAction::parse(action);
+            let permission = Some(permission).filter(|p| !p.is_empty());
+            let pattern = Some(pattern.trim()).filter(|p| !p.is_empty());
+            match (action, permission, pattern) {
+                (Some(action), Some(permission), Some(pattern)) => policy.rules.push(PolicyRule {
+                    action,
+                    permission: permission.to_string(),
+                    pattern: pattern.to_string(),
+                    project: project.clone(),
+                    line: line_no,
+                }),
+                (None, _, _) => policy.errors.push(format!(
+                    "line {}: expected allow, always or reject",
+                    line_no
+                )),
+                _ => policy.errors.push(format!(
+                    "line {}: expected <action> <permission> <pattern>",
+                    line_no
+                )),
+            }
+        }
+        policy
+    }
+
+    /// Whether any rule is scoped to a `[project ...]` section, so evaluating
+    /// needs the request's directory.
+    pub fn has_project_rules(&self) -> bool {
+        self.rules.iter().any(|rule| rule.project.is_some())
+    }
+
+    /// The rule that decides this request, if any. Requests without patterns
+    /// only match rules whose pattern is `*`.
+    pub fn evaluate(
+        &self,
+        permission: &str,
+        patterns: &[String],
+        directory: Option<&str>,
+    ) -> Option<&PolicyRule> {
+        let matches = |rule: &PolicyRule, pattern: &str| wildcard_match(&rule.pattern, pattern);
+        let candidates = || {
+            self.rules
+                .iter()
+                .filter(move |rule| rule.applies_to(permission, directory))
+        };
+        if patterns.is_empty() {
+            return candidates().find(|rule| rule.pattern == "*");
+        }
+        candidates()
+            .filter(|rule| rule.action == PolicyAction::Reject)
+            .find(|rule| patterns.iter().any(|pattern| matches(rule, pattern)))
+            .or_else(|| {
+                candidates()
+                    .filter(|rule| rule.action != PolicyAction::Reject)
+                    .find(|rule| patterns.iter().all(|pattern| matches(rule, pattern)))
+            })
+    }
+}
+
+/// Splits off the first whitespace-separated field, returning it and the
+/// remainder with leading whitespace removed.
+fn next_field(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    match text.split_once(char::is_whitespace) {
+        Some((field, rest)) => (field, rest.trim_start()),
+        None => (text, ""),
+    }
+}
+
+/// Glob match where `*` spans any run of characters (including `/`) and `?`
+/// matches exactly one.
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    backtrack = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_spans_separators() {
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("rm -rf *", "rm -rf /tmp/x"));
+        assert!(wildcard_match("/repo/*.rs", "/repo/src/main.rs"));
+        assert!(wildcard_match("git ?tatus", "git status"));
+        assert!(!wildcard_match("git status", "git status --short"));
+        assert!(!wildcard_match("/repo/*", "/other/repo/file"));
+    }
+
+    #[test]
+    fn parse_collects_rules_sections_and_errors() {
+        let policy = PermissionPolicy::parse(
+            "# team policy\nmode = dry-run\nreject bash rm -rf *\n\n[project /repo]\nalways edit /repo/src/*\n[global]\nallow read *\nmaybe read *\nallow bash\n",
+        );
+        assert!(policy.dry_run);
+        assert_eq!(policy.rules.len(), 3);
+        assert_eq!(policy.rules[0].pattern, "rm -rf *");
+        assert_eq!(policy.rules[1].project, Some(PathBuf::from("/repo")));
+        assert_eq!(policy.rules[2].project, None);
+        assert_eq!(policy.rules[2].line, 8);
+        assert_eq!(policy.errors.len(), 2);
+    }
+
+    #[test]
+    fn parse_accepts_the_column_aligned_doc_example() {
+        let policy = PermissionPolicy::parse(
+            "# action  permission  pattern (rest of the line)\n\
+             reject    bash        rm -rf *\n\
+             allow     read        *\n\
+             \n\
+             [project /home/me/repo]\n\
+             always    edit        /home/me/repo/src/*\n",
+        );
+        assert!(policy.errors.is_empty(), "{:?}", policy.errors);
+        let rules: Vec<(PolicyAction, &str, &str)> = policy
+            .rules
+            .iter()
+            .map(|r| (r.action, r.permission.as_str(), r.pattern.as_str()))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                (PolicyAction::Reject, "bash", "rm -rf *"),
+                (PolicyAction::Allow, "read", "*"),
+                (PolicyAction::Always, "edit", "/home/me/repo/src/*"),
+            ]
+        );
+        assert_eq!(
+            policy.rules[2].project,
+            Some(PathBuf::from("/home/me/repo"))
+        );
+    }
+
+    #[test]
+    fn reject_wins_then_first_allow_matching_every_pattern() {
+        let policy = PermissionPolicy::parse(
+            "allow bash git *\nreject bash * rm -rf *\n[project /repo]\nalways edit /repo/*\n",
+        );
+        let patterns = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let rule = policy.evaluate("bash", &patterns(&["git status"]), None);
+        assert_eq!(rule.map(|r| r.action), Some(PolicyAction::Allow));
+        let rule = policy.evaluate("bash", &patterns(&["git pull", "cd / && rm -rf *"]), None);
+        assert_eq!(rule.map(|r| r.action), Some(PolicyAction::Reject));
+        assert!(policy
+            .evaluate("bash", &patterns(&["git pull", "make"]), None)
+            .is_none());
+
+        let edit = patterns(&["/repo/src/lib.rs"]);
+        assert!(policy.evaluate("edit", &edit, Some("/elsewhere")).is_none());
+        let rule = policy.evaluate("edit", &edit, Some("/repo/sub"));
+        assert_eq!(rule.map(|r| r.action), Some(PolicyAction::Always));
+        assert!(policy.evaluate("read", &[], None).is_none());
+    }
+}
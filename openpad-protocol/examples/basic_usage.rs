@@ -83,6 +83,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         agent: None,
         system: None,
         no_reply: None,
+        variant: None,
     };
 
     match client.send_prompt_with_options(&session.id, prompt).await {
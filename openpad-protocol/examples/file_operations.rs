@@ -69,6 +69,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             type_filter: Some("file".to_string()),
             directory: None,
             limit: Some(10),
+            dirs: Some(false),
         })
         .await
     {
@@ -89,6 +90,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             type_filter: Some("directory".to_string()),
             directory: None,
             limit: Some(5),
+            dirs: Some(true),
         })
         .await
     {
@@ -4,17 +4,19 @@
 //! including REST endpoints and Server-Sent Events (SSE) subscription.
 
 use crate::{
-    Agent, AppendPromptRequest, AuthSetRequest, CommandRequest, Config, ExecuteCommandRequest,
-    File, FileDiff, FileReadRequest, FileReadResponse, FileStatusRequest, FilesSearchRequest,
-    GlobalSession, HealthResponse, LogRequest, MCPStatus, McpAddRequest, McpResource,
-    MessageWithParts, PathInfo, PermissionReply, PermissionReplyRequest, PermissionRequest,
-    PermissionResponse, Project, ProjectUpdateRequest, PromptRequest, ProvidersResponse, Pty,
-    RevertRequest, SessionCreateRequest, SessionInitRequest, SessionSummarizeRequest,
-    SessionUpdateRequest, ShellRequest, ShowToastRequest, Skill, Symbol, SymbolsSearchRequest,
-    TextSearchRequest, TextSearchResult, Todo, ToolIDs, ToolList,
+    Agent, AgentPermissionConfig, AgentPermissionConfigUpdate, AppendPromptRequest, AuthSetRequest,
+    CommandRequest, Config, ExecuteCommandRequest, File, FileDiff, FileListRequest, FileNode,
+    FileReadRequest, FileReadResponse, FileStatusRequest, FilesSearchRequest, GlobalSession,
+    HealthResponse, LogRequest, MCPStatus, McpAddRequest, McpResource, MessageWithParts, PathInfo,
+    PermissionConfig, PermissionReply, PermissionReplyRequest, PermissionRequest,
+    PermissionResponse, PermissionRuleset, Project, ProjectUpdateRequest, PromptRequest,
+    ProvidersResponse, Pty, RevertRequest, SessionCreateRequest, SessionInitRequest,
+    SessionSummarizeRequest, SessionUpdateRequest, ShellRequest, ShowToastRequest, Skill, Symbol,
+    SymbolsSearchRequest, TextSearchRequest, TextSearchResult, Todo, ToolIDs, ToolList,
 };
 use crate::{AssistantError, Error, Event, Message, Part, PartInput, Result, Session};
 use reqwest::Client as HttpClient;
+use std::collections::HashMap;
 use std::env;
 use tokio::sync::broadcast;
 
@@ -426,6 +428,25 @@ impl OpenCodeClient {
         self.get_json("/config", "get config").await
     }
 
+    /// Writes `rules` as the `permission` config of `agent`. The server merges
+    /// this into its config, so patterns missing from `rules` are kept.
+    pub async fn update_agent_permission(
+        &self,
+        agent: &str,
+        rules: PermissionRuleset,
+    ) -> Result<Config> {
+        let request = AgentPermissionConfigUpdate {
+            agent: HashMap::from([(
+                agent.to_string(),
+                AgentPermissionConfig {
+                    permission: PermissionConfig(rules),
+                },
+            )]),
+        };
+        self.patch_json("/config", &request, "update agent permission")
+            .await
+    }
+
     pub async fn get_providers(&self) -> Result<ProvidersResponse> {
         self.get_json("/config/providers", "get providers").await
     }
@@ -662,6 +683,9 @@ impl OpenCodeClient {
         if let Some(limit) = request.limit {
             query.push(("limit", limit.to_string()));
         }
+        if let Some(dirs) = request.dirs {
+            query.push(("dirs", dirs.to_string()));
+        }
 
         let response = self
             .http
@@ -690,6 +714,21 @@ impl OpenCodeClient {
         Ok(response.json().await?)
     }
 
+    pub async fn list_files(&self, request: FileListRequest) -> Result<Vec<FileNode>> {
+        let url = format!("{}/file", self.base_url);
+        let response = self
+            .http
+            .get(&url)
+            .query(&[("directory", &self.directory)])
+            .query(&[("path", &request.path)])
+            .timeout(std::time::Duration::from_secs(30))
+            .send()
+            .await?;
+
+        let response = Self::check_response(response, "list files").await?;
+        Ok(response.json().await?)
+    }
+
     pub async fn read_file(&self, request: FileReadRequest) -> Result<FileReadResponse> {
         let url = format!("{}/file/content", self.base_url);
         let response = self
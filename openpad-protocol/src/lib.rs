@@ -77,6 +77,8 @@ mod tests {
         let _: SymbolsSearchRequest;
         let _: Symbol;
         let _: SymbolLocation;
+        let _: FileListRequest;
+        let _: FileNode;
         let _: FileReadRequest;
         let _: FileReadResponse;
         let _: FileStatusRequest;
@@ -192,6 +194,7 @@ mod tests {
 
         let config = Config {
             model: Some("gpt-4".to_string()),
+            agent: HashMap::new(),
             extra,
         };
 
@@ -274,6 +277,46 @@ mod tests {
             spec.get("components")?.get("schemas")?.get(name)
         }
 
+        /// Asserts that `value` fits `schema`: matching types, and only declared
+        /// properties on objects.
+        fn assert_matches_schema(value: &Value, schema: &Value, path: &str) {
+            match schema["type"].as_str() {
+                Some("object") => {
+                    let object = value
+                        .as_object()
+                        .unwrap_or_else(|| panic!("{}: expected an object, got {}", path, value));
+                    let properties = schema["properties"].as_object();
+                    for (key, field) in object {
+                        let field_schema = properties
+                            .and_then(|properties| properties.get(key))
+                            .unwrap_or_else(|| panic!("{}: unexpected field {}", path, key));
+                        assert_matches_schema(field, field_schema, &format!("{}.{}", path, key));
+                    }
+                }
+                Some("number") => assert!(
+                    value.is_number(),
+                    "{}: expected a number, got {}",
+                    path,
+                    value
+                ),
+                Some("string") => assert!(
+                    value.is_string(),
+                    "{}: expected a string, got {}",
+                    path,
+                    value
+                ),
+                Some("boolean") => {
+                    assert!(
+                        value.is_boolean(),
+                        "{}: expected a boolean, got {}",
+                        path,
+                        value
+                    )
+                }
+                other => panic!("{}: unsupported schema type {:?}", path, other),
+            }
+        }
+
         /// Helper to validate that a type can be serialized and has expected fields.
         fn validate_serialization<T: serde::Serialize>(
             value: &T,
@@ -559,7 +602,7 @@ mod tests {
             ];
 
             for action in actions {
-                let json = serde_json::to_value(&action).expect("Failed to serialize");
+                let json = serde_json::to_value(action).expect("Failed to serialize");
                 assert!(
                     json.is_string(),
                     "PermissionAction should serialize as string"
@@ -774,6 +817,173 @@ mod tests {
             );
         }
 
+        #[test]
+        fn test_revert_request_matches_openapi() {
+            let spec = load_openapi_spec();
+            let body = &spec["paths"]["/session/{sessionID}/revert"]["post"]["requestBody"]
+                ["content"]["application/json"]["schema"];
+            let properties = body["properties"]
+                .as_object()
+                .expect("revert body properties");
+
+            let request = RevertRequest {
+                message_id: "msg_1".to_string(),
+                part_id: Some("prt_2".to_string()),
+            };
+            let json = validate_serialization(&request, &["messageID", "partID"]);
+            for key in json.as_object().unwrap().keys() {
+                assert!(properties.contains_key(key), "unexpected field: {}", key);
+            }
+
+            let whole_message = RevertRequest {
+                message_id: "msg_1".to_string(),
+                part_id: None,
+            };
+            let json = serde_json::to_value(&whole_message).unwrap();
+            assert_eq!(json, serde_json::json!({ "messageID": "msg_1" }));
+        }
+
+        #[test]
+        fn test_session_update_request_matches_openapi() {
+            let spec = load_openapi_spec();
+            let body = &spec["paths"]["/session/{sessionID}"]["patch"]["requestBody"]["content"]
+                ["application/json"]["schema"];
+
+            let rename = SessionUpdateRequest {
+                title: Some("New title".to_string()),
+                time: None,
+            };
+            let json = serde_json::to_value(&rename).unwrap();
+            assert_matches_schema(&json, body, "rename");
+            assert_eq!(json, serde_json::json!({ "title": "New title" }));
+
+            let archive = SessionUpdateRequest {
+                title: None,
+                time: Some(SessionUpdateTime {
+                    archived: 1700000000000.0,
+                }),
+            };
+            let json = validate_serialization(&archive, &["time"]);
+            assert_matches_schema(&json, body, "archive");
+            assert_eq!(json["time"]["archived"], 1700000000000.0);
+
+            let unarchive = SessionUpdateRequest {
+                title: None,
+                time: Some(SessionUpdateTime { archived: 0.0 }),
+            };
+            let json = serde_json::to_value(&unarchive).unwrap();
+            assert_matches_schema(&json, body, "unarchive");
+            assert_eq!(json, serde_json::json!({ "time": { "archived": 0.0 } }));
+
+            let time = |archived| SessionTime {
+                created: 1,
+                updated: 2,
+                compacting: None,
+                archived,
+            };
+            assert!(time(Some(1700000000000)).is_archived());
+            assert!(!time(Some(0)).is_archived());
+            assert!(!time(None).is_archived());
+        }
+
+        #[test]
+        fn test_agent_permission_config_keeps_rule_order() {
+            let rule = |permission: &str, pattern: &str, action| PermissionRule {
+                permission: permission.to_string(),
+                pattern: pattern.to_string(),
+                action,
+            };
+            let update = AgentPermissionConfigUpdate {
+                agent: HashMap::from([(
+                    "build".to_string(),
+                    AgentPermissionConfig {
+                        permission: PermissionConfig(vec![
+                            rule("read", "*", PermissionAction::Allow),
+                            rule("bash", "git *", PermissionAction::Allow),
+                            rule("bash", "*", PermissionAction::Ask),
+                            rule("bash", "git *", PermissionAction::Deny),
+                        ]),
+                    },
+                )]),
+            };
+            assert_eq!(
+                serde_json::to_string(&update).unwrap(),
+                r#"{"agent":{"build":{"permission":{"read":"allow","bash":{"*":"ask","git *":"deny"}}}}}"#
+            );
+        }
+
+        #[test]
+        fn test_permission_config_rejects_orders_it_cannot_keep() {
+            let rule = |permission: &str, pattern: &str, action| PermissionRule {
+                permission: permission.to_string(),
+                pattern: pattern.to_string(),
+                action,
+            };
+            // Grouping would move `bash rm *` ahead of the `*` rule it follows.
+            let config = PermissionConfig(vec![
+                rule("bash", "git *", PermissionAction::Allow),
+                rule("*", "*", PermissionAction::Ask),
+                rule("bash", "rm *", PermissionAction::Deny),
+            ]);
+            assert_eq!(config.reordered_rule(), Some(2));
+            assert!(serde_json::to_string(&config).is_err());
+
+            // Rules for unrelated permissions may be regrouped.
+            let config = PermissionConfig(vec![
+                rule("read", "*", PermissionAction::Allow),
+                rule("bash", "*", PermissionAction::Ask),
+                rule("read", "*.env", PermissionAction::Deny),
+            ]);
+            assert_eq!(config.reordered_rule(), None);
+        }
+
+        #[test]
+        fn test_config_reads_agent_permission_in_written_order() {
+            let config: Config = serde_json::from_str(
+                r#"{
+                    "model": "a/b",
+                    "agent": {
+                        "build": {
+                            "model": "c/d",
+                            "permission": {
+                                "bash": {"*": "ask", "git *": "allow", "a*": "deny"},
+                                "edit": "deny"
+                            }
+                        },
+                        "plan": {"permission": "ask"}
+                    }
+                }"#,
+            )
+            .unwrap();
+            let rules = &config.agent["build"].permission.as_ref().unwrap().0;
+            let patterns: Vec<(&str, &str)> = rules
+                .iter()
+                .map(|rule| (rule.permission.as_str(), rule.pattern.as_str()))
+                .collect();
+            assert_eq!(
+                patterns,
+                vec![
+                    ("bash", "*"),
+                    ("bash", "git *"),
+                    ("bash", "a*"),
+                    ("edit", "*")
+                ]
+            );
+            assert_eq!(rules[3].action, PermissionAction::Deny);
+            assert_eq!(config.agent["build"].extra["model"], "c/d");
+            let plan = &config.agent["plan"].permission.as_ref().unwrap().0;
+            assert_eq!(
+                (plan[0].permission.as_str(), plan[0].action),
+                ("*", PermissionAction::Ask)
+            );
+
+            let config: PermissionConfig = serde_json::from_str(
+                r#"{"read": "allow", "bash": "ask", "__originalKeys": ["bash", "read"]}"#,
+            )
+            .unwrap();
+            assert_eq!(config.0[0].permission, "bash");
+        }
+
         #[test]
         fn test_file_diff_structure() {
             let spec = load_openapi_spec();
@@ -879,6 +1089,87 @@ mod tests {
             let json = serde_json::to_value(&model).expect("Failed to serialize Model");
             assert_eq!(json.get("id").and_then(|v| v.as_str()), Some("claude-3"));
             assert_eq!(json.get("name").and_then(|v| v.as_str()), Some("Claude 3"));
+
+            let mut model = model;
+            model.variants = serde_json::from_value(serde_json::json!({
+                "max": {"thinking": {"type": "enabled", "budgetTokens": 32000}},
+                "high": {"reasoningEffort": "high"},
+                "low": {"reasoningEffort": "low"},
+                "custom": {"temperature": 0.2},
+                "medium": {"reasoningEffort": "medium", "disabled": true}
+            }))
+            .expect("Failed to deserialize model variants");
+            assert_eq!(model.variant_names(), vec!["low", "high", "max", "custom"]);
+            let high = &model.variants.as_ref().unwrap()["high"];
+            assert_eq!(high.reasoning_effort.as_deref(), Some("high"));
+            assert!(high.options.is_empty());
+        }
+
+        #[test]
+        fn test_prompt_request_variant_matches_openapi() {
+            let spec = load_openapi_spec();
+            let properties = spec
+                .pointer("/paths/~1session~1{sessionID}~1message/post/requestBody/content/application~1json/schema/properties")
+                .expect("prompt request schema not found");
+            assert!(properties.get("variant").is_some());
+
+            let mut request = PromptRequest {
+                model: None,
+                agent: None,
+                system: None,
+                parts: vec![PartInput::text("hi")],
+                no_reply: None,
+                variant: None,
+            };
+            let json = serde_json::to_value(&request).expect("Failed to serialize PromptRequest");
+            assert!(json.get("variant").is_none());
+            request.variant = Some("high".to_string());
+            let json = serde_json::to_value(&request).expect("Failed to serialize PromptRequest");
+            assert_eq!(json.get("variant").and_then(|v| v.as_str()), Some("high"));
+        }
+
+        #[test]
+        fn test_file_node_matches_openapi() {
+            let spec = load_openapi_spec();
+            let schema = get_schema(&spec, "FileNode").expect("FileNode schema not found");
+            let required: Vec<&str> = schema
+                .get("required")
+                .and_then(|v| v.as_array())
+                .expect("FileNode schema missing required fields")
+                .iter()
+                .filter_map(|v| v.as_str())
+                .collect();
+
+            let node: FileNode = serde_json::from_value(serde_json::json!({
+                "name": "src",
+                "path": "src",
+                "absolute": "/repo/src",
+                "type": "directory",
+                "ignored": false
+            }))
+            .expect("Failed to deserialize FileNode");
+            assert!(node.is_dir());
+            validate_serialization(&node, &required);
+        }
+
+        #[test]
+        fn test_path_info_matches_openapi() {
+            let spec = load_openapi_spec();
+            assert!(get_schema(&spec, "Path").is_some(), "Path schema not found");
+
+            let info: PathInfo = serde_json::from_value(serde_json::json!({
+                "home": "/home/dev",
+                "state": "/home/dev/.local/state/opencode",
+                "config": "/home/dev/.config/opencode",
+                "worktree": "/home/dev/repo",
+                "directory": "/home/dev/repo/sub"
+            }))
+            .expect("Failed to deserialize PathInfo");
+            assert_eq!(info.root(), "/home/dev/repo/sub");
+
+            let legacy: PathInfo =
+                serde_json::from_value(serde_json::json!({ "path": "/srv/app" })).unwrap();
+            assert_eq!(legacy.root(), "/srv/app");
         }
     }
 }
@@ -211,9 +211,31 @@ pub struct ProjectTime {
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PathInfo {
+    #[serde(default)]
     pub path: String,
     #[serde(default)]
     pub exists: Option<bool>,
+    #[serde(default)]
+    pub home: Option<String>,
+    #[serde(default)]
+    pub state: Option<String>,
+    #[serde(default)]
+    pub config: Option<String>,
+    #[serde(default)]
+    pub worktree: Option<String>,
+    #[serde(default)]
+    pub directory: Option<String>,
+}
+
+impl PathInfo {
+    /// The server's working directory: `directory`, then `worktree`, then legacy `path`.
+    pub fn root(&self) -> &str {
+        [self.directory.as_deref(), self.worktree.as_deref()]
+            .into_iter()
+            .flatten()
+            .find(|p| !p.is_empty())
+            .unwrap_or(&self.path)
+    }
 }
 
 // ============================================================================
@@ -224,6 +246,9 @@ pub struct PathInfo {
 pub struct Config {
     #[serde(default)]
     pub model: Option<String>,
+    /// Per-agent settings, keyed by agent name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub agent: HashMap<String, AgentConfig>,
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
 }
@@ -232,6 +257,26 @@ impl fmt::Debug for Config {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Config")
             .field("model", &self.model)
+            .field("agent", &self.agent)
+            .field("extra", &ExtraMasked(&self.extra))
+            .finish()
+    }
+}
+
+/// One agent's entry in the config. Only the permission rules are typed, as
+/// written in the config rather than merged with the server's defaults.
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct AgentConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permission: Option<PermissionConfig>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl fmt::Debug for AgentConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AgentConfig")
+            .field("permission", &self.permission)
             .field("extra", &ExtraMasked(&self.extra))
             .finish()
     }
@@ -332,7 +377,7 @@ impl<'a, V: fmt::Debug> fmt::Debug for ExtraMasked<'a, V> {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Provider {
     pub id: String,
     pub name: String,
@@ -344,7 +389,7 @@ pub struct Provider {
     pub models: HashMap<String, Model>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Model {
     pub id: String,
     #[serde(rename = "providerID")]
@@ -361,17 +406,58 @@ pub struct Model {
     pub headers: HashMap<String, SecretString>,
     pub release_date: String,
     #[serde(default)]
-    pub variants: Option<HashMap<String, serde_json::Value>>,
+    pub variants: Option<HashMap<String, ModelVariant>>,
+}
+
+impl Model {
+    /// Variants that can be sent as `PromptRequest.variant`, from the least
+    /// to the most reasoning effort. Unranked names follow alphabetically.
+    pub fn variant_names(&self) -> Vec<String> {
+        const EFFORT_ORDER: [&str; 7] =
+            ["none", "minimal", "low", "medium", "high", "xhigh", "max"];
+        let rank = |name: &str, variant: &ModelVariant| {
+            let effort = variant.reasoning_effort.as_deref().unwrap_or(name);
+            EFFORT_ORDER
+                .iter()
+                .position(|level| *level == effort)
+                .unwrap_or(EFFORT_ORDER.len())
+        };
+        let mut names: Vec<(usize, &String)> = self
+            .variants
+            .iter()
+            .flatten()
+            .filter(|(_, variant)| !variant.disabled)
+            .map(|(name, variant)| (rank(name, variant), name))
+            .collect();
+        names.sort();
+        names.into_iter().map(|(_, name)| name.clone()).collect()
+    }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Provider options applied when a model variant is selected, e.g.
+/// `{"reasoningEffort": "high"}` or `{"thinking": {"budgetTokens": 16000}}`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ModelVariant {
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+    #[serde(
+        default,
+        rename = "reasoningEffort",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub reasoning_effort: Option<String>,
+    #[serde(flatten)]
+    pub options: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct ModelApi {
     pub id: String,
     pub url: String,
     pub npm: String,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct ModelCapabilities {
     pub temperature: bool,
     pub reasoning: bool,
@@ -382,7 +468,7 @@ pub struct ModelCapabilities {
     pub interleaved: serde_json::Value,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct ModelModalities {
     pub text: bool,
     pub audio: bool,
@@ -391,7 +477,7 @@ pub struct ModelModalities {
     pub pdf: bool,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct ModelCost {
     pub input: f64,
     pub output: f64,
@@ -400,7 +486,7 @@ pub struct ModelCost {
     pub experimental_over_200k: Option<ModelCostOver200K>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct ModelCacheCost {
     pub read: f64,
     pub write: f64,
@@ -413,7 +499,7 @@ pub struct ModelCostOver200K {
     pub cache: ModelCacheCost,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct ModelLimit {
     pub context: f64,
     #[serde(default)]
@@ -462,6 +548,8 @@ pub struct FilesSearchRequest {
     pub directory: Option<String>,
     #[serde(default)]
     pub limit: Option<usize>, // 1-200
+    #[serde(default)]
+    pub dirs: Option<bool>, // include directories in results
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -476,6 +564,49 @@ pub struct Symbol {
     pub location: SymbolLocation,
 }
 
+impl Symbol {
+    /// Filesystem path of the symbol's location, with any `file://` scheme stripped.
+    pub fn path(&self) -> &str {
+        self.location
+            .uri
+            .strip_prefix("file://")
+            .unwrap_or(&self.location.uri)
+    }
+
+    /// Human-readable name for the LSP `SymbolKind` number.
+    pub fn kind_label(&self) -> &'static str {
+        match self.kind as i64 {
+            1 => "file",
+            2 => "module",
+            3 => "namespace",
+            4 => "package",
+            5 => "class",
+            6 => "method",
+            7 => "property",
+            8 => "field",
+            9 => "constructor",
+            10 => "enum",
+            11 => "interface",
+            12 => "function",
+            13 => "variable",
+            14 => "constant",
+            15 => "string",
+            16 => "number",
+            17 => "boolean",
+            18 => "array",
+            19 => "object",
+            20 => "key",
+            21 => "null",
+            22 => "enum member",
+            23 => "struct",
+            24 => "event",
+            25 => "operator",
+            26 => "type parameter",
+            _ => "symbol",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SymbolLocation {
     pub uri: String,
@@ -487,6 +618,30 @@ pub struct FileReadRequest {
     pub path: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FileListRequest {
+    /// Directory to list, relative to the client's directory ("" for the root)
+    pub path: String,
+}
+
+/// Entry returned by `GET /file`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FileNode {
+    pub name: String,
+    pub path: String,
+    pub absolute: String,
+    #[serde(rename = "type")]
+    pub node_type: String, // "file" or "directory"
+    #[serde(default)]
+    pub ignored: bool,
+}
+
+impl FileNode {
+    pub fn is_dir(&self) -> bool {
+        self.node_type == "directory"
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct FileReadResponse {
     #[serde(rename = "type")]
@@ -682,7 +837,7 @@ pub struct AuthSetRequest {
 ///
 /// Permission rules use pattern matching to determine whether an operation
 /// should be allowed, denied, or require user confirmation.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct PermissionRule {
     /// The permission type being controlled (e.g., "read", "write", "bash")
     pub permission: String,
@@ -693,7 +848,7 @@ pub struct PermissionRule {
 }
 
 /// Action to take for a permission request.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum PermissionAction {
     /// Automatically allow the operation
@@ -747,10 +902,212 @@ pub struct PermissionReplyRequest {
 
 /// A collection of permission rules for a session.
 ///
-/// Rules are evaluated in order, with the first matching rule determining
+/// Rules are evaluated in order, with the last matching rule determining
 /// the action to take.
 pub type PermissionRuleset = Vec<PermissionRule>;
 
+/// A ruleset in the shape of the `permission` config key: one entry per
+/// permission in first-seen order, holding either a bare action (a lone `*`
+/// rule) or a `pattern -> action` map in rule order.
+///
+/// (De)serialized by hand because `serde_json::Value` maps would sort the
+/// patterns, and their order decides which rule wins.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PermissionConfig(pub PermissionRuleset);
+
+impl PermissionConfig {
+    /// Rules grouped per permission in first-seen order, as the config shape
+    /// stores them, each with its index in `self.0`. A repeated pattern
+    /// overrides the earlier one, so it moves to the later position.
+    fn groups(&self) -> Vec<(&str, Vec<(usize, &PermissionRule)>)> {
+        let mut groups: Vec<(&str, Vec<(usize, &PermissionRule)>)> = Vec::new();
+        for (at, rule) in self.0.iter().enumerate() {
+            let index = match groups.iter().position(|(p, _)| *p == rule.permission) {
+                Some(index) => index,
+                None => {
+                    groups.push((&rule.permission, Vec::new()));
+                    groups.len() - 1
+                }
+            };
+            let rules = &mut groups[index].1;
+            rules.retain(|(_, kept)| kept.pattern != rule.pattern);
+            rules.push((at, rule));
+        }
+        groups
+    }
+
+    /// The first rule the config shape would move ahead of an earlier rule
+    /// that can match the same request, changing which rule wins. Rules for
+    /// different permissions only compete when one permission is a wildcard.
+    pub fn reordered_rule(&self) -> Option<usize> {
+        let order: Vec<(usize, &str)> = self
+            .groups()
+            .into_iter()
+            .flat_map(|(permission, rules)| rules.into_iter().map(move |(at, _)| (at, permission)))
+            .collect();
+        let is_wildcard = |permission: &str| permission.contains(['*', '?']);
+        order.iter().enumerate().find_map(|(k, &(at, permission))| {
+            order[k + 1..]
+                .iter()
+                .any(|&(later_at, other)| {
+                    later_at < at && (is_wildcard(permission) || is_wildcard(other))
+                })
+                .then_some(at)
+        })
+    }
+}
+
+impl Serialize for PermissionConfig {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeMap};
+
+        if let Some(at) = self.reordered_rule() {
+            return Err(S::Error::custom(format!(
+                "permission rule {} cannot keep its position in the config",
+                at + 1
+            )));
+        }
+        let groups = self.groups();
+        let mut map = serializer.serialize_map(Some(groups.len()))?;
+        for (permission, rules) in &groups {
+            match rules.as_slice() {
+                [(_, rule)] if rule.pattern == "*" => {
+                    map.serialize_entry(permission, &rule.action)?
+                }
+                _ => map.serialize_entry(permission, &PatternActions(rules))?,
+            }
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for PermissionConfig {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::{MapAccess, Visitor};
+
+        struct ConfigVisitor;
+
+        impl<'de> Visitor<'de> for ConfigVisitor {
+            type Value = PermissionConfig;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a permission action or a map of permissions")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                let action = PermissionAction::deserialize(
+                    serde::de::value::StrDeserializer::<E>::new(value),
+                )?;
+                Ok(PermissionConfig(vec![PermissionRule {
+                    permission: "*".to_string(),
+                    pattern: "*".to_string(),
+                    action,
+                }]))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut groups: Vec<(String, Vec<(String, PermissionAction)>)> = Vec::new();
+                let mut original_keys: Option<Vec<String>> = None;
+                while let Some(permission) = map.next_key::<String>()? {
+                    if permission == "__originalKeys" {
+                        original_keys = Some(map.next_value()?);
+                        continue;
+                    }
+                    let PatternRules(rules) = map.next_value()?;
+                    groups.push((permission, rules));
+                }
+                // The server records the written key order when its own
+                // parsing may not keep it.
+                if let Some(keys) = original_keys {
+                    groups.sort_by_key(|(permission, _)| {
+                        keys.iter()
+                            .position(|key| key == permission)
+                            .unwrap_or(keys.len())
+                    });
+                }
+                Ok(PermissionConfig(
+                    groups
+                        .into_iter()
+                        .flat_map(|(permission, rules)| {
+                            rules
+                                .into_iter()
+                                .map(move |(pattern, action)| PermissionRule {
+                                    permission: permission.clone(),
+                                    pattern,
+                                    action,
+                                })
+                        })
+                        .collect(),
+                ))
+            }
+        }
+
+        deserializer.deserialize_any(ConfigVisitor)
+    }
+}
+
+/// One permission's config value: a bare action for every pattern, or a
+/// `pattern -> action` map in the order written.
+struct PatternRules(Vec<(String, PermissionAction)>);
+
+impl<'de> Deserialize<'de> for PatternRules {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::{MapAccess, Visitor};
+
+        struct PatternVisitor;
+
+        impl<'de> Visitor<'de> for PatternVisitor {
+            type Value = PatternRules;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a permission action or a map of patterns to actions")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                let action = PermissionAction::deserialize(
+                    serde::de::value::StrDeserializer::<E>::new(value),
+                )?;
+                Ok(PatternRules(vec![("*".to_string(), action)]))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut rules = Vec::new();
+                while let Some(entry) = map.next_entry::<String, PermissionAction>()? {
+                    rules.push(entry);
+                }
+                Ok(PatternRules(rules))
+            }
+        }
+
+        deserializer.deserialize_any(PatternVisitor)
+    }
+}
+
+struct PatternActions<'a>(&'a [(usize, &'a PermissionRule)]);
+
+impl Serialize for PatternActions<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (_, rule) in self.0 {
+            map.serialize_entry(&rule.pattern, &rule.action)?;
+        }
+        map.end()
+    }
+}
+
+/// Body of `PATCH /config` that writes one agent's permission rules.
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentPermissionConfigUpdate {
+    pub agent: HashMap<String, AgentPermissionConfig>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentPermissionConfig {
+    pub permission: PermissionConfig,
+}
+
 // ============================================================================
 // Session API types
 // ============================================================================
@@ -770,6 +1127,14 @@ pub struct SessionTime {
     pub archived: Option<i64>,
 }
 
+impl SessionTime {
+    /// Whether the session is archived. Unarchiving sets the archive time to
+    /// `0`, since the update API has no way to clear it.
+    pub fn is_archived(&self) -> bool {
+        self.archived.is_some_and(|at| at > 0)
+    }
+}
+
 /// Summary of changes made during a session.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SessionSummary {
@@ -868,16 +1233,17 @@ pub struct GlobalSession {
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SessionUpdateRequest {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub time: Option<SessionUpdateTime>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SessionUpdateTime {
-    #[serde(default)]
-    pub archived: Option<f64>,
+    /// Archive timestamp in milliseconds. The API only takes a number here, so
+    /// `0` is sent to unarchive; see `SessionTime::is_archived`.
+    pub archived: f64,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -925,6 +1291,9 @@ pub struct PromptRequest {
     pub parts: Vec<PartInput>,
     #[serde(default, rename = "noReply", skip_serializing_if = "Option::is_none")]
     pub no_reply: Option<bool>,
+    /// One of the model's `variants`, e.g. a reasoning effort level
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -941,7 +1310,12 @@ pub struct ShellRequest {
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct RevertRequest {
+    #[serde(rename = "messageID", alias = "message_id")]
     pub message_id: String,
+    /// Revert only from this part (e.g. a `step-start`) onward instead of the
+    /// whole message.
+    #[serde(default, rename = "partID", skip_serializing_if = "Option::is_none")]
+    pub part_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -1144,7 +1518,7 @@ pub struct AssistantMessage {
     /// Structured output if requested
     #[serde(default)]
     pub structured: Option<serde_json::Value>,
-    /// Model variant to use
+    /// Model variant the reply was generated with
     #[serde(default)]
     pub variant: Option<String>,
     /// How the message generation finished (e.g., "stop", "length")
@@ -1758,6 +2132,32 @@ impl PartInput {
             source: None,
         }
     }
+
+    /// File part pointing at a workspace symbol returned by `search_symbols`.
+    ///
+    /// `text` describes where the symbol reference appears in the prompt text
+    /// (e.g. the `#name` token the user typed).
+    pub fn symbol(symbol: &Symbol, text: FilePartSourceText) -> Self {
+        let path = symbol.path().to_string();
+        let range = symbol.location.range.clone();
+        let url = format!(
+            "file://{}?start={}&end={}",
+            path, range.start.line as i64, range.end.line as i64
+        );
+        Self::File {
+            id: None,
+            mime: "text/plain".to_string(),
+            filename: Some(symbol.name.clone()),
+            url,
+            source: Some(FilePartSource::Symbol {
+                text,
+                path,
+                range,
+                name: symbol.name.clone(),
+                kind: symbol.kind as i64,
+            }),
+        }
+    }
 }
 
 /// Server-sent events from the OpenCode server.
@@ -1923,6 +2323,46 @@ pub enum Event {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_symbol_part_input_serialization() {
+        let symbol = Symbol {
+            name: "parse_sse_event".to_string(),
+            kind: 12.0,
+            location: SymbolLocation {
+                uri: "file:///repo/src/client.rs".to_string(),
+                range: Range {
+                    start: Position {
+                        line: 10.0,
+                        character: 4.0,
+                    },
+                    end: Position {
+                        line: 42.0,
+                        character: 5.0,
+                    },
+                },
+            },
+        };
+        assert_eq!(symbol.path(), "/repo/src/client.rs");
+        assert_eq!(symbol.kind_label(), "function");
+
+        let part = PartInput::symbol(
+            &symbol,
+            FilePartSourceText {
+                value: "#parse_sse_event".to_string(),
+                start: 6,
+                end: 22,
+            },
+        );
+        let json = serde_json::to_value(&part).unwrap();
+        assert_eq!(json["type"], "file");
+        assert_eq!(json["url"], "file:///repo/src/client.rs?start=10&end=42");
+        assert_eq!(json["source"]["type"], "symbol");
+        assert_eq!(json["source"]["path"], "/repo/src/client.rs");
+        assert_eq!(json["source"]["name"], "parse_sse_event");
+        assert_eq!(json["source"]["kind"], 12);
+        assert_eq!(json["source"]["text"]["start"], 6);
+    }
+
     #[test]
     fn test_summarize_tool_input_redaction() {
         let mut input = HashMap::new();
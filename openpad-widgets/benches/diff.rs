@@ -0,0 +1,61 @@
+//! Line diff timings on generated files with scattered edits.
+//!
+//! Run with `cargo bench -p openpad-widgets --bench diff`. For reference, the
+//! LCS table this replaced took 5.7 ms at 1,000 lines, and above 2,000 lines it
+//! gave up and reported the whole middle of the file as changed (3,883
+//! changed lines instead of 43 at 2,000 lines). The Myers diff stays exact,
+//! at about 0.2 ms for 3,000 lines and 0.9 ms for 10,000 lines in a release
+//! build.
+
+use openpad_widgets::diff_model::{diff_ops, unified_diff, DiffOp};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const RUNS: u32 = 20;
+
+fn generated_file(lines: usize) -> (String, String) {
+    let before: Vec<String> = (0..lines)
+        .map(|k| format!("    let value_{} = compute({});", k, k))
+        .collect();
+    let mut after = before.clone();
+    for k in (0..lines).step_by(97) {
+        after[k] = format!("    // edited {}", k);
+    }
+    after.insert(lines / 2, "    new_line();".to_string());
+    (before.join("\n"), after.join("\n"))
+}
+
+fn average(mut run: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        run();
+    }
+    start.elapsed() / RUNS
+}
+
+fn main() {
+    println!(
+        "{:>7}  {:>12}  {:>12}  {:>8}",
+        "lines", "diff_ops", "unified", "changed"
+    );
+    for lines in [300, 1_000, 3_000, 10_000, 30_000] {
+        let (before, after) = generated_file(lines);
+        let old: Vec<&str> = before.lines().collect();
+        let new: Vec<&str> = after.lines().collect();
+
+        let changed = diff_ops(&old, &new)
+            .iter()
+            .filter(|op| !matches!(op, DiffOp::Equal(_)))
+            .count();
+        let ops_time = average(|| {
+            black_box(diff_ops(black_box(&old), black_box(&new)));
+        });
+        let unified_time = average(|| {
+            black_box(unified_diff(black_box(&before), black_box(&after), 3));
+        });
+        println!(
+            "{:>7}  {:>12?}  {:>12?}  {:>8}",
+            lines, ops_time, unified_time, changed
+        );
+    }
+}
@@ -1,15 +1,38 @@
+use crate::diff_model::{
+    build_rows, context_fold_start, expand_hunk_gap, hunk_gap, parse_unified, DiffLine,
+    DiffLineKind, DiffMode, DiffRow,
+};
 use makepad_widgets::*;
+use std::collections::HashSet;
 
 const DIFF_COLOR_ADD: Vec4 = vec4(0.301, 0.792, 0.301, 1.0);
 const DIFF_COLOR_DEL: Vec4 = vec4(0.878, 0.376, 0.376, 1.0);
 const DIFF_COLOR_CONTEXT: Vec4 = vec4(0.733, 0.757, 0.788, 1.0);
 const DIFF_COLOR_HEADER: Vec4 = vec4(0.533, 0.690, 0.859, 1.0);
+const DIFF_COLOR_LINE_NUMBER: Vec4 = vec4(0.420, 0.451, 0.502, 1.0);
+
+const DIFF_BG_ADD: Vec4 = vec4(0.301, 0.792, 0.301, 0.10);
+const DIFF_BG_DEL: Vec4 = vec4(0.878, 0.376, 0.376, 0.10);
+const DIFF_BG_EMPTY: Vec4 = vec4(0.0, 0.0, 0.0, 0.25);
+const DIFF_BG_NONE: Vec4 = vec4(0.0, 0.0, 0.0, 0.0);
+const DIFF_BG_FOLD: Vec4 = vec4(0.533, 0.690, 0.859, 0.08);
+const DIFF_BG_ADD_STRONG: Vec4 = vec4(0.301, 0.792, 0.301, 0.35);
+const DIFF_BG_DEL_STRONG: Vec4 = vec4(0.878, 0.376, 0.376, 0.35);
+
+/// Space between the old and new columns in split mode.
+const SPLIT_COLUMN_GAP: f64 = 12.0;
+/// Width reserved for line numbers in split mode.
+const LINE_NUMBER_WIDTH: f64 = 36.0;
 
 script_mod! {
     use mod.prelude.widgets_internal.*
     use mod.widgets.*
     use mod.theme.*
 
+    set_type_default() do #(DrawDiffCell::script_shader(vm)){
+        ..mod.draw.DrawQuad
+    }
+
     mod.widgets.ColoredDiffText = #(ColoredDiffText::register_widget(vm)) {
         width: Fill
         height: Fit
@@ -20,9 +43,30 @@ script_mod! {
                 return self.color
             }
         }
+
+        draw_cell +: {
+            pixel: fn() {
+                return self.color
+            }
+        }
+
+        draw_emphasis +: {
+            pixel: fn() {
+                return self.color
+            }
+        }
     }
 }
 
+#[derive(Script, ScriptHook)]
+#[repr(C)]
+struct DrawDiffCell {
+    #[deref]
+    draw_super: DrawQuad,
+    #[live]
+    color: Vec4,
+}
+
 #[derive(Script, ScriptHook, Widget)]
 pub struct ColoredDiffText {
     #[source]
@@ -33,33 +77,82 @@ pub struct ColoredDiffText {
 
     #[live]
     draw_text: DrawText,
+    #[live]
+    draw_cell: DrawDiffCell,
+    /// Changed spans inside a line; separate from `draw_cell` because spans
+    /// are drawn while a cell is still open.
+    #[live]
+    draw_emphasis: DrawDiffCell,
 
+    #[rust]
+    mode: DiffMode,
     #[rust]
     lines: Vec<DiffLine>,
+    /// Per line: the unified display range in `normalized_text` and the byte
+    /// offset where the text after the `+`/`-`/`·` marker starts.
+    #[rust]
+    spans: Vec<LineSpan>,
+    #[rust]
+    line_numbers: Vec<(String, String)>,
+    #[rust]
+    rows: Vec<DiffRow>,
+    #[rust]
+    fold_labels: Vec<String>,
+    /// Fold starts the user has expanded; reset when the text changes.
+    #[rust]
+    expanded: HashSet<usize>,
+    /// Old text of each `... file` section, for filling in the lines skipped
+    /// between hunks; without it hunk headers are plain lines.
+    #[rust]
+    sources: Vec<String>,
+    /// Fold and gap rows drawn last frame, for click hit-testing.
+    #[rust]
+    fold_areas: Vec<(Area, DiffRow)>,
     #[rust]
     cached_text: String,
     #[rust]
     normalized_text: String,
 }
 
-#[derive(Clone)]
-struct DiffLine {
+#[derive(Clone, Copy)]
+struct LineSpan {
     start: usize,
+    body: usize,
     end: usize,
-    line_type: DiffLineType,
 }
 
-#[derive(Clone, Copy, PartialEq)]
-enum DiffLineType {
-    Addition,
-    Deletion,
-    Context,
-    Header,
+fn text_color(kind: DiffLineKind) -> Vec4 {
+    match kind {
+        DiffLineKind::Added => DIFF_COLOR_ADD,
+        DiffLineKind::Removed => DIFF_COLOR_DEL,
+        DiffLineKind::Context => DIFF_COLOR_CONTEXT,
+        DiffLineKind::Header => DIFF_COLOR_HEADER,
+    }
 }
 
 impl Widget for ColoredDiffText {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
         self.view.handle_event(cx, event, scope);
+
+        if let Event::MouseDown(mouse) = event {
+            let clicked_fold = self
+                .fold_areas
+                .iter()
+                .find(|(area, _)| area.rect(cx).contains(mouse.abs))
+                .map(|(_, row)| *row);
+            match clicked_fold {
+                Some(DiffRow::Fold { start, .. }) => {
+                    self.expanded.insert(start);
+                    self.rebuild_rows();
+                    self.view.redraw(cx);
+                }
+                Some(DiffRow::Gap { header, .. }) => {
+                    self.expand_gap(header);
+                    self.view.redraw(cx);
+                }
+                _ => {}
+            }
+        }
     }
 
     fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
@@ -71,74 +164,287 @@ impl Widget for ColoredDiffText {
             },
         );
 
-        for line in &self.lines {
-            let text_color = match line.line_type {
-                DiffLineType::Addition => DIFF_COLOR_ADD,
-                DiffLineType::Deletion => DIFF_COLOR_DEL,
-                DiffLineType::Context => DIFF_COLOR_CONTEXT,
-                DiffLineType::Header => DIFF_COLOR_HEADER,
-            };
+        self.fold_areas.clear();
+        let column_width = ((cx.turtle().unused_inner_width() - SPLIT_COLUMN_GAP) / 2.0).max(0.0);
+        let mut fold_index = 0;
+        for row_index in 0..self.rows.len() {
+            match self.rows[row_index] {
+                DiffRow::Line(index) => {
+                    let from = self.spans[index].start;
+                    self.draw_line_text(cx, index, from);
+                }
+                DiffRow::Split { left, right } => {
+                    cx.begin_turtle(
+                        Walk::new(Size::fill(), Size::fit()),
+                        Layout {
+                            flow: Flow::Right,
+                            spacing: SPLIT_COLUMN_GAP,
+                            ..Layout::default()
+                        },
+                    );
+                    self.draw_split_cell(cx, left, column_width, true);
+                    self.draw_split_cell(cx, right, column_width, false);
+                    cx.end_turtle();
+                }
+                row @ (DiffRow::Fold { .. } | DiffRow::Gap { .. }) => {
+                    self.draw_cell.color = DIFF_BG_FOLD;
+                    self.draw_cell.begin(
+                        cx,
+                        Walk::new(Size::fill(), Size::fit()),
+                        Layout::default(),
+                    );
+                    self.draw_text.color = DIFF_COLOR_HEADER;
+                    let label = &self.fold_labels[fold_index];
+                    self.draw_text
+                        .draw_walk(cx, Walk::fit(), Align::default(), label);
+                    self.draw_cell.end(cx);
+                    self.fold_areas.push((self.draw_cell.area(), row));
+                    fold_index += 1;
+                }
+            }
+        }
+
+        cx.end_turtle();
+        DrawStep::done()
+    }
+}
 
-            self.draw_text.color = text_color;
+impl ColoredDiffText {
+    /// Draws line `index` of `normalized_text` from byte `from` to its end,
+    /// putting the spans that differ from its paired line on a stronger background.
+    fn draw_line_text(&mut self, cx: &mut Cx2d, index: usize, from: usize) {
+        let line = &self.lines[index];
+        let span = self.spans[index];
+        self.draw_text.color = text_color(line.kind);
+        if line.emphasis.is_empty() {
             // Optimization: use a string slice from the persistent normalized_text buffer.
             // This avoids creating new String objects per line during the draw loop.
-            let text = &self.normalized_text[line.start..line.end];
+            let text = &self.normalized_text[from..span.end];
             self.draw_text
                 .draw_walk(cx, Walk::fit(), Align::default(), text);
+            return;
         }
 
+        self.draw_emphasis.color = if line.kind == DiffLineKind::Added {
+            DIFF_BG_ADD_STRONG
+        } else {
+            DIFF_BG_DEL_STRONG
+        };
+        cx.begin_turtle(
+            Walk::fit(),
+            Layout {
+                flow: Flow::Right,
+                ..Layout::default()
+            },
+        );
+        let mut at = from;
+        for &(start, end) in &line.emphasis {
+            let (start, end) = (span.body + start, span.body + end);
+            if at < start {
+                let text = &self.normalized_text[at..start];
+                self.draw_text
+                    .draw_walk(cx, Walk::fit(), Align::default(), text);
+            }
+            self.draw_emphasis.begin(cx, Walk::fit(), Layout::default());
+            let text = &self.normalized_text[start..end];
+            self.draw_text
+                .draw_walk(cx, Walk::fit(), Align::default(), text);
+            self.draw_emphasis.end(cx);
+            at = end;
+        }
+        if at < span.end {
+            let text = &self.normalized_text[at..span.end];
+            self.draw_text
+                .draw_walk(cx, Walk::fit(), Align::default(), text);
+        }
         cx.end_turtle();
-        DrawStep::done()
     }
-}
 
-impl ColoredDiffText {
+    /// One side of a split row. Cells have a fixed width and clip their text,
+    /// so long lines never spill into the other column.
+    fn draw_split_cell(&mut self, cx: &mut Cx2d, index: Option<usize>, width: f64, old: bool) {
+        let kind = index.map(|index| self.lines[index].kind);
+        self.draw_cell.color = match kind {
+            Some(DiffLineKind::Added) => DIFF_BG_ADD,
+            Some(DiffLineKind::Removed) => DIFF_BG_DEL,
+            Some(_) => DIFF_BG_NONE,
+            None => DIFF_BG_EMPTY,
+        };
+        self.draw_cell.begin(
+            cx,
+            Walk::new(Size::Fixed(width), Size::fit()),
+            Layout {
+                flow: Flow::Right,
+                ..Layout::default()
+            },
+        );
+        match (index, kind) {
+            (Some(index), Some(_)) => {
+                let (old_number, new_number) = &self.line_numbers[index];
+                self.draw_text.color = DIFF_COLOR_LINE_NUMBER;
+                self.draw_text.draw_walk(
+                    cx,
+                    Walk::new(Size::Fixed(LINE_NUMBER_WIDTH), Size::fit()),
+                    Align::default(),
+                    if old { old_number } else { new_number },
+                );
+                let from = self.spans[index].body;
+                self.draw_line_text(cx, index, from);
+            }
+            _ => {
+                // Keep the empty side as tall as a text line.
+                self.draw_text
+                    .draw_walk(cx, Walk::fit(), Align::default(), " ");
+            }
+        }
+        self.draw_cell.end(cx);
+    }
+
+    fn rebuild_rows(&mut self) {
+        self.rows = build_rows(
+            &self.lines,
+            self.mode,
+            &self.expanded,
+            !self.sources.is_empty(),
+        );
+        let hidden_label = |hidden: usize| {
+            format!(
+                "⋯ {} unchanged line{} (click to expand)",
+                hidden,
+                if hidden == 1 { "" } else { "s" }
+            )
+        };
+        self.fold_labels = self
+            .rows
+            .iter()
+            .filter_map(|row| match *row {
+                DiffRow::Fold { hidden, .. } => Some(hidden_label(hidden)),
+                DiffRow::Gap { header, hidden } => Some(format!(
+                    "{}  {}",
+                    self.lines[header].text,
+                    hidden_label(hidden)
+                )),
+                _ => None,
+            })
+            .collect();
+    }
+
+    /// Replaces the hunk header at `header` with the unchanged lines it
+    /// skipped, leaving them unfolded.
+    fn expand_gap(&mut self, header: usize) {
+        let Some(gap) = hunk_gap(&self.lines, header) else {
+            return;
+        };
+        let Some(source) = self.sources.get(gap.file) else {
+            return;
+        };
+        let Some(inserted) = expand_hunk_gap(&mut self.lines, header, source) else {
+            return;
+        };
+
+        // Spans index into `normalized_text`, so the new lines' text can go
+        // at its end.
+        let mut spans = Vec::with_capacity(inserted);
+        let mut numbers = Vec::with_capacity(inserted);
+        for line in &self.lines[header..header + inserted] {
+            let start = self.normalized_text.len();
+            self.normalized_text.push('·');
+            let body = self.normalized_text.len();
+            self.normalized_text.push_str(&line.text);
+            let end = self.normalized_text.len();
+            spans.push(LineSpan { start, body, end });
+            numbers.push((
+                line.old_no.map(|n| n.to_string()).unwrap_or_default(),
+                line.new_no.map(|n| n.to_string()).unwrap_or_default(),
+            ));
+        }
+        self.spans.splice(header..=header, spans);
+        self.line_numbers.splice(header..=header, numbers);
+
+        let shift = inserted - 1;
+        self.expanded = self
+            .expanded
+            .iter()
+            .map(|&start| if start > header { start + shift } else { start })
+            .collect();
+        self.expanded
+            .insert(context_fold_start(&self.lines, header));
+        self.rebuild_rows();
+    }
+
+    /// Sets the old text of each file section of the diff, in order, so the
+    /// lines skipped between hunks can be expanded.
+    pub fn set_sources(&mut self, cx: &mut Cx, sources: &[&str]) {
+        if self
+            .sources
+            .iter()
+            .map(String::as_str)
+            .eq(sources.iter().copied())
+        {
+            return;
+        }
+        self.sources = sources.iter().map(|source| source.to_string()).collect();
+        self.rebuild_rows();
+        self.view.redraw(cx);
+    }
+
+    pub fn set_mode(&mut self, cx: &mut Cx, mode: DiffMode) {
+        if self.mode == mode {
+            return;
+        }
+        self.mode = mode;
+        self.rebuild_rows();
+        self.view.redraw(cx);
+    }
+
     pub fn set_diff_text(&mut self, cx: &mut Cx, text: &str) {
         if self.cached_text == text {
             return;
         }
         // Optimization: avoid repeated allocation by clearing and reusing persistent buffers.
-        // We reuse cached_text, normalized_text, and lines Vec to reduce heap churn.
+        // We reuse cached_text, normalized_text, and spans Vec to reduce heap churn.
         self.cached_text.clear();
         self.cached_text.push_str(text);
 
+        self.lines = parse_unified(text);
         self.normalized_text.clear();
-        self.lines.clear();
-
-        for line in text.lines() {
-            let line_type = if line.starts_with('+') {
-                DiffLineType::Addition
-            } else if line.starts_with('-') {
-                DiffLineType::Deletion
-            } else if line.starts_with("──") || line.starts_with("...") || line.starts_with("@@")
-            {
-                DiffLineType::Header
-            } else {
-                DiffLineType::Context
-            };
-
+        self.spans.clear();
+        for (line, raw) in self.lines.iter().zip(text.lines()) {
             let start = self.normalized_text.len();
-            if let Some(stripped) = line.strip_prefix(' ') {
-                self.normalized_text.push('·');
-                self.normalized_text.push_str(stripped);
-            } else {
-                self.normalized_text.push_str(line);
+            let body = match line.kind {
+                DiffLineKind::Header => start,
+                DiffLineKind::Context if raw.starts_with(' ') => {
+                    self.normalized_text.push('·');
+                    self.normalized_text.len()
+                }
+                DiffLineKind::Context => start,
+                DiffLineKind::Added | DiffLineKind::Removed => {
+                    self.normalized_text.push_str(&raw[..1]);
+                    self.normalized_text.len()
+                }
             };
+            self.normalized_text.push_str(&line.text);
             let end = self.normalized_text.len();
-
-            self.lines.push(DiffLine {
-                start,
-                end,
-                line_type,
-            });
+            self.spans.push(LineSpan { start, body, end });
         }
 
+        let number = |n: Option<usize>| n.map(|n| n.to_string()).unwrap_or_default();
+        self.line_numbers = self
+            .lines
+            .iter()
+            .map(|line| (number(line.old_no), number(line.new_no)))
+            .collect();
+
+        self.expanded.clear();
+        self.rebuild_rows();
         self.view.redraw(cx);
     }
 }
 
 pub trait ColoredDiffTextApi {
     fn set_diff_text(&self, cx: &mut Cx, text: &str);
+    fn set_sources(&self, cx: &mut Cx, sources: &[&str]);
+    fn set_mode(&self, cx: &mut Cx, mode: DiffMode);
 }
 
 impl ColoredDiffTextApi for ColoredDiffTextRef {
@@ -147,4 +453,16 @@ impl ColoredDiffTextApi for ColoredDiffTextRef {
             inner.set_diff_text(cx, text);
         }
     }
+
+    fn set_sources(&self, cx: &mut Cx, sources: &[&str]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_sources(cx, sources);
+        }
+    }
+
+    fn set_mode(&self, cx: &mut Cx, mode: DiffMode) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_mode(cx, mode);
+        }
+    }
 }
@@ -0,0 +1,1055 @@
+//! Line diffs for the diff views: a Myers line diff and its unified text, and
+//! the model behind `ColoredDiffText`, which parses that text into numbered
+//! lines laid out as unified or side-by-side rows with unchanged runs folded
+//! and the gaps between hunks expandable from the old file.
+
+use std::collections::HashSet;
+
+/// Unchanged lines kept visible around a change before the rest is folded.
+pub const FOLD_CONTEXT: usize = 3;
+
+/// How a diff is laid out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DiffMode {
+    /// One column, removed lines above added lines
+    #[default]
+    Unified,
+    /// Old text on the left, new text on the right
+    Split,
+}
+
+impl DiffMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Unified => "unified",
+            Self::Split => "split",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "unified" => Some(Self::Unified),
+            "split" => Some(Self::Split),
+            _ => None,
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Unified => Self::Split,
+            Self::Split => Self::Unified,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffLineKind {
+    Added,
+    Removed,
+    Context,
+    /// File headers, `@@` hunk headers and `...` separators
+    Header,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    /// Line text without its `+`/`-`/` ` prefix; headers keep their full text
+    pub text: String,
+    /// One-based line number in the old file, when known
+    pub old_no: Option<usize>,
+    /// One-based line number in the new file, when known
+    pub new_no: Option<usize>,
+    /// Byte ranges of `text` that differ from the paired removed/added line
+    pub emphasis: Vec<(usize, usize)>,
+}
+
+/// Byte ranges within one line.
+type Spans = Vec<(usize, usize)>;
+
+/// Longest line, in tokens, that still gets an intra-line diff.
+const MAX_INLINE_TOKENS: usize = 256;
+
+/// Splits a line into words, runs of whitespace and single punctuation
+/// characters, as byte ranges.
+fn tokenize(text: &str) -> Vec<(usize, usize)> {
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            0
+        } else if c.is_whitespace() {
+            1
+        } else {
+            2
+        }
+    };
+    let mut tokens: Vec<(usize, usize)> = Vec::new();
+    let mut last_class = None;
+    for (at, c) in text.char_indices() {
+        let this_class = class(c);
+        match tokens.last_mut() {
+            Some(token) if last_class == Some(this_class) && this_class != 2 => {
+                token.1 = at + c.len_utf8();
+            }
+            _ => tokens.push((at, at + c.len_utf8())),
+        }
+        last_class = Some(this_class);
+    }
+    tokens
+}
+
+/// Changed byte ranges of `old` and `new` from a token-level LCS, or `None`
+/// when the lines share too little for a sub-diff to help.
+fn inline_changes(old: &str, new: &str) -> Option<(Spans, Spans)> {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+    if old_tokens.len() > MAX_INLINE_TOKENS || new_tokens.len() > MAX_INLINE_TOKENS {
+        return None;
+    }
+    let old_words: Vec<&str> = old_tokens.iter().map(|&(s, e)| &old[s..e]).collect();
+    let new_words: Vec<&str> = new_tokens.iter().map(|&(s, e)| &new[s..e]).collect();
+    let (n, m) = (old_tokens.len(), new_tokens.len());
+    let mut lengths = vec![0u16; (n + 1) * (m + 1)];
+    let at = |x: usize, y: usize| x * (m + 1) + y;
+    for x in (0..n).rev() {
+        for y in (0..m).rev() {
+            lengths[at(x, y)] = if old_words[x] == new_words[y] {
+                lengths[at(x + 1, y + 1)] + 1
+            } else {
+                lengths[at(x + 1, y)].max(lengths[at(x, y + 1)])
+            };
+        }
+    }
+
+    let mut old_changed = Vec::new();
+    let mut new_changed = Vec::new();
+    let mut common_bytes = 0;
+    let (mut x, mut y) = (0, 0);
+    while x < n || y < m {
+        if x < n && y < m && old_words[x] == new_words[y] {
+            common_bytes += old_tokens[x].1 - old_tokens[x].0;
+            x += 1;
+            y += 1;
+        } else if y == m || (x < n && lengths[at(x + 1, y)] >= lengths[at(x, y + 1)]) {
+            old_changed.push(old_tokens[x]);
+            x += 1;
+        } else {
+            new_changed.push(new_tokens[y]);
+            y += 1;
+        }
+    }
+    // Mostly rewritten lines are clearer without a patchwork of highlights.
+    if common_bytes * 2 < old.len().max(new.len()) {
+        return None;
+    }
+    Some((merge_ranges(old_changed), merge_ranges(new_changed)))
+}
+
+fn merge_ranges(ranges: Spans) -> Spans {
+    let mut merged: Spans = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if last.1 == range.0 => last.1 = range.1,
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Pairs removed and added lines within each change block, the same way the
+/// split layout does, and records which spans of each pair differ.
+fn mark_inline_changes(lines: &mut [DiffLine]) {
+    let mut i = 0;
+    while i < lines.len() {
+        if !matches!(lines[i].kind, DiffLineKind::Added | DiffLineKind::Removed) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < lines.len()
+            && matches!(lines[i].kind, DiffLineKind::Added | DiffLineKind::Removed)
+        {
+            i += 1;
+        }
+        let (removed, added): (Vec<usize>, Vec<usize>) =
+            (start..i).partition(|&k| lines[k].kind == DiffLineKind::Removed);
+        for (&old, &new) in removed.iter().zip(&added) {
+            if let Some((old_spans, new_spans)) = inline_changes(&lines[old].text, &lines[new].text)
+            {
+                lines[old].emphasis = old_spans;
+                lines[new].emphasis = new_spans;
+            }
+        }
+    }
+}
+
+/// `(old, new)` start lines from a `@@ -a,b +c,d @@` header.
+fn parse_hunk_header(line: &str) -> Option<(usize, usize)> {
+    let mut parts = line.strip_prefix("@@ ")?.split_whitespace();
+    let start = |part: Option<&str>, sign: char| -> Option<usize> {
+        part?.strip_prefix(sign)?.split(',').next()?.parse().ok()
+    };
+    let old = start(parts.next(), '-')?;
+    let new = start(parts.next(), '+')?;
+    Some((old.max(1), new.max(1)))
+}
+
+/// Parses unified diff text into lines. Numbering restarts at each
+/// `... file` header, follows `@@` headers and is unknown after a bare `...`
+/// separator, since the skipped line count is not recorded there. Paired
+/// removed/added lines get their differing spans marked in `emphasis`.
+pub fn parse_unified(text: &str) -> Vec<DiffLine> {
+    let mut lines = Vec::new();
+    let mut next: Option<(usize, usize)> = Some((1, 1));
+    for line in text.lines() {
+        let header = |text: &str| DiffLine {
+            kind: DiffLineKind::Header,
+            text: text.to_string(),
+            old_no: None,
+            new_no: None,
+            emphasis: Vec::new(),
+        };
+        if line.starts_with("@@") {
+            next = parse_hunk_header(line);
+            lines.push(header(line));
+            continue;
+        }
+        if line.starts_with("──") || line.starts_with("...") {
+            next = if line.trim_end() == "..." {
+                None
+            } else {
+                Some((1, 1))
+            };
+            lines.push(header(line));
+            continue;
+        }
+
+        let (kind, body) = if let Some(body) = line.strip_prefix('+') {
+            (DiffLineKind::Added, body)
+        } else if let Some(body) = line.strip_prefix('-') {
+            (DiffLineKind::Removed, body)
+        } else {
+            (
+                DiffLineKind::Context,
+                line.strip_prefix(' ').unwrap_or(line),
+            )
+        };
+        let (old_no, new_no) = match (kind, next) {
+            (_, None) => (None, None),
+            (DiffLineKind::Added, Some((old, new))) => {
+                next = Some((old, new + 1));
+                (None, Some(new))
+            }
+            (DiffLineKind::Removed, Some((old, new))) => {
+                next = Some((old + 1, new));
+                (Some(old), None)
+            }
+            (_, Some((old, new))) => {
+                next = Some((old + 1, new + 1));
+                (Some(old), Some(new))
+            }
+        };
+        lines.push(DiffLine {
+            kind,
+            text: body.to_string(),
+            old_no,
+            new_no,
+            emphasis: Vec::new(),
+        });
+    }
+    mark_inline_changes(&mut lines);
+    lines
+}
+
+/// One drawn row; indices point into the parsed lines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffRow {
+    /// A full-width line: every line in unified mode, headers in split mode
+    Line(usize),
+    /// Split mode: an old line on the left and a new line on the right
+    Split {
+        left: Option<usize>,
+        right: Option<usize>,
+    },
+    /// `hidden` unchanged lines starting at line `start`, expanded on click
+    Fold { start: usize, hidden: usize },
+    /// The `@@` header at line `header`, standing in for the `hidden`
+    /// unchanged lines the diff skipped before that hunk
+    Gap { header: usize, hidden: usize },
+}
+
+/// Where the context run containing line `index` starts folding: after
+/// `FOLD_CONTEXT` lines, or at its first line when it opens a file or hunk.
+pub fn context_fold_start(lines: &[DiffLine], index: usize) -> usize {
+    let mut start = index;
+    while start > 0 && lines[start - 1].kind == DiffLineKind::Context {
+        start -= 1;
+    }
+    if start == 0 || lines[start - 1].kind == DiffLineKind::Header {
+        start
+    } else {
+        start + FOLD_CONTEXT
+    }
+}
+
+/// Unchanged lines a diff skipped before one of its hunks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HunkGap {
+    /// Index of the file among the diff's `... file` sections
+    pub file: usize,
+    /// One-based old and new numbers of the first skipped line
+    pub old_start: usize,
+    pub new_start: usize,
+    pub hidden: usize,
+}
+
+/// The lines skipped between the previous hunk of the same file (or the top
+/// of the file) and the `@@` header at `header`, if there are any.
+pub fn hunk_gap(lines: &[DiffLine], header: usize) -> Option<HunkGap> {
+    let line = lines.get(header)?;
+    if line.kind != DiffLineKind::Header {
+        return None;
+    }
+    let (old_next, _) = parse_hunk_header(&line.text)?;
+    let is_file_header = |line: &DiffLine| {
+        line.kind == DiffLineKind::Header
+            && !line.text.starts_with("@@")
+            && line.text.trim_end() != "..."
+    };
+    let section_start = lines[..header]
+        .iter()
+        .rposition(is_file_header)
+        .map_or(0, |k| k + 1);
+    let earlier = &lines[section_start..header];
+    let old_last = earlier.iter().rev().find_map(|l| l.old_no).unwrap_or(0);
+    let new_last = earlier.iter().rev().find_map(|l| l.new_no).unwrap_or(0);
+    let hidden = old_next.checked_sub(old_last + 1)?;
+    if hidden == 0 {
+        return None;
+    }
+    Some(HunkGap {
+        file: lines[..header]
+            .iter()
+            .filter(|l| is_file_header(l))
+            .count()
+            .saturating_sub(1),
+        old_start: old_last + 1,
+        new_start: new_last + 1,
+        hidden,
+    })
+}
+
+/// Replaces the `@@` header at `header` with the unchanged lines it skipped,
+/// read from `old_source`, the old text of the header's file. Returns how
+/// many lines were inserted.
+pub fn expand_hunk_gap(
+    lines: &mut Vec<DiffLine>,
+    header: usize,
+    old_source: &str,
+) -> Option<usize> {
+    let gap = hunk_gap(lines, header)?;
+    let skipped: Vec<DiffLine> = old_source
+        .lines()
+        .skip(gap.old_start - 1)
+        .take(gap.hidden)
+        .enumerate()
+        .map(|(k, text)| DiffLine {
+            kind: DiffLineKind::Context,
+            text: text.to_string(),
+            old_no: Some(gap.old_start + k),
+            new_no: Some(gap.new_start + k),
+            emphasis: Vec::new(),
+        })
+        .collect();
+    if skipped.len() != gap.hidden {
+        return None;
+    }
+    lines.splice(header..=header, skipped);
+    Some(gap.hidden)
+}
+
+/// Lays `lines` out for `mode`. Unchanged runs longer than the surrounding
+/// context are folded unless their fold start is in `expanded`. With
+/// `expand_gaps`, hunk headers that skip lines become `Gap` rows.
+pub fn build_rows(
+    lines: &[DiffLine],
+    mode: DiffMode,
+    expanded: &HashSet<usize>,
+    expand_gaps: bool,
+) -> Vec<DiffRow> {
+    let context_row = |index: usize| match mode {
+        DiffMode::Unified => DiffRow::Line(index),
+        DiffMode::Split => DiffRow::Split {
+            left: Some(index),
+            right: Some(index),
+        },
+    };
+    let is_header = |index: usize| lines[index].kind == DiffLineKind::Header;
+
+    let mut rows = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        match lines[i].kind {
+            DiffLineKind::Header => {
+                match hunk_gap(lines, i).filter(|_| expand_gaps) {
+                    Some(gap) => rows.push(DiffRow::Gap {
+                        header: i,
+                        hidden: gap.hidden,
+                    }),
+                    None => rows.push(DiffRow::Line(i)),
+                }
+                i += 1;
+            }
+            DiffLineKind::Context => {
+                let start = i;
+                while i < lines.len() && lines[i].kind == DiffLineKind::Context {
+                    i += 1;
+                }
+                let end = i;
+                let trail = if end == lines.len() || is_header(end) {
+                    0
+                } else {
+                    FOLD_CONTEXT
+                };
+                let fold_start = context_fold_start(lines, start);
+                let fold_end = end.saturating_sub(trail).max(fold_start);
+                // A fold row standing in for a single line saves nothing.
+                if fold_end - fold_start > 1 && !expanded.contains(&fold_start) {
+                    rows.extend((start..fold_start).map(context_row));
+                    rows.push(DiffRow::Fold {
+                        start: fold_start,
+                        hidden: fold_end - fold_start,
+                    });
+                    rows.extend((fold_end..end).map(context_row));
+                } else {
+                    rows.extend((start..end).map(context_row));
+                }
+            }
+            DiffLineKind::Added | DiffLineKind::Removed => {
+                let start = i;
+                while i < lines.len()
+                    && matches!(lines[i].kind, DiffLineKind::Added | DiffLineKind::Removed)
+                {
+                    i += 1;
+                }
+                match mode {
+                    DiffMode::Unified => rows.extend((start..i).map(DiffRow::Line)),
+                    DiffMode::Split => {
+                        let (removed, added): (Vec<usize>, Vec<usize>) =
+                            (start..i).partition(|&k| lines[k].kind == DiffLineKind::Removed);
+                        for k in 0..removed.len().max(added.len()) {
+                            rows.push(DiffRow::Split {
+                                left: removed.get(k).copied(),
+                                right: added.get(k).copied(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    rows
+}
+
+/// One step of a line diff.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffOp<'a> {
+    Equal(&'a str),
+    Delete(&'a str),
+    Insert(&'a str),
+}
+
+/// Furthest x reached on each diagonal `k = x - y`, indexable by negative `k`.
+struct Frontier {
+    offset: isize,
+    x: Vec<isize>,
+}
+
+impl Frontier {
+    fn new(max_d: usize) -> Self {
+        Self {
+            offset: max_d as isize,
+            x: vec![0; 2 * max_d + 1],
+        }
+    }
+}
+
+impl std::ops::Index<isize> for Frontier {
+    type Output = isize;
+
+    fn index(&self, k: isize) -> &isize {
+        &self.x[(k + self.offset) as usize]
+    }
+}
+
+impl std::ops::IndexMut<isize> for Frontier {
+    fn index_mut(&mut self, k: isize) -> &mut isize {
+        &mut self.x[(k + self.offset) as usize]
+    }
+}
+
+fn max_d(old_len: usize, new_len: usize) -> usize {
+    (old_len + new_len).div_ceil(2) + 1
+}
+
+/// Start of the middle snake of an optimal edit script, searching forward
+/// from the start and backward from the end until the two meet.
+fn find_middle_snake(
+    old: &[&str],
+    new: &[&str],
+    forward: &mut Frontier,
+    backward: &mut Frontier,
+) -> Option<(usize, usize)> {
+    let n = old.len() as isize;
+    let m = new.len() as isize;
+    let delta = n - m;
+    let odd = delta & 1 == 1;
+    forward[1] = 0;
+    backward[1] = 0;
+
+    for d in 0..max_d(old.len(), new.len()) as isize {
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && forward[k - 1] < forward[k + 1]) {
+                forward[k + 1]
+            } else {
+                forward[k - 1] + 1
+            };
+            let mut y = x - k;
+            let (x0, y0) = (x, y);
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            forward[k] = x;
+            if odd && (k - delta).abs() < d && forward[k] + backward[-(k - delta)] >= n {
+                return Some((x0 as usize, y0 as usize));
+            }
+        }
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && backward[k - 1] < backward[k + 1]) {
+                backward[k + 1]
+            } else {
+                backward[k - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[(n - x - 1) as usize] == new[(m - y - 1) as usize] {
+                x += 1;
+                y += 1;
+            }
+            backward[k] = x;
+            if !odd && (k - delta).abs() <= d && backward[k] + forward[-(k - delta)] >= n {
+                return Some(((n - x) as usize, (m - y) as usize));
+            }
+        }
+    }
+    None
+}
+
+fn conquer<'a>(
+    old: &[&'a str],
+    new: &[&'a str],
+    forward: &mut Frontier,
+    backward: &mut Frontier,
+    ops: &mut Vec<DiffOp<'a>>,
+) {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    ops.extend(old[..prefix].iter().map(|line| DiffOp::Equal(line)));
+    let (old, new) = (&old[prefix..], &new[prefix..]);
+    let suffix = old
+        .iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[..old.len() - suffix];
+    let new_mid = &new[..new.len() - suffix];
+
+    if old_mid.is_empty() || new_mid.is_empty() {
+        ops.extend(old_mid.iter().map(|line| DiffOp::Delete(line)));
+        ops.extend(new_mid.iter().map(|line| DiffOp::Insert(line)));
+    } else if let Some((x, y)) = find_middle_snake(old_mid, new_mid, forward, backward) {
+        conquer(&old_mid[..x], &new_mid[..y], forward, backward, ops);
+        conquer(&old_mid[x..], &new_mid[y..], forward, backward, ops);
+    } else {
+        ops.extend(old_mid.iter().map(|line| DiffOp::Delete(line)));
+        ops.extend(new_mid.iter().map(|line| DiffOp::Insert(line)));
+    }
+
+    ops.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| DiffOp::Equal(line)),
+    );
+}
+
+/// Minimal line diff using Myers' O(ND) algorithm in its linear-space form,
+/// so a few edits in a large file stay cheap and precise. Within each change
+/// run, deletions are listed before insertions.
+pub fn diff_ops<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffOp<'a>> {
+    let mut forward = Frontier::new(max_d(old.len(), new.len()));
+    let mut backward = Frontier::new(max_d(old.len(), new.len()));
+    let mut ops = Vec::with_capacity(old.len().max(new.len()));
+    conquer(old, new, &mut forward, &mut backward, &mut ops);
+
+    let mut run_start = 0;
+    while run_start < ops.len() {
+        if matches!(ops[run_start], DiffOp::Equal(_)) {
+            run_start += 1;
+            continue;
+        }
+        let run_end = ops[run_start..]
+            .iter()
+            .position(|op| matches!(op, DiffOp::Equal(_)))
+            .map_or(ops.len(), |len| run_start + len);
+        ops[run_start..run_end].sort_by_key(|op| matches!(op, DiffOp::Insert(_)));
+        run_start = run_end;
+    }
+    ops
+}
+
+/// `start,count` of a hunk side, in the form `diff -u` prints it.
+fn hunk_range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, count),
+    }
+}
+
+/// Unified diff of `before` and `after` with `context` unchanged lines
+/// around each change and an `@@ -a,b +c,d @@` header per hunk.
+pub fn unified_diff(before: &str, after: &str, context: usize) -> String {
+    let old_lines: Vec<&str> = before.lines().collect();
+    let new_lines: Vec<&str> = after.lines().collect();
+    let ops = diff_ops(&old_lines, &new_lines);
+    if ops.is_empty() {
+        return String::from(" (no changes)\n");
+    }
+
+    // Old and new line positions before each op.
+    let mut positions = Vec::with_capacity(ops.len() + 1);
+    let (mut old_at, mut new_at) = (0, 0);
+    for op in &ops {
+        positions.push((old_at, new_at));
+        match op {
+            DiffOp::Equal(_) => {
+                old_at += 1;
+                new_at += 1;
+            }
+            DiffOp::Delete(_) => old_at += 1,
+            DiffOp::Insert(_) => new_at += 1,
+        }
+    }
+    positions.push((old_at, new_at));
+
+    let mut output = String::new();
+    let total = ops.len();
+    let mut i = 0;
+    while i < total {
+        if matches!(ops[i], DiffOp::Equal(_)) {
+            i += 1;
+            continue;
+        }
+        let hunk_start = i.saturating_sub(context);
+        // Extend over later changes separated by at most two contexts' worth of lines.
+        let mut hunk_end = i;
+        loop {
+            while hunk_end < total && !matches!(ops[hunk_end], DiffOp::Equal(_)) {
+                hunk_end += 1;
+            }
+            let equal_start = hunk_end;
+            while hunk_end < total && matches!(ops[hunk_end], DiffOp::Equal(_)) {
+                hunk_end += 1;
+            }
+            let equal_count = hunk_end - equal_start;
+            if hunk_end == total || equal_count > context.saturating_mul(2) {
+                hunk_end = equal_start.saturating_add(context).min(hunk_end);
+                break;
+            }
+        }
+
+        let (old_start, new_start) = positions[hunk_start];
+        let (old_end, new_end) = positions[hunk_end];
+        output.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_end - old_start),
+            hunk_range(new_start, new_end - new_start)
+        ));
+        for op in &ops[hunk_start..hunk_end] {
+            let (prefix, line) = match op {
+                DiffOp::Equal(line) => (' ', line),
+                DiffOp::Delete(line) => ('-', line),
+                DiffOp::Insert(line) => ('+', line),
+            };
+            output.push(prefix);
+            output.push_str(line);
+            output.push('\n');
+        }
+        i = hunk_end;
+    }
+    output
+}
+
+/// One file of a git-style patch with its changed line counts.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PatchFile {
+    pub path: String,
+    pub additions: usize,
+    pub deletions: usize,
+    /// The file's hunks, `@@` headers included.
+    pub hunks: String,
+}
+
+/// Splits a `git diff` style patch into files. Hunk lines are only counted
+/// inside `@@` hunks, so `---`/`+++` file headers never count as changes.
+pub fn parse_git_patch(patch: &str) -> Vec<PatchFile> {
+    let mut files: Vec<PatchFile> = Vec::new();
+    let mut in_hunk = false;
+    let mut lines = patch.lines().peekable();
+    while let Some(line) = lines.next() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            let path = rest
+                .split_once(" b/")
+                .map_or(rest, |(_, new)| new)
+                .to_string();
+            files.push(PatchFile {
+                path,
+                ..PatchFile::default()
+            });
+            in_hunk = false;
+            continue;
+        }
+        let starts_file =
+            line.starts_with("--- ") && lines.peek().is_some_and(|next| next.starts_with("+++ "));
+        if starts_file && !in_hunk {
+            let old = line[4..].trim_start_matches("a/");
+            let new_line = lines.next().unwrap_or_default();
+            let new = new_line[4..].trim_start_matches("b/");
+            let path = if new == "/dev/null" { old } else { new };
+            match files.last_mut() {
+                // Headers of a file already opened by `diff --git`.
+                Some(file) if file.hunks.is_empty() => file.path = path.to_string(),
+                _ => files.push(PatchFile {
+                    path: path.to_string(),
+                    ..PatchFile::default()
+                }),
+            }
+            continue;
+        }
+        if line.starts_with("@@") {
+            in_hunk = true;
+        } else if !in_hunk || line.starts_with('\\') {
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+        if line.starts_with('+') {
+            file.additions += 1;
+        } else if line.starts_with('-') {
+            file.deletions += 1;
+        }
+        file.hunks.push_str(line);
+        file.hunks.push('\n');
+    }
+    files
+}
+
+/// Patch files as one diff text in the `... file (+a -d)` form
+/// [`parse_unified`] numbers per file.
+pub fn patch_display_text(files: &[PatchFile]) -> String {
+    let mut text = String::new();
+    for file in files {
+        text.push_str(&format!(
+            "... {} (+{} -{})\n",
+            file.path, file.additions, file.deletions
+        ));
+        text.push_str(&file.hunks);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_unified_numbers_old_and_new_lines() {
+        let lines = parse_unified("... a.rs (+1 -1)\n keep\n-old\n+new\n tail\n");
+        let numbers: Vec<_> = lines.iter().map(|l| (l.old_no, l.new_no)).collect();
+        assert_eq!(
+            numbers,
+            vec![
+                (None, None),
+                (Some(1), Some(1)),
+                (Some(2), None),
+                (None, Some(2)),
+                (Some(3), Some(3)),
+            ]
+        );
+        assert_eq!(lines[2].text, "old");
+
+        let lines = parse_unified("@@ -10,2 +12,2 @@\n a\n-b\n+c\n...\n d\n");
+        assert_eq!((lines[1].old_no, lines[1].new_no), (Some(10), Some(12)));
+        assert_eq!(lines[3].new_no, Some(13));
+        assert_eq!((lines[5].old_no, lines[5].new_no), (None, None));
+    }
+
+    #[test]
+    fn build_rows_folds_long_unchanged_runs() {
+        let mut text = String::from("... f (+1 -0)\n");
+        for k in 0..10 {
+            text.push_str(&format!(" line{}\n", k));
+        }
+        text.push_str("+added\n");
+        text.push_str(" after\n");
+        let lines = parse_unified(&text);
+
+        let rows = build_rows(&lines, DiffMode::Unified, &HashSet::new(), false);
+        assert_eq!(
+            rows[1],
+            DiffRow::Fold {
+                start: 1,
+                hidden: 7
+            }
+        );
+        assert_eq!(rows.len(), 1 + 1 + 3 + 1 + 1);
+
+        let expanded: HashSet<usize> = [1].into_iter().collect();
+        let rows = build_rows(&lines, DiffMode::Unified, &expanded, false);
+        assert_eq!(rows.len(), lines.len());
+        assert!(!rows.iter().any(|row| matches!(row, DiffRow::Fold { .. })));
+    }
+
+    #[test]
+    fn hunk_gaps_expand_from_the_old_file() {
+        let before: String = (1..=20).map(|n| format!("line {n}\n")).collect();
+        let after = before
+            .replace("line 5\n", "five\n")
+            .replace("line 16\n", "sixteen\n");
+        let text = format!("... f (+2 -2)\n{}", unified_diff(&before, &after, 3));
+        let mut lines = parse_unified(&text);
+
+        let rows = build_rows(&lines, DiffMode::Unified, &HashSet::new(), true);
+        assert_eq!(
+            rows[1],
+            DiffRow::Gap {
+                header: 1,
+                hidden: 1
+            }
+        );
+        let second = lines
+            .iter()
+            .rposition(|l| l.text.starts_with("@@"))
+            .unwrap();
+        assert_eq!(
+            hunk_gap(&lines, second),
+            Some(HunkGap {
+                file: 0,
+                old_start: 9,
+                new_start: 9,
+                hidden: 4
+            })
+        );
+
+        assert_eq!(expand_hunk_gap(&mut lines, second, &before), Some(4));
+        let numbers: Vec<_> = lines.iter().filter_map(|l| l.old_no).collect();
+        assert_eq!(numbers, (2..=19).collect::<Vec<_>>());
+        assert!(lines[second..second + 4]
+            .iter()
+            .all(|l| l.kind == DiffLineKind::Context && l.new_no == l.old_no));
+        assert_eq!(lines[second].text, "line 9");
+    }
+
+    #[test]
+    fn build_rows_pairs_removed_and_added_lines_in_split_mode() {
+        let lines = parse_unified(" a\n-b\n-c\n+B\n d\n");
+        let rows = build_rows(&lines, DiffMode::Split, &HashSet::new(), false);
+        assert_eq!(
+            rows,
+            vec![
+                DiffRow::Split {
+                    left: Some(0),
+                    right: Some(0)
+                },
+                DiffRow::Split {
+                    left: Some(1),
+                    right: Some(3)
+                },
+                DiffRow::Split {
+                    left: Some(2),
+                    right: None
+                },
+                DiffRow::Split {
+                    left: Some(4),
+                    right: Some(4)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn paired_lines_mark_only_the_changed_words() {
+        let lines = parse_unified("-let total = count + 1;\n+let total = count + 2;\n");
+        assert_eq!(lines[0].emphasis, vec![(20, 21)]);
+        assert_eq!(lines[1].emphasis, vec![(20, 21)]);
+
+        let lines = parse_unified("-fn old_name(a: u32) -> u32 {\n+fn new_name(a: u32) -> u64 {\n");
+        let spans = |k: usize| -> Vec<&str> {
+            let line = &lines[k];
+            line.emphasis
+                .iter()
+                .map(|&(s, e)| &line.text[s..e])
+                .collect()
+        };
+        assert_eq!(spans(0), vec!["old_name", "u32"]);
+        assert_eq!(spans(1), vec!["new_name", "u64"]);
+
+        // Unrelated lines and unpaired lines are left whole.
+        let lines = parse_unified("-alpha beta\n+completely different\n+extra\n");
+        assert!(lines.iter().all(|line| line.emphasis.is_empty()));
+    }
+
+    /// Reference LCS length for checking that the Myers script is minimal.
+    fn lcs_len(old: &[&str], new: &[&str]) -> usize {
+        let mut row = vec![0usize; new.len() + 1];
+        for a in old {
+            let mut diagonal = 0;
+            for (j, b) in new.iter().enumerate() {
+                let above = row[j + 1];
+                row[j + 1] = if a == b {
+                    diagonal + 1
+                } else {
+                    row[j + 1].max(row[j])
+                };
+                diagonal = above;
+            }
+        }
+        row[new.len()]
+    }
+
+    #[test]
+    fn diff_ops_is_minimal_and_rebuilds_both_sides() {
+        // Small deterministic pseudo-random inputs over a tiny alphabet, so
+        // that lines repeat and many alignments are possible.
+        let mut seed = 0x2545_f491_u32;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed
+        };
+        let alphabet = ["a", "b", "c", "d"];
+        for _ in 0..300 {
+            let old: Vec<&str> = (0..next() % 12)
+                .map(|_| alphabet[next() as usize % 4])
+                .collect();
+            let new: Vec<&str> = (0..next() % 12)
+                .map(|_| alphabet[next() as usize % 4])
+                .collect();
+            let ops = diff_ops(&old, &new);
+
+            let rebuilt_old: Vec<&str> = ops
+                .iter()
+                .filter_map(|op| match op {
+                    DiffOp::Equal(line) | DiffOp::Delete(line) => Some(*line),
+                    DiffOp::Insert(_) => None,
+                })
+                .collect();
+            let rebuilt_new: Vec<&str> = ops
+                .iter()
+                .filter_map(|op| match op {
+                    DiffOp::Equal(line) | DiffOp::Insert(line) => Some(*line),
+                    DiffOp::Delete(_) => None,
+                })
+                .collect();
+            assert_eq!(rebuilt_old, old);
+            assert_eq!(rebuilt_new, new);
+            let equal = ops
+                .iter()
+                .filter(|op| matches!(op, DiffOp::Equal(_)))
+                .count();
+            assert_eq!(equal, lcs_len(&old, &new), "{:?} -> {:?}", old, new);
+        }
+    }
+
+    #[test]
+    fn diff_ops_stays_precise_on_large_files() {
+        let before: Vec<String> = (0..5000).map(|k| format!("line {}", k)).collect();
+        let mut after = before.clone();
+        after[10] = "changed".to_string();
+        after.remove(2500);
+        after.insert(4000, "inserted".to_string());
+        let old: Vec<&str> = before.iter().map(String::as_str).collect();
+        let new: Vec<&str> = after.iter().map(String::as_str).collect();
+
+        let ops = diff_ops(&old, &new);
+        let changes = ops
+            .iter()
+            .filter(|op| !matches!(op, DiffOp::Equal(_)))
+            .count();
+        assert_eq!(changes, 4);
+    }
+
+    #[test]
+    fn unified_diff_writes_hunk_headers() {
+        let before = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let after = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+        assert_eq!(
+            unified_diff(before, after, 1),
+            "@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n@@ -10 +10,2 @@\n j\n+k\n"
+        );
+        assert_eq!(unified_diff("", "x\n", 3), "@@ -0,0 +1 @@\n+x\n");
+        assert_eq!(unified_diff("", "", 3), " (no changes)\n");
+
+        let lines = parse_unified(&unified_diff(before, after, 1));
+        assert_eq!((lines[6].old_no, lines[6].new_no), (Some(10), Some(10)));
+        assert_eq!(lines[7].new_no, Some(11));
+    }
+
+    #[test]
+    fn diff_mode_round_trips_through_its_name() {
+        for mode in [DiffMode::Unified, DiffMode::Split] {
+            assert_eq!(DiffMode::parse(mode.as_str()), Some(mode));
+        }
+        assert_eq!(DiffMode::Unified.toggled(), DiffMode::Split);
+        assert_eq!(DiffMode::parse("sideways"), None);
+    }
+
+    #[test]
+    fn git_patch_counts_only_hunk_lines() {
+        let patch = "diff --git a/src/a.rs b/src/a.rs\n\
+index 111..222 100644\n\
+--- a/src/a.rs\n\
++++ b/src/a.rs\n\
+@@ -1,3 +1,3 @@\n\
+ keep\n\
+---old comment\n\
++++new comment\n\
+ tail\n\
+\\ No newline at end of file\n\
+diff --git a/gone.txt b/gone.txt\n\
+deleted file mode 100644\n\
+--- a/gone.txt\n\
++++ /dev/null\n\
+@@ -1,2 +0,0 @@\n\
+-one\n\
+-two\n";
+        let files = parse_git_patch(patch);
+        let counts: Vec<_> = files
+            .iter()
+            .map(|f| (f.path.as_str(), f.additions, f.deletions))
+            .collect();
+        assert_eq!(counts, vec![("src/a.rs", 1, 1), ("gone.txt", 0, 2)]);
+
+        let lines = parse_unified(&patch_display_text(&files));
+        assert_eq!(lines[0].text, "... src/a.rs (+1 -1)");
+        let removed = lines
+            .iter()
+            .find(|l| l.kind == DiffLineKind::Removed)
+            .unwrap();
+        assert_eq!(
+            (removed.text.as_str(), removed.old_no),
+            ("--old comment", Some(2))
+        );
+    }
+}
@@ -1,5 +1,39 @@
 use crate::colored_diff_text::{ColoredDiffTextApi, ColoredDiffTextWidgetExt};
+use crate::diff_model::DiffMode;
+use crate::utils::preferences;
 use makepad_widgets::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+const DIFF_MODE_PREFERENCE: &str = "diff_mode";
+
+/// Whether diffs are shown side by side. Shared by every `DiffView` and
+/// loaded from the user's preferences on first use.
+fn split_mode_flag() -> &'static AtomicBool {
+    static SPLIT_MODE: OnceLock<AtomicBool> = OnceLock::new();
+    SPLIT_MODE.get_or_init(|| {
+        let mode = preferences::get(DIFF_MODE_PREFERENCE)
+            .and_then(|value| DiffMode::parse(&value))
+            .unwrap_or_default();
+        AtomicBool::new(mode == DiffMode::Split)
+    })
+}
+
+pub fn diff_mode() -> DiffMode {
+    if split_mode_flag().load(Ordering::Relaxed) {
+        DiffMode::Split
+    } else {
+        DiffMode::Unified
+    }
+}
+
+/// Switches every diff view to `mode` and remembers it for the next launch.
+pub fn set_diff_mode(mode: DiffMode) {
+    split_mode_flag().store(mode == DiffMode::Split, Ordering::Relaxed);
+    if let Err(err) = preferences::set(DIFF_MODE_PREFERENCE, mode.as_str()) {
+        log!("Could not save diff mode preference: {}", err);
+    }
+}
 
 script_mod! {
     use mod.prelude.widgets_internal.*
@@ -60,6 +94,25 @@ script_mod! {
                         text_style: theme.font_bold {font_size: 11}
                     }
                 }
+
+                View { width: Fill height: Fit }
+
+                mode_toggle_button := Button {
+                    width: Fit
+                    height: 24
+                    padding: Inset{left: 8 right: 8}
+                    text: "Split view"
+                    draw_bg +: {
+                        color: #2a2f36
+                        color_hover: #313843
+                        border_radius: 6.0
+                        border_size: 0.0
+                    }
+                    draw_text +: {
+                        color: #aab3bd
+                        text_style: theme.font_regular {font_size: 10}
+                    }
+                }
             }
         }
 
@@ -105,27 +158,59 @@ pub struct DiffView {
     summary_text: String,
     #[rust]
     summary_header_clicked: bool,
+    #[rust]
+    mode: DiffMode,
 }
 
 impl Widget for DiffView {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
-        self.view.handle_event(cx, event, scope);
+        let actions = cx.capture_actions(|cx| {
+            self.view.handle_event(cx, event, scope);
+        });
+
+        let toggle = self.view.button(cx, &[id!(mode_toggle_button)]);
+        if toggle.clicked(&actions) {
+            set_diff_mode(self.mode.toggled());
+            self.sync_mode(cx);
+            // Other diff views pick the new mode up when they draw.
+            cx.redraw_all();
+        }
 
         if let Event::MouseDown(mouse) = event {
             let header = self.view.view(cx, &[id!(summary_header)]);
             let header_rect = header.area().rect(cx);
-            if header_rect.contains(mouse.abs) {
+            if header_rect.contains(mouse.abs) && !toggle.area().rect(cx).contains(mouse.abs) {
                 self.summary_header_clicked = true;
             }
         }
     }
 
     fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.sync_mode(cx);
         self.view.draw_walk(cx, scope, walk)
     }
 }
 
 impl DiffView {
+    /// Follows the shared diff mode; the toggle names the mode it switches to.
+    fn sync_mode(&mut self, cx: &mut Cx) {
+        let mode = diff_mode();
+        if self.mode == mode {
+            return;
+        }
+        self.mode = mode;
+        self.view
+            .colored_diff_text(cx, &[id!(diff_text)])
+            .set_mode(cx, mode);
+        self.view.button(cx, &[id!(mode_toggle_button)]).set_text(
+            cx,
+            match mode {
+                DiffMode::Unified => "Split view",
+                DiffMode::Split => "Unified view",
+            },
+        );
+    }
+
     pub fn set_diff_text(
         &mut self,
         cx: &mut Cx,
@@ -171,6 +256,14 @@ impl DiffView {
         self.redraw(cx);
     }
 
+    /// Old text of each file in the diff, in order; lets the lines skipped
+    /// between hunks be expanded.
+    pub fn set_sources(&mut self, cx: &mut Cx, sources: &[&str]) {
+        self.view
+            .colored_diff_text(cx, &[id!(diff_text)])
+            .set_sources(cx, sources);
+    }
+
     pub fn set_expanded(&mut self, cx: &mut Cx, expanded: bool) {
         self.expanded = expanded;
         self.view
@@ -218,6 +311,7 @@ pub trait DiffViewApi {
         deletions: &str,
         full_diff: &str,
     );
+    fn set_sources(&self, cx: &mut Cx, sources: &[&str]);
     fn clear_diffs(&self, cx: &mut Cx);
     fn set_expanded(&self, cx: &mut Cx, expanded: bool);
     fn summary_header_clicked(&self, cx: &mut Cx) -> bool;
@@ -237,6 +331,12 @@ impl DiffViewApi for DiffViewRef {
         }
     }
 
+    fn set_sources(&self, cx: &mut Cx, sources: &[&str]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_sources(cx, sources);
+        }
+    }
+
     fn clear_diffs(&self, cx: &mut Cx) {
         if let Some(mut inner) = self.borrow_mut() {
             inner.clear_diffs(cx);
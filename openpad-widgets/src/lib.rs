@@ -18,6 +18,7 @@ pub mod upward_dropdown;
 // Generic components moved from openpad-app
 pub mod assistant_bubble;
 pub mod colored_diff_text;
+pub mod diff_model;
 pub mod diff_view;
 pub mod message_list;
 pub mod message_logic;
@@ -62,8 +63,8 @@ pub fn script_mod(vm: &mut ScriptVm) {
 
     crate::user_bubble::script_mod(vm);
     crate::assistant_bubble::script_mod(vm);
-    crate::permission_card::script_mod(vm);
     crate::colored_diff_text::script_mod(vm);
+    crate::permission_card::script_mod(vm);
     crate::diff_view::script_mod(vm);
     crate::terminal::script_mod(vm);
     crate::terminal_panel::script_mod(vm);
@@ -88,22 +88,29 @@ impl MessageList {
                     let steps_base =
                         widget.view(cx, &[id!(steps_expanded), id!(steps_scroll), id!(content)]);
                     for step_id in 0..MessageList::MAX_STEP_ROWS.min(msg.steps.len()) {
-                        let Some(&(row_id, header_id, _, _, _, _)) =
+                        let Some(&(row_id, header_id, _, _, _, _, revert_id)) =
                             MessageList::STEP_ROW.get(step_id)
                         else {
                             continue;
                         };
-                        if steps_base
-                            .view(cx, &[row_id])
-                            .button(cx, &[header_id])
-                            .clicked(&actions)
-                        {
+                        let row = steps_base.view(cx, &[row_id]);
+                        if row.button(cx, &[header_id]).clicked(&actions) {
                             if let Some(step) = self.messages[item_id].steps.get_mut(step_id) {
                                 step.expanded = !step.expanded;
                                 self.redraw(cx);
                             }
                             break;
                         }
+                        if row.button(cx, &[revert_id]).clicked(&actions) {
+                            let step = &self.messages[item_id].steps[step_id];
+                            if let Some(part_id) = &step.part_id {
+                                cx.action(MessageListAction::RevertToStep {
+                                    message_id: step.message_id.clone(),
+                                    part_id: part_id.clone(),
+                                });
+                            }
+                            break;
+                        }
                     }
                 }
             }
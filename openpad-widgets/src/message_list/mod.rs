@@ -1,5 +1,5 @@
 use crate::diff_view::{DiffViewApi, DiffViewWidgetRefExt};
-use crate::message_logic::{DisplayMessage, MessageProcessor};
+use crate::message_logic::{DisplayMessage, MessageProcessor, PermissionPreview};
 use crate::permission_card::{PermissionCardApi, PermissionCardWidgetRefExt};
 use makepad_widgets::*;
 
@@ -446,6 +446,15 @@ script_mod! {
                                             draw_text +: { color: #d2dae8, text_style: theme.font_regular { font_size: 9 } }
                                             text: ""
                                         }
+
+                                        step_row_0_revert := Button {
+                                            visible: false
+                                            width: Fit, height: Fit
+                                            padding: Inset{ left: 6, right: 6, top: 2, bottom: 2 }
+                                            draw_bg +: { color: #0000, color_hover: #333, border_radius: 4.0, border_size: 0.0 }
+                                            draw_text +: { color: #8b95a5, color_hover: #ffffff, text_style: theme.font_regular { font_size: 8 } }
+                                            text: "↺ Revert to here"
+                                        }
                                     }
 
                                     step_row_0_body := View {
@@ -497,6 +506,15 @@ script_mod! {
                                             draw_text +: { color: #d2dae8, text_style: theme.font_regular { font_size: 9 } }
                                             text: ""
                                         }
+
+                                        step_row_1_revert := Button {
+                                            visible: false
+                                            width: Fit, height: Fit
+                                            padding: Inset{ left: 6, right: 6, top: 2, bottom: 2 }
+                                            draw_bg +: { color: #0000, color_hover: #333, border_radius: 4.0, border_size: 0.0 }
+                                            draw_text +: { color: #8b95a5, color_hover: #ffffff, text_style: theme.font_regular { font_size: 8 } }
+                                            text: "↺ Revert to here"
+                                        }
                                     }
 
                                     step_row_1_body := View {
@@ -548,6 +566,15 @@ script_mod! {
                                             draw_text +: { color: #d2dae8, text_style: theme.font_regular { font_size: 9 } }
                                             text: ""
                                         }
+
+                                        step_row_2_revert := Button {
+                                            visible: false
+                                            width: Fit, height: Fit
+                                            padding: Inset{ left: 6, right: 6, top: 2, bottom: 2 }
+                                            draw_bg +: { color: #0000, color_hover: #333, border_radius: 4.0, border_size: 0.0 }
+                                            draw_text +: { color: #8b95a5, color_hover: #ffffff, text_style: theme.font_regular { font_size: 8 } }
+                                            text: "↺ Revert to here"
+                                        }
                                     }
 
                                     step_row_2_body := View {
@@ -599,6 +626,15 @@ script_mod! {
                                             draw_text +: { color: #d2dae8, text_style: theme.font_regular { font_size: 9 } }
                                             text: ""
                                         }
+
+                                        step_row_3_revert := Button {
+                                            visible: false
+                                            width: Fit, height: Fit
+                                            padding: Inset{ left: 6, right: 6, top: 2, bottom: 2 }
+                                            draw_bg +: { color: #0000, color_hover: #333, border_radius: 4.0, border_size: 0.0 }
+                                            draw_text +: { color: #8b95a5, color_hover: #ffffff, text_style: theme.font_regular { font_size: 8 } }
+                                            text: "↺ Revert to here"
+                                        }
                                     }
 
                                     step_row_3_body := View {
@@ -650,6 +686,15 @@ script_mod! {
                                             draw_text +: { color: #d2dae8, text_style: theme.font_regular { font_size: 9 } }
                                             text: ""
                                         }
+
+                                        step_row_4_revert := Button {
+                                            visible: false
+                                            width: Fit, height: Fit
+                                            padding: Inset{ left: 6, right: 6, top: 2, bottom: 2 }
+                                            draw_bg +: { color: #0000, color_hover: #333, border_radius: 4.0, border_size: 0.0 }
+                                            draw_text +: { color: #8b95a5, color_hover: #ffffff, text_style: theme.font_regular { font_size: 8 } }
+                                            text: "↺ Revert to here"
+                                        }
                                     }
 
                                     step_row_4_body := View {
@@ -701,6 +746,15 @@ script_mod! {
                                             draw_text +: { color: #d2dae8, text_style: theme.font_regular { font_size: 9 } }
                                             text: ""
                                         }
+
+                                        step_row_5_revert := Button {
+                                            visible: false
+                                            width: Fit, height: Fit
+                                            padding: Inset{ left: 6, right: 6, top: 2, bottom: 2 }
+                                            draw_bg +: { color: #0000, color_hover: #333, border_radius: 4.0, border_size: 0.0 }
+                                            draw_text +: { color: #8b95a5, color_hover: #ffffff, text_style: theme.font_regular { font_size: 8 } }
+                                            text: "↺ Revert to here"
+                                        }
                                     }
 
                                     step_row_5_body := View {
@@ -752,6 +806,15 @@ script_mod! {
                                             draw_text +: { color: #d2dae8, text_style: theme.font_regular { font_size: 9 } }
                                             text: ""
                                         }
+
+                                        step_row_6_revert := Button {
+                                            visible: false
+                                            width: Fit, height: Fit
+                                            padding: Inset{ left: 6, right: 6, top: 2, bottom: 2 }
+                                            draw_bg +: { color: #0000, color_hover: #333, border_radius: 4.0, border_size: 0.0 }
+                                            draw_text +: { color: #8b95a5, color_hover: #ffffff, text_style: theme.font_regular { font_size: 8 } }
+                                            text: "↺ Revert to here"
+                                        }
                                     }
 
                                     step_row_6_body := View {
@@ -803,6 +866,15 @@ script_mod! {
                                             draw_text +: { color: #d2dae8, text_style: theme.font_regular { font_size: 9 } }
                                             text: ""
                                         }
+
+                                        step_row_7_revert := Button {
+                                            visible: false
+                                            width: Fit, height: Fit
+                                            padding: Inset{ left: 6, right: 6, top: 2, bottom: 2 }
+                                            draw_bg +: { color: #0000, color_hover: #333, border_radius: 4.0, border_size: 0.0 }
+                                            draw_text +: { color: #8b95a5, color_hover: #ffffff, text_style: theme.font_regular { font_size: 8 } }
+                                            text: "↺ Revert to here"
+                                        }
                                     }
 
                                     step_row_7_body := View {
@@ -854,6 +926,15 @@ script_mod! {
                                             draw_text +: { color: #d2dae8, text_style: theme.font_regular { font_size: 9 } }
                                             text: ""
                                         }
+
+                                        step_row_8_revert := Button {
+                                            visible: false
+                                            width: Fit, height: Fit
+                                            padding: Inset{ left: 6, right: 6, top: 2, bottom: 2 }
+                                            draw_bg +: { color: #0000, color_hover: #333, border_radius: 4.0, border_size: 0.0 }
+                                            draw_text +: { color: #8b95a5, color_hover: #ffffff, text_style: theme.font_regular { font_size: 8 } }
+                                            text: "↺ Revert to here"
+                                        }
                                     }
 
                                     step_row_8_body := View {
@@ -905,6 +986,15 @@ script_mod! {
                                             draw_text +: { color: #d2dae8, text_style: theme.font_regular { font_size: 9 } }
                                             text: ""
                                         }
+
+                                        step_row_9_revert := Button {
+                                            visible: false
+                                            width: Fit, height: Fit
+                                            padding: Inset{ left: 6, right: 6, top: 2, bottom: 2 }
+                                            draw_bg +: { color: #0000, color_hover: #333, border_radius: 4.0, border_size: 0.0 }
+                                            draw_text +: { color: #8b95a5, color_hover: #ffffff, text_style: theme.font_regular { font_size: 8 } }
+                                            text: "↺ Revert to here"
+                                        }
                                     }
 
                                     step_row_9_body := View {
@@ -1034,6 +1124,8 @@ pub struct PendingPermissionDisplay {
     pub request_id: String,
     pub permission: String,
     pub patterns: Vec<String>,
+    pub always: Vec<String>,
+    pub preview: PermissionPreview,
 }
 
 #[derive(Script, ScriptHook, Widget)]
@@ -1088,4 +1180,9 @@ pub enum MessageListAction {
     #[default]
     None,
     RevertToMessage(String),
+    /// Revert a single step and everything after it.
+    RevertToStep {
+        message_id: String,
+        part_id: String,
+    },
 }
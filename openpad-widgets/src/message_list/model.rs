@@ -29,8 +29,8 @@ impl MessageList {
     pub(super) const MAX_STEP_ROWS: usize = 10;
 
     /// Pre-computed LiveId tuples for step rows. Eliminates match arms in render/events.
-    /// Order: (row, header, body, content, dot, line)
-    pub(super) const STEP_ROW: [(LiveId, LiveId, LiveId, LiveId, LiveId, LiveId); 10] = [
+    /// Order: (row, header, body, content, dot, line, revert)
+    pub(super) const STEP_ROW: [(LiveId, LiveId, LiveId, LiveId, LiveId, LiveId, LiveId); 10] = [
         (
             live_id!(step_row_0),
             live_id!(step_row_0_header),
@@ -38,6 +38,7 @@ impl MessageList {
             live_id!(step_row_0_content),
             live_id!(step_row_0_dot),
             live_id!(step_row_0_line),
+            live_id!(step_row_0_revert),
         ),
         (
             live_id!(step_row_1),
@@ -46,6 +47,7 @@ impl MessageList {
             live_id!(step_row_1_content),
             live_id!(step_row_1_dot),
             live_id!(step_row_1_line),
+            live_id!(step_row_1_revert),
         ),
         (
             live_id!(step_row_2),
@@ -54,6 +56,7 @@ impl MessageList {
             live_id!(step_row_2_content),
             live_id!(step_row_2_dot),
             live_id!(step_row_2_line),
+            live_id!(step_row_2_revert),
         ),
         (
             live_id!(step_row_3),
@@ -62,6 +65,7 @@ impl MessageList {
             live_id!(step_row_3_content),
             live_id!(step_row_3_dot),
             live_id!(step_row_3_line),
+            live_id!(step_row_3_revert),
         ),
         (
             live_id!(step_row_4),
@@ -70,6 +74,7 @@ impl MessageList {
             live_id!(step_row_4_content),
             live_id!(step_row_4_dot),
             live_id!(step_row_4_line),
+            live_id!(step_row_4_revert),
         ),
         (
             live_id!(step_row_5),
@@ -78,6 +83,7 @@ impl MessageList {
             live_id!(step_row_5_content),
             live_id!(step_row_5_dot),
             live_id!(step_row_5_line),
+            live_id!(step_row_5_revert),
         ),
         (
             live_id!(step_row_6),
@@ -86,6 +92,7 @@ impl MessageList {
             live_id!(step_row_6_content),
             live_id!(step_row_6_dot),
             live_id!(step_row_6_line),
+            live_id!(step_row_6_revert),
         ),
         (
             live_id!(step_row_7),
@@ -94,6 +101,7 @@ impl MessageList {
             live_id!(step_row_7_content),
             live_id!(step_row_7_dot),
             live_id!(step_row_7_line),
+            live_id!(step_row_7_revert),
         ),
         (
             live_id!(step_row_8),
@@ -102,6 +110,7 @@ impl MessageList {
             live_id!(step_row_8_content),
             live_id!(step_row_8_dot),
             live_id!(step_row_8_line),
+            live_id!(step_row_8_revert),
         ),
         (
             live_id!(step_row_9),
@@ -110,6 +119,7 @@ impl MessageList {
             live_id!(step_row_9_content),
             live_id!(step_row_9_dot),
             live_id!(step_row_9_line),
+            live_id!(step_row_9_revert),
         ),
     ];
 
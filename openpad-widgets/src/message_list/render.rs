@@ -37,6 +37,8 @@ impl MessageList {
                             perm.request_id.clone(),
                             &perm.permission,
                             &perm.patterns,
+                            &perm.always,
+                            &perm.preview,
                         );
                         item_widget.draw_all(cx, scope);
                         continue;
@@ -214,10 +216,10 @@ impl MessageList {
                         }
 
                         if msg.role == "assistant" {
-                            if let Some(ref model_id) = msg.model_id {
+                            if msg.model_id.is_some() {
                                 item_widget
                                     .label(cx, &[id!(model_label)])
-                                    .set_text(cx, model_id);
+                                    .set_text(cx, &msg.cached_model_label);
                             }
                             if let Some(error_text) = &msg.error_text {
                                 item_widget
@@ -281,6 +283,7 @@ impl MessageList {
                                         content_id,
                                         dot_id,
                                         line_id,
+                                        revert_id,
                                     )) = Self::STEP_ROW.get(step_id)
                                     else {
                                         continue;
@@ -305,6 +308,14 @@ impl MessageList {
                                             .label(cx, &[content_id])
                                             .set_text(cx, &step.cached_body);
                                         let _ = dot_id;
+                                        let can_revert = step.part_id.is_some()
+                                            && !step.has_running
+                                            && !self.is_working
+                                            && self.revert_message_id.is_none();
+                                        steps_base
+                                            .view(cx, &[row_id])
+                                            .button(cx, &[revert_id])
+                                            .set_visible(cx, can_revert);
                                         let show_line = step_id + 1 < msg.steps.len();
                                         let line_view =
                                             steps_base.view(cx, &[row_id]).view(cx, &[line_id]);
@@ -334,6 +345,9 @@ impl MessageList {
                                     &msg.cached_diff_del,
                                     &msg.cached_full_diff,
                                 );
+                                let sources: Vec<&str> =
+                                    msg.diffs.iter().map(|d| d.before.as_str()).collect();
+                                diff_view.set_sources(cx, &sources);
                             }
                             diff_view.set_expanded(cx, msg.show_diffs);
                         }
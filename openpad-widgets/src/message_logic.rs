@@ -1,13 +1,7 @@
+use crate::diff_model::FOLD_CONTEXT;
 use openpad_protocol::{FileDiff, Message, MessageWithParts, Part, TokenUsage};
 use std::collections::HashMap;
 
-#[derive(Debug)]
-enum DiffOp<'a> {
-    Equal(&'a str),
-    Delete(&'a str),
-    Insert(&'a str),
-}
-
 /// Categories for grouping tools by type
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum ToolCategory {
@@ -83,8 +77,16 @@ pub struct StepDetail {
     pub title: Option<String>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct DisplayStep {
+    /// Message the step came from; one assistant card can span several.
+    pub message_id: String,
+    /// The `step-start` part, i.e. the checkpoint a per-step revert rolls back to.
+    pub part_id: Option<String>,
+    /// Workspace snapshot taken when the step started.
+    pub snapshot: Option<String>,
+    /// Files changed by the step, from its `patch` parts.
+    pub files: Vec<String>,
     pub reason: String,
     pub cost: f64,
     pub tokens: Option<TokenUsage>,
@@ -105,6 +107,8 @@ pub struct DisplayMessage {
     pub message_id: Option<String>,
     pub timestamp: Option<i64>,
     pub model_id: Option<String>,
+    /// Model variant of the latest turn, e.g. a reasoning effort level
+    pub variant: Option<String>,
     pub tokens: Option<TokenUsage>,
     pub cost: Option<f64>,
     pub error_text: Option<String>,
@@ -123,6 +127,7 @@ pub struct DisplayMessage {
     pub cached_timestamp: String,
     pub cached_token_usage: String,
     pub cached_cost: String,
+    pub cached_model_label: String,
     pub cached_full_diff: String,
     pub cached_diff_files: String,
     pub cached_diff_add: String,
@@ -179,6 +184,11 @@ impl MessageProcessor {
                     }
                 };
 
+            let variant = match &mwp.info {
+                Message::Assistant(msg) => msg.variant.clone().filter(|v| !v.is_empty()),
+                Message::User(_) => None,
+            };
+
             let message_id = mwp.info.id().to_string();
             let mut text_parts: Vec<String> = Vec::new();
             let mut steps: Vec<DisplayStep> = Vec::new();
@@ -189,19 +199,12 @@ impl MessageProcessor {
                 } else if let Some((_mime, filename, _url)) = p.file_info() {
                     let name = filename.unwrap_or("attachment");
                     text_parts.push(format!("[Attachment: {}]", name));
-                } else if matches!(p, Part::StepStart { .. }) {
+                } else if let Part::StepStart { id, snapshot, .. } = p {
                     steps.push(DisplayStep {
-                        reason: String::new(),
-                        cost: 0.,
-                        tokens: None,
-                        details: Vec::new(),
-                        expanded: false,
-                        has_error: false,
-                        has_running: false,
-                        cached_description: String::new(),
-                        cached_body: String::new(),
-                        cached_header_expanded: String::new(),
-                        cached_header_collapsed: String::new(),
+                        message_id: message_id.clone(),
+                        part_id: (!id.is_empty()).then(|| id.clone()),
+                        snapshot: snapshot.clone(),
+                        ..DisplayStep::default()
                     });
                 } else if let Part::Tool { tool, state, .. } = p {
                     // Use tool_display for basic info
@@ -249,17 +252,11 @@ impl MessageProcessor {
                         }
                     } else {
                         steps.push(DisplayStep {
-                            reason: String::new(),
-                            cost: 0.,
-                            tokens: None,
+                            message_id: message_id.clone(),
                             details: vec![detail],
-                            expanded: false,
                             has_error,
                             has_running: is_running,
-                            cached_description: String::new(),
-                            cached_body: String::new(),
-                            cached_header_expanded: String::new(),
-                            cached_header_collapsed: String::new(),
+                            ..DisplayStep::default()
                         });
                     }
                 } else if let Some((reason, cost, tokens)) = p.step_finish_info() {
@@ -270,19 +267,22 @@ impl MessageProcessor {
                         last.has_running = false;
                     } else {
                         steps.push(DisplayStep {
+                            message_id: message_id.clone(),
                             reason: reason.to_string(),
                             cost,
                             tokens: tokens.cloned(),
-                            details: Vec::new(),
-                            expanded: false,
-                            has_error: false,
-                            has_running: false,
-                            cached_description: String::new(),
-                            cached_body: String::new(),
-                            cached_header_expanded: String::new(),
-                            cached_header_collapsed: String::new(),
+                            ..DisplayStep::default()
                         });
                     }
+                } else if let Part::Patch { files, .. } = p {
+                    // Patches follow the step-finish of the step that made them.
+                    if let Some(last) = steps.last_mut() {
+                        for file in files {
+                            if !last.files.contains(file) {
+                                last.files.push(file.clone());
+                            }
+                        }
+                    }
                 }
             }
 
@@ -358,6 +358,7 @@ impl MessageProcessor {
                 // Take the latest model/tokens/error from each turn.
                 if model_id.is_some() {
                     pending.model_id = model_id;
+                    pending.variant = variant;
                 }
                 if tokens.is_some() {
                     pending.tokens = tokens;
@@ -379,6 +380,7 @@ impl MessageProcessor {
                     message_id: Some(message_id),
                     timestamp,
                     model_id,
+                    variant,
                     tokens,
                     cost,
                     error_text,
@@ -406,8 +408,13 @@ impl MessageProcessor {
         step.cached_body = Self::format_step_body(step);
         // Optimization: avoid reformatting step headers (including error prefix) in the draw loop
         let prefix = if step.has_error { "! " } else { "" };
-        step.cached_header_expanded = format!("{}▾ {}", prefix, step.cached_description);
-        step.cached_header_collapsed = format!("{}▸ {}", prefix, step.cached_description);
+        let files = match step.files.len() {
+            0 => String::new(),
+            1 => " · 1 file".to_string(),
+            n => format!(" · {} files", n),
+        };
+        step.cached_header_expanded = format!("{}▾ {}{}", prefix, step.cached_description, files);
+        step.cached_header_collapsed = format!("{}▸ {}{}", prefix, step.cached_description, files);
     }
 
     pub fn refresh_message_caches(msg: &mut DisplayMessage) {
@@ -441,9 +448,14 @@ impl MessageProcessor {
             .cost
             .map(crate::utils::formatters::format_cost)
             .unwrap_or_default();
+        msg.cached_model_label = match (&msg.model_id, &msg.variant) {
+            (Some(model), Some(variant)) => format!("{} · {}", model, variant),
+            (Some(model), None) => model.clone(),
+            (None, _) => String::new(),
+        };
 
         // Optimization: Pre-calculate and cache unified diffs once when the message is created/updated.
-        // This avoids expensive LCS/DP computations (O(N*M)) in the draw loop every frame.
+        // This keeps diff computation out of the draw loop.
         if !msg.diffs.is_empty() {
             let total_additions: i64 = msg.diffs.iter().map(|d| d.additions).sum();
             let total_deletions: i64 = msg.diffs.iter().map(|d| d.deletions).sum();
@@ -465,7 +477,8 @@ impl MessageProcessor {
                 );
                 full_diff.push_str(&header);
 
-                let unified = Self::compute_unified_diff(&diff.before, &diff.after, 3);
+                // The diff view expands the lines between hunks from `diff.before` on demand.
+                let unified = Self::compute_unified_diff(&diff.before, &diff.after, FOLD_CONTEXT);
                 full_diff.push_str(&unified);
                 full_diff.push('\n');
             }
@@ -751,176 +764,9 @@ impl MessageProcessor {
     }
 
     /// Compute a unified diff between two strings with the given number of context lines.
-    /// Uses an iterative LCS approach safe for large files. Optimized with a 1D DP table.
+    /// Hunks carry `@@ -a,b +c,d @@` headers; see `diff_model::diff_ops` for the algorithm.
     pub fn compute_unified_diff(before: &str, after: &str, context: usize) -> String {
-        let old_lines: Vec<&str> = before.lines().collect();
-        let new_lines: Vec<&str> = after.lines().collect();
-
-        let diff_ops = Self::compute_diff_ops(&old_lines, &new_lines);
-
-        if diff_ops.is_empty() {
-            return String::from(" (no changes)\n");
-        }
-
-        // Build output with context
-        let mut output = String::new();
-        let mut i = 0;
-        let total = diff_ops.len();
-
-        while i < total {
-            // Find the start of a change hunk
-            if matches!(diff_ops[i], DiffOp::Equal(_)) {
-                i += 1;
-                continue;
-            }
-
-            // Determine context start
-            let context_start = i.saturating_sub(context);
-
-            // Find end of this hunk (including trailing context)
-            let mut hunk_end = i;
-            while hunk_end < total {
-                if matches!(diff_ops[hunk_end], DiffOp::Equal(_)) {
-                    // Count consecutive equals
-                    let eq_start = hunk_end;
-                    while hunk_end < total && matches!(diff_ops[hunk_end], DiffOp::Equal(_)) {
-                        hunk_end += 1;
-                    }
-                    let eq_count = hunk_end - eq_start;
-                    // If gap between changes is larger than 2*context, break
-                    if hunk_end < total && eq_count > context * 2 {
-                        hunk_end = eq_start + context;
-                        break;
-                    }
-                    if hunk_end >= total {
-                        hunk_end = std::cmp::min(eq_start + context, total);
-                        break;
-                    }
-                } else {
-                    hunk_end += 1;
-                }
-            }
-
-            // Print separator if not at the start
-            if context_start > 0 {
-                output.push_str("...\n");
-            }
-
-            for op in diff_ops.iter().take(hunk_end).skip(context_start) {
-                match op {
-                    DiffOp::Equal(line) => {
-                        output.push(' ');
-                        output.push_str(line);
-                        output.push('\n');
-                    }
-                    DiffOp::Delete(line) => {
-                        output.push('-');
-                        output.push_str(line);
-                        output.push('\n');
-                    }
-                    DiffOp::Insert(line) => {
-                        output.push('+');
-                        output.push_str(line);
-                        output.push('\n');
-                    }
-                }
-            }
-
-            i = hunk_end;
-        }
-
-        output
-    }
-
-    /// Compute diff operations using iterative LCS (Myers-like approach via DP table).
-    /// For very large files, we fall back to a simpler line-by-line comparison.
-    fn compute_diff_ops<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffOp<'a>> {
-        let old_len = old.len();
-        let new_len = new.len();
-
-        // For very large files, use a simpler approach to avoid memory issues
-        if old_len * new_len > 4_000_000 {
-            return Self::simple_diff(old, new);
-        }
-
-        // Optimization: Use a 1D vector for the DP table to reduce heap allocations (from O(N) to O(1))
-        // and improve cache locality, resulting in faster diff computation for larger files.
-        let stride = new_len + 1;
-        let mut dp = vec![0u32; (old_len + 1) * stride];
-
-        for i in 1..=old_len {
-            for j in 1..=new_len {
-                if old[i - 1] == new[j - 1] {
-                    dp[i * stride + j] = dp[(i - 1) * stride + (j - 1)] + 1;
-                } else {
-                    dp[i * stride + j] =
-                        std::cmp::max(dp[(i - 1) * stride + j], dp[i * stride + (j - 1)]);
-                }
-            }
-        }
-
-        // Backtrack iteratively to build diff ops
-        let mut ops = Vec::new();
-        let mut i = old_len;
-        let mut j = new_len;
-
-        while i > 0 || j > 0 {
-            if i > 0 && j > 0 && old[i - 1] == new[j - 1] {
-                ops.push(DiffOp::Equal(old[i - 1]));
-                i -= 1;
-                j -= 1;
-            } else if j > 0 && (i == 0 || dp[i * stride + (j - 1)] >= dp[(i - 1) * stride + j]) {
-                ops.push(DiffOp::Insert(new[j - 1]));
-                j -= 1;
-            } else {
-                ops.push(DiffOp::Delete(old[i - 1]));
-                i -= 1;
-            }
-        }
-
-        ops.reverse();
-        ops
-    }
-
-    /// Simple diff for very large files: show all old lines as deleted, all new lines as inserted,
-    /// with common prefix/suffix preserved.
-    fn simple_diff<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffOp<'a>> {
-        let mut ops = Vec::new();
-
-        // Find common prefix
-        let prefix_len = old
-            .iter()
-            .zip(new.iter())
-            .take_while(|(a, b)| a == b)
-            .count();
-
-        // Find common suffix (not overlapping with prefix)
-        let old_remaining = &old[prefix_len..];
-        let new_remaining = &new[prefix_len..];
-        let suffix_len = old_remaining
-            .iter()
-            .rev()
-            .zip(new_remaining.iter().rev())
-            .take_while(|(a, b)| a == b)
-            .count();
-
-        let old_mid = &old[prefix_len..old.len() - suffix_len];
-        let new_mid = &new[prefix_len..new.len() - suffix_len];
-
-        for line in &old[..prefix_len] {
-            ops.push(DiffOp::Equal(line));
-        }
-        for line in old_mid {
-            ops.push(DiffOp::Delete(line));
-        }
-        for line in new_mid {
-            ops.push(DiffOp::Insert(line));
-        }
-        for line in &old[old.len() - suffix_len..] {
-            ops.push(DiffOp::Equal(line));
-        }
-
-        ops
+        crate::diff_model::unified_diff(before, after, context)
     }
 
     pub fn format_step_body(step: &DisplayStep) -> String {
@@ -947,6 +793,14 @@ impl MessageProcessor {
             };
             lines.push(line);
         }
+        if !step.files.is_empty() {
+            let files: Vec<String> = step.files.iter().map(|f| Self::format_path(f)).collect();
+            lines.push(format!("Changed {}", files.join(", ")));
+        }
+        if let Some(snapshot) = &step.snapshot {
+            let short = snapshot.get(..8).unwrap_or(snapshot);
+            lines.push(format!("Checkpoint {}", short));
+        }
         if step.cost > 0.0 || step.tokens.is_some() {
             let mut stats = Vec::new();
             if step.cost > 0.0 {
@@ -971,9 +825,147 @@ impl MessageProcessor {
     }
 }
 
+/// Lines of an edit diff shown on a permission card before the rest is cut.
+pub const PERMISSION_DIFF_MAX_LINES: usize = 40;
+
+/// What a permission card shows besides the permission name and patterns.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum PermissionPreview {
+    #[default]
+    None,
+    /// Shell command(s) the agent wants to run
+    Command(String),
+    /// Unified diff of a pending edit, cut at `PERMISSION_DIFF_MAX_LINES`
+    Diff {
+        path: String,
+        diff: String,
+        hidden_lines: usize,
+    },
+    /// URL the agent wants to fetch
+    Link(String),
+}
+
+impl PermissionPreview {
+    /// Picks the preview from the permission's metadata, falling back to its
+    /// patterns when the server sent no metadata for it.
+    pub fn from_request(
+        permission: &str,
+        patterns: &[String],
+        metadata: &HashMap<String, serde_json::Value>,
+    ) -> Self {
+        let text = |key: &str| {
+            metadata
+                .get(key)
+                .and_then(|value| value.as_str())
+                .filter(|value| !value.trim().is_empty())
+                .map(str::to_string)
+        };
+        match permission {
+            "bash" => text("command")
+                .or_else(|| (!patterns.is_empty()).then(|| patterns.join("\n")))
+                .map_or(Self::None, Self::Command),
+            "edit" | "write" | "patch" => {
+                let Some(diff) = text("diff") else {
+                    return Self::None;
+                };
+                let path = text("filepath")
+                    .or_else(|| text("filePath"))
+                    .or_else(|| patterns.first().cloned())
+                    .unwrap_or_default();
+                let lines: Vec<&str> = diff
+                    .lines()
+                    .skip_while(|line| {
+                        line.starts_with("Index:")
+                            || (!line.is_empty() && line.chars().all(|c| c == '='))
+                    })
+                    .collect();
+                let shown = lines.len().min(PERMISSION_DIFF_MAX_LINES);
+                Self::Diff {
+                    path,
+                    diff: lines[..shown].join("\n"),
+                    hidden_lines: lines.len() - shown,
+                }
+            }
+            "webfetch" => text("url")
+                .or_else(|| patterns.first().cloned())
+                .map_or(Self::None, Self::Link),
+            _ => Self::None,
+        }
+    }
+
+    /// The link to open in the browser on click. Only http(s) URLs qualify;
+    /// the URL comes from the agent, so other schemes are shown but not opened.
+    pub fn openable_url(&self) -> Option<&str> {
+        match self {
+            Self::Link(url) if url.starts_with("https://") || url.starts_with("http://") => {
+                Some(url)
+            }
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::MessageProcessor;
+    use super::{MessageProcessor, PermissionPreview};
+    use openpad_protocol::{AssistantMessage, Message, MessageTime, MessageWithParts, Part};
+    use std::collections::HashMap;
+
+    #[test]
+    fn steps_keep_checkpoint_part_and_patched_files() {
+        let info = Message::Assistant(AssistantMessage {
+            id: "msg_2".to_string(),
+            session_id: "ses_1".to_string(),
+            time: MessageTime {
+                created: 1,
+                completed: Some(2),
+            },
+            error: None,
+            parent_id: "msg_1".to_string(),
+            model_id: "model".to_string(),
+            provider_id: "provider".to_string(),
+            mode: String::new(),
+            agent: String::new(),
+            path: None,
+            summary: None,
+            cost: 0.0,
+            tokens: None,
+            structured: None,
+            variant: Some("high".to_string()),
+            finish: None,
+        });
+        let step_start = |id: &str, snapshot: &str| Part::StepStart {
+            id: id.to_string(),
+            session_id: "ses_1".to_string(),
+            message_id: "msg_2".to_string(),
+            snapshot: Some(snapshot.to_string()),
+        };
+        let patch = |files: &[&str]| Part::Patch {
+            id: "prt_patch".to_string(),
+            session_id: "ses_1".to_string(),
+            message_id: "msg_2".to_string(),
+            hash: "abc".to_string(),
+            files: files.iter().map(|f| f.to_string()).collect(),
+        };
+        let parts = vec![
+            step_start("prt_a", "0123456789abcdef"),
+            patch(&["src/lib.rs", "src/main.rs"]),
+            patch(&["src/lib.rs"]),
+            step_start("prt_b", "fedcba9876543210"),
+        ];
+
+        let display = MessageProcessor::rebuild_from_parts(&[MessageWithParts { info, parts }]);
+        assert_eq!(display[0].cached_model_label, "model · high");
+        let steps = &display[0].steps;
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].message_id, "msg_2");
+        assert_eq!(steps[0].part_id.as_deref(), Some("prt_a"));
+        assert_eq!(steps[0].files, vec!["src/lib.rs", "src/main.rs"]);
+        assert!(steps[0].cached_header_collapsed.ends_with(" · 2 files"));
+        assert!(steps[0].cached_body.contains("Checkpoint 01234567"));
+        assert_eq!(steps[1].part_id.as_deref(), Some("prt_b"));
+        assert!(steps[1].files.is_empty());
+    }
 
     #[test]
     fn needs_markdown_detects_code_blocks() {
@@ -1002,4 +994,54 @@ mod tests {
             "hello world, no markdown here"
         ));
     }
+
+    #[test]
+    fn permission_preview_reads_metadata_then_patterns() {
+        let mut metadata = HashMap::new();
+        assert_eq!(
+            PermissionPreview::from_request("bash", &["ls -la".to_string()], &metadata),
+            PermissionPreview::Command("ls -la".to_string())
+        );
+        assert_eq!(
+            PermissionPreview::from_request("read", &["*".to_string()], &metadata),
+            PermissionPreview::None
+        );
+
+        metadata.insert(
+            "url".to_string(),
+            serde_json::json!("https://example.com/docs"),
+        );
+        let link = PermissionPreview::from_request("webfetch", &[], &metadata);
+        assert_eq!(
+            link,
+            PermissionPreview::Link("https://example.com/docs".to_string())
+        );
+        assert_eq!(link.openable_url(), Some("https://example.com/docs"));
+        assert_eq!(
+            PermissionPreview::Link("file:///etc/passwd".to_string()).openable_url(),
+            None
+        );
+
+        let body: String = (0..50).map(|i| format!("+line {}\n", i)).collect();
+        metadata.insert("filepath".to_string(), serde_json::json!("src/main.rs"));
+        metadata.insert(
+            "diff".to_string(),
+            serde_json::json!(format!(
+                "Index: src/main.rs\n===\n--- src/main.rs\n+++ src/main.rs\n{}",
+                body
+            )),
+        );
+        let PermissionPreview::Diff {
+            path,
+            diff,
+            hidden_lines,
+        } = PermissionPreview::from_request("edit", &[], &metadata)
+        else {
+            panic!("edit permission should preview its diff");
+        };
+        assert_eq!(path, "src/main.rs");
+        assert!(diff.starts_with("--- src/main.rs"));
+        assert_eq!(diff.lines().count(), super::PERMISSION_DIFF_MAX_LINES);
+        assert_eq!(hidden_lines, 12);
+    }
 }
@@ -1,3 +1,5 @@
+use crate::colored_diff_text::{ColoredDiffTextApi, ColoredDiffTextWidgetExt};
+use crate::message_logic::PermissionPreview;
 use makepad_widgets::*;
 
 script_mod! {
@@ -59,6 +61,91 @@ script_mod! {
             }
         }
 
+        always_label := Label {
+            width: Fill
+            height: Fit
+            visible: false
+            text: ""
+            draw_text +: {
+                color: #9ca3af
+                text_style: theme.font_regular {font_size: 10}
+            }
+        }
+
+        command_block := RoundedView {
+            width: Fill
+            height: Fit
+            visible: false
+            padding: Inset{left: 10 right: 10 top: 8 bottom: 8}
+            show_bg: true
+            draw_bg +: {
+                color: #15181d
+                border_radius: 6.0
+            }
+
+            command_label := Label {
+                width: Fill
+                height: Fit
+                text: ""
+                draw_text +: {
+                    color: #e6e9ee
+                    text_style: theme.font_code {font_size: 10}
+                }
+            }
+        }
+
+        diff_block := View {
+            width: Fill
+            height: Fit
+            visible: false
+            flow: Down
+            spacing: 4
+
+            diff_path_label := Label {
+                width: Fill
+                height: Fit
+                text: ""
+                draw_text +: {
+                    color: #aab3bd
+                    text_style: theme.font_code {font_size: 10}
+                }
+            }
+
+            diff_text := mod.widgets.ColoredDiffText {
+                width: Fill
+                height: Fit
+            }
+
+            diff_more_label := Label {
+                width: Fill
+                height: Fit
+                visible: false
+                text: ""
+                draw_text +: {
+                    color: #6b7b8c
+                    text_style: theme.font_regular {font_size: 10}
+                }
+            }
+        }
+
+        link_button := Button {
+            width: Fill
+            height: Fit
+            visible: false
+            padding: 0
+            align: Align{x: 0.0, y: 0.5}
+            text: ""
+            draw_bg +: {
+                color: #0000
+                color_hover: #60a5fa22
+                border_size: 0.0
+            }
+            draw_text +: {
+                color: #60a5fa
+                text_style: theme.font_code {font_size: 10}
+            }
+        }
+
         buttons_row := View {
             width: Fill
             height: Fit
@@ -160,6 +247,9 @@ pub struct PermissionCard {
     request_id: String,
     #[rust]
     resolved: bool,
+    /// http(s) URL shown by `link_button`, opened in the browser on click
+    #[rust]
+    link_url: String,
 }
 
 impl Widget for PermissionCard {
@@ -168,6 +258,11 @@ impl Widget for PermissionCard {
             self.view.handle_event(cx, event, scope);
         });
 
+        if self.view.button(cx, &[id!(link_button)]).clicked(&actions) && !self.link_url.is_empty()
+        {
+            cx.open_url(&self.link_url, OpenUrlInPlace::No);
+        }
+
         if self.resolved {
             return;
         }
@@ -211,6 +306,51 @@ impl Widget for PermissionCard {
 }
 
 impl PermissionCard {
+    fn set_preview(&mut self, cx: &mut Cx, preview: &PermissionPreview) {
+        self.link_url = preview.openable_url().unwrap_or_default().to_string();
+        self.view
+            .view(cx, &[id!(command_block)])
+            .set_visible(cx, matches!(preview, PermissionPreview::Command(_)));
+        self.view
+            .view(cx, &[id!(diff_block)])
+            .set_visible(cx, matches!(preview, PermissionPreview::Diff { .. }));
+        self.view
+            .widget(cx, &[id!(link_button)])
+            .set_visible(cx, matches!(preview, PermissionPreview::Link(_)));
+
+        match preview {
+            PermissionPreview::None => {}
+            PermissionPreview::Command(command) => {
+                self.view
+                    .label(cx, &[id!(command_label)])
+                    .set_text(cx, command);
+            }
+            PermissionPreview::Diff {
+                path,
+                diff,
+                hidden_lines,
+            } => {
+                self.view
+                    .label(cx, &[id!(diff_path_label)])
+                    .set_text(cx, path);
+                self.view
+                    .colored_diff_text(cx, &[id!(diff_text)])
+                    .set_diff_text(cx, diff);
+                self.view
+                    .widget(cx, &[id!(diff_more_label)])
+                    .set_visible(cx, *hidden_lines > 0);
+                self.view
+                    .label(cx, &[id!(diff_more_label)])
+                    .set_text(cx, &format!("… {} more lines", hidden_lines));
+            }
+            PermissionPreview::Link(url) => {
+                self.view
+                    .button(cx, &[id!(link_button)])
+                    .set_text(cx, &format!("↗ {}", url));
+            }
+        }
+    }
+
     fn mark_resolved_inner(&mut self, cx: &mut Cx, status: &str) {
         self.resolved = true;
         self.view
@@ -234,6 +374,8 @@ pub trait PermissionCardApi {
         request_id: String,
         permission: &str,
         patterns: &[String],
+        always: &[String],
+        preview: &PermissionPreview,
     );
     fn mark_resolved(&self, cx: &mut Cx, status: &str);
 }
@@ -246,6 +388,8 @@ impl PermissionCardApi for PermissionCardRef {
         request_id: String,
         permission: &str,
         patterns: &[String],
+        always: &[String],
+        preview: &PermissionPreview,
     ) {
         if let Some(mut inner) = self.borrow_mut() {
             inner.session_id = session_id;
@@ -267,6 +411,19 @@ impl PermissionCardApi for PermissionCardRef {
                 .label(cx, &[id!(patterns_label)])
                 .set_text(cx, &patterns_text);
 
+            // "Always" allows these patterns for the rest of the session, which
+            // can be broader than what this one request asks for.
+            inner
+                .view
+                .widget(cx, &[id!(always_label)])
+                .set_visible(cx, !always.is_empty());
+            inner.view.label(cx, &[id!(always_label)]).set_text(
+                cx,
+                &format!("\"Always\" will also allow: {}", always.join(", ")),
+            );
+
+            inner.set_preview(cx, preview);
+
             inner
                 .view
                 .view(cx, &[id!(buttons_row)])
@@ -0,0 +1,84 @@
+//! Per-user UI preferences that survive restarts, stored as `key=value` lines
+//! in `$XDG_CONFIG_HOME/openpad/preferences` (`~/.config/openpad/preferences`).
+
+use std::io;
+use std::path::PathBuf;
+
+/// Path of `file_name` inside the openpad config directory, e.g.
+/// `~/.config/openpad/<file_name>`.
+pub fn config_path(file_name: &str) -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("openpad").join(file_name))
+}
+
+fn preferences_path() -> Option<PathBuf> {
+    config_path("preferences")
+}
+
+fn parse_entries(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .filter(|(key, _)| !key.is_empty())
+        .collect()
+}
+
+fn render_entries(entries: &[(String, String)]) -> String {
+    entries
+        .iter()
+        .map(|(key, value)| format!("{}={}\n", key, value))
+        .collect()
+}
+
+fn load_entries() -> Vec<(String, String)> {
+    preferences_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|text| parse_entries(&text))
+        .unwrap_or_default()
+}
+
+/// The stored value for `key`, if any.
+pub fn get(key: &str) -> Option<String> {
+    load_entries()
+        .into_iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, value)| value)
+}
+
+/// Stores `value` under `key`, keeping every other entry.
+pub fn set(key: &str, value: &str) -> io::Result<()> {
+    let path = preferences_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+    let mut entries = load_entries();
+    let value = value.replace('\n', " ");
+    match entries.iter_mut().find(|(k, _)| k == key) {
+        Some(entry) => entry.1 = value,
+        None => entries.push((key.to_string(), value)),
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, render_entries(&entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_round_trip_and_skip_comments() {
+        let entries = parse_entries("# comment\ndiff_mode = split\nbroken line\nmodel=a=b\n");
+        assert_eq!(
+            entries,
+            vec![
+                ("diff_mode".to_string(), "split".to_string()),
+                ("model".to_string(), "a=b".to_string()),
+            ]
+        );
+        assert_eq!(parse_entries(&render_entries(&entries)), entries);
+    }
+}